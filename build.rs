@@ -0,0 +1,95 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[path = "src/utils/properties.rs"]
+mod properties;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/utils/properties.rs");
+    compile_messages();
+    let languages = selected_languages();
+    write_selected_languages(languages.as_deref());
+    if env::var_os("CARGO_FEATURE_EMBEDDED_PROFILES").is_some() {
+        embed_profiles(languages.as_deref());
+    }
+}
+
+/// Collects the languages selected with `lang-*` features, or None if no such feature is enabled.
+fn selected_languages() -> Option<Vec<String>> {
+    let mut languages: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_LANG_").map(|code| code.to_lowercase().replace('_', "-")))
+        .collect();
+    languages.sort();
+    if languages.is_empty() { None } else { Some(languages) }
+}
+
+/// Generates `languages.rs` with the languages of the built-in profile set.
+///
+/// A single selected language fails the build, as the factory needs at least two profiles.
+fn write_selected_languages(languages: Option<&[String]>) {
+    let mut code = match languages {
+        Some(languages) => format!("pub static SELECTED_LANGUAGES: Option<&[&str]> = Some(&{:?});\n", languages),
+        None => "pub static SELECTED_LANGUAGES: Option<&[&str]> = None;\n".to_string(),
+    };
+    if let Some([language]) = languages {
+        code.push_str(&format!(
+            "compile_error!({:?});\n",
+            format!("the lang-* features select only `{}`, but at least two built-in languages are needed; enable another lang-* feature", language)
+        ));
+    }
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("languages.rs");
+    fs::write(out, code).expect("failed to write languages.rs");
+}
+
+/// Generates `messages.rs` with the entries of `messages.properties` as a sorted static table.
+fn compile_messages() {
+    let path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("src/utils/messages.properties");
+    println!("cargo:rerun-if-changed={}", path.display());
+    let content = fs::read_to_string(&path).expect("failed to read messages.properties");
+    let mut messages: Vec<(String, String)> = properties::parse_properties(&content).into_iter().collect();
+    messages.sort();
+    let mut code = String::from("static MESSAGES: &[(&str, &str)] = &[\n");
+    for (key, value) in messages {
+        code.push_str(&format!("    ({:?}, {:?}),\n", key, value));
+    }
+    code.push_str("];\n");
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("messages.rs");
+    fs::write(out, code).expect("failed to write messages.rs");
+}
+
+/// Generates `embedded_profiles.rs` with the built-in profiles included via `include_str!`.
+fn embed_profiles(languages: Option<&[String]>) {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let dir = manifest_dir.join(if env::var_os("CARGO_FEATURE_SMALL_PROFILES").is_some() { "profiles_small" } else { "profiles" });
+    println!("cargo:rerun-if-changed={}", dir.display());
+    // Built-in languages can be narrowed down with `lang-*` features
+    let mut entries: Vec<PathBuf> = profile_files(&dir)
+        .into_iter()
+        .filter(|path| languages.is_none_or(|languages| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            languages.iter().any(|lang| *lang == name)
+        }))
+        .collect();
+    entries.sort();
+    let mut code = String::from("/// Built-in language profiles as (file name, JSON content) pairs.\npub static EMBEDDED_PROFILES: &[(&str, &str)] = &[\n");
+    for path in entries {
+        let name = path.file_name().unwrap().to_string_lossy();
+        code.push_str(&format!("    ({:?}, include_str!({:?})),\n", name, path.display().to_string()));
+    }
+    code.push_str("];\n");
+    let out = PathBuf::from(env::var("OUT_DIR").unwrap()).join("embedded_profiles.rs");
+    fs::write(out, code).expect("failed to write embedded_profiles.rs");
+}
+
+/// Lists the profile files of a directory, or nothing if it does not exist.
+fn profile_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect()
+}
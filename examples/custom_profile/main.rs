@@ -92,7 +92,7 @@ fn main() {
         Ok(probs) => {
             println!("\tFrench text probabilities:");
             for lang in probs {
-                println!("\t\t{}: {:.3}", lang.lang.unwrap_or_default(), lang.prob);
+                println!("\t\t{}: {:.3}", lang.lang.as_deref().unwrap_or_default(), lang.prob);
             }
         }
         Err(e) => println!("Probability error: {:?}", e),
@@ -39,26 +39,12 @@ fn main() {
     // Create an EMPTY factory
     let mut factory = DetectorFactory::new().build();
 
-    // Get number of profiles to set final size
-    let mut profile_count = lang_profiles.len();
-    // Since we know that we are going to add another profile which is not in the default set
-    // we increase the final size by 1
-    profile_count += 1;
-
-    println!("Final size (assuming we are going to extend default set) of languages array will be: {}", profile_count);
-    
-    for (i, profile) in lang_profiles.into_iter().enumerate() {
-        let profile_name = if let Some(name) = &profile.name {
-            name.clone()
-        } else {
-            "unknown".to_string()
-        };
-        println!("\tAdding profile: {} at index {}", profile_name, i);
-        if let Err(e) = factory.add_profile(profile, i, profile_count) {
-            println!("Error adding {} profile: {:?}", profile_name, e);
-            return;
-        }
+    // Profiles are appended one after another, no need to know the final number of languages
+    if let Err(e) = factory.add_profiles(lang_profiles) {
+        println!("Error adding default profiles: {:?}", e);
+        return;
     }
+    println!("\tAdded {} profiles", factory.get_lang_list().len());
 
     // Load another profile (in documentation for generating profiles it is Sakha (Yakut) language - "sah")
     let sah_path = Path::new("./scripts/datasets/generated").join("sah_generated.json");
@@ -78,7 +64,7 @@ fn main() {
         }
     };
     println!("Adding Sakha language profile to the factory");
-    if let Err(e) = factory.add_profile(sah_profile, profile_count - 1, profile_count) {
+    if let Err(e) = factory.push_profile(sah_profile) {
         println!("Error adding Sakha profile: {:?}", e);
         return;
     }
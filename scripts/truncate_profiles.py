@@ -0,0 +1,39 @@
+# Generates the compact profile set used by the `small-profiles` cargo feature.
+#
+# Every profile keeps only its most frequent n-grams. `n_words` totals are kept
+# unchanged so the probabilities of the remaining n-grams stay the same as in the
+# full profiles.
+
+import json
+import os
+
+# Source directory with full language profiles
+input_dir = "../profiles"
+
+# Output directory for truncated profiles
+output_dir = "../profiles_small"
+
+# Maximum number of n-grams to keep per language
+MAX_NGRAMS = 2000
+
+def truncate_profile(profile: dict, max_ngrams: int) -> dict:
+    # Sort by count descending, ties broken by n-gram to keep output stable
+    top = sorted(profile["freq"].items(), key=lambda kv: (-kv[1], kv[0]))[:max_ngrams]
+    return {
+        "freq": dict(top),
+        "n_words": profile["n_words"],
+        "name": profile["name"]
+    }
+
+if __name__ == "__main__":
+    os.makedirs(output_dir, exist_ok=True)
+    for filename in sorted(os.listdir(input_dir)):
+        path = os.path.join(input_dir, filename)
+        if not os.path.isfile(path):
+            continue
+        with open(path, "r", encoding="utf-8") as f:
+            profile = json.load(f)
+        truncated = truncate_profile(profile, MAX_NGRAMS)
+        with open(os.path.join(output_dir, filename), "w", encoding="utf-8") as f:
+            json.dump(truncated, f, ensure_ascii=False, separators=(",", ":"))
+        print(f"{filename}: {len(profile['freq'])} -> {len(truncated['freq'])} n-grams")
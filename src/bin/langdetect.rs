@@ -0,0 +1,538 @@
+//! Command line tool for langdetect-rs.
+//!
+//! Build with `cargo build --features cli`.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Instant;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use langdetect_rs::corpus::CorpusSplitter;
+use langdetect_rs::detector::DetectorError;
+use langdetect_rs::detector_factory::DetectorFactory;
+use langdetect_rs::eval::LatencyStats;
+use langdetect_rs::language::Language;
+use langdetect_rs::profile_data::ProfileData;
+use langdetect_rs::trainer::LangProfileTrainer;
+use langdetect_rs::utils::lang_profile::{LangProfile, LangProfileJson};
+use langdetect_rs::utils::ngram::Normalization;
+use langdetect_rs::utils::script::scripts_by_share;
+
+#[derive(Parser)]
+#[command(name = "langdetect", version, about = "Language detection tools")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Detect the language of texts read from files or stdin
+    Detect {
+        /// Files to read; stdin is read if none is given
+        files: Vec<PathBuf>,
+        /// Comma-separated language codes to restrict detection to
+        #[arg(long, value_delimiter = ',')]
+        languages: Vec<String>,
+        /// Randomization seed for reproducible results
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Smoothing parameter of the detector
+        #[arg(long)]
+        alpha: Option<f64>,
+        /// Treat every non-empty line as a separate document
+        #[arg(long)]
+        batch: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        output: OutputFormat,
+        /// Read stdin line by line and print a JSON result per line as soon as it is detected,
+        /// followed by a throughput summary on stderr
+        #[arg(long, conflicts_with_all = ["files", "batch", "output"])]
+        stream: bool,
+    },
+    /// Split a corpus (plain or gzip-compressed, one document per line) into a file per detected language
+    Split {
+        /// Corpus to read; stdin is read if omitted
+        input: Option<PathBuf>,
+        /// Output directory, receiving `<lang>.txt` for every language and `unknown.txt`
+        #[arg(long = "out")]
+        output: PathBuf,
+        /// Minimum probability of the detected language; less confident lines go to `unknown.txt`
+        #[arg(long, default_value_t = CorpusSplitter::MIN_CONFIDENCE)]
+        min_confidence: f64,
+        /// Drop less confident lines instead of writing them to `unknown.txt`
+        #[arg(long)]
+        drop_unknown: bool,
+        /// Comma-separated language codes to restrict detection to
+        #[arg(long, value_delimiter = ',')]
+        languages: Vec<String>,
+        /// Randomization seed for reproducible results
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Smoothing parameter of the detector
+        #[arg(long)]
+        alpha: Option<f64>,
+    },
+    /// Train a JSON language profile from a directory of plain-text files
+    Train {
+        /// Language code of the trained profile
+        #[arg(long)]
+        lang: String,
+        /// Directory with the training corpus, one document per file
+        #[arg(long = "in")]
+        input: PathBuf,
+        /// Output JSON profile
+        #[arg(long = "out")]
+        output: PathBuf,
+        #[command(flatten)]
+        options: TrainOptions,
+    },
+    /// Generate a JSON language profile from a Wikipedia abstract dump (plain or gzip-compressed XML)
+    GenProfile {
+        /// Language code of the generated profile
+        #[arg(long)]
+        lang: String,
+        /// Abstract dump, e.g. kkwiki-latest-abstract.xml.gz
+        #[arg(long = "in")]
+        input: PathBuf,
+        /// Output JSON profile
+        #[arg(long = "out")]
+        output: PathBuf,
+        #[command(flatten)]
+        options: TrainOptions,
+    },
+    /// Print statistics and validation warnings of JSON language profiles
+    ProfileInfo {
+        /// Path to the JSON profile (or a directory, gzip file or zip archive of profiles)
+        path: PathBuf,
+        /// Number of most frequent n-grams to show per order
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// Convert JSON profiles (a file, a directory, a .gz file or a .zip archive) to another format
+    Convert {
+        /// Input profile file, directory, gzip file or zip archive
+        #[arg(long = "in")]
+        input: PathBuf,
+        /// Output file
+        #[arg(long = "out")]
+        output: PathBuf,
+        /// Output format; guessed from the output extension if omitted
+        #[arg(long, value_enum)]
+        format: Option<Format>,
+    },
+}
+
+/// Training settings shared by `train` and `gen-profile`.
+#[derive(Args)]
+struct TrainOptions {
+    /// Skip near-duplicate sentences
+    #[arg(long)]
+    dedup: bool,
+    /// Maximum number of characters a single document may contribute
+    #[arg(long)]
+    max_chars: Option<usize>,
+    /// N-grams occurring at most (unigram total / ratio) times are omitted
+    #[arg(long, default_value_t = LangProfile::LESS_FREQ_RATIO)]
+    less_freq_ratio: usize,
+    /// N-grams occurring at most this many times are omitted
+    #[arg(long, default_value_t = LangProfile::MINIMUM_FREQ)]
+    min_freq: usize,
+    /// Character normalization of n-gram extraction; detect with the same one
+    #[arg(long, value_enum, default_value_t = NormalizationArg::Strict)]
+    normalization: NormalizationArg,
+}
+
+impl TrainOptions {
+    /// Creates a trainer with these settings.
+    fn trainer(&self, lang: &str) -> LangProfileTrainer {
+        LangProfileTrainer::new(lang)
+            .with_deduplication(self.dedup)
+            .with_max_chars_per_document(self.max_chars)
+            .with_omit_thresholds(self.less_freq_ratio, self.min_freq)
+            .with_normalization(self.normalization.into())
+            .build()
+    }
+}
+
+/// Character normalization rules, see `Normalization`.
+#[derive(Clone, Copy, ValueEnum)]
+enum NormalizationArg {
+    /// Digits, apostrophes and hyphens end a word, as in the Java library
+    Strict,
+    /// Digits next to letters and apostrophes and hyphens inside words are kept
+    Lenient,
+}
+
+impl From<NormalizationArg> for Normalization {
+    fn from(arg: NormalizationArg) -> Normalization {
+        match arg {
+            NormalizationArg::Strict => Normalization::Strict,
+            NormalizationArg::Lenient => Normalization::Lenient,
+        }
+    }
+}
+
+/// Output formats of detection results.
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum OutputFormat {
+    /// `source: lang:prob lang:prob ...`, one document per line
+    Text,
+    /// One JSON object per document and line
+    Json,
+}
+
+/// Profile storage formats.
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// Plain JSON profile (single profile)
+    Json,
+    /// Compact binary format of `DetectorFactory::save_binary`
+    #[value(alias = "binary")]
+    Bincode,
+    /// Gzip-compressed JSON profile (single profile)
+    Gzip,
+    /// Zip archive with one JSON profile per language
+    Zip,
+}
+
+impl Format {
+    /// Guesses the format from a file extension.
+    fn from_path(path: &Path) -> Option<Format> {
+        match path.extension()?.to_str()? {
+            "json" => Some(Format::Json),
+            "bin" => Some(Format::Bincode),
+            "gz" => Some(Format::Gzip),
+            "zip" => Some(Format::Zip),
+            _ => None,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Detect { languages, seed, alpha, stream: true, .. } => detect_stream(&languages, seed, alpha),
+        Command::Detect { files, languages, seed, alpha, batch, output, .. } => detect(&files, &languages, seed, alpha, batch, output),
+        Command::Split { input, output, min_confidence, drop_unknown, languages, seed, alpha } => {
+            split(input.as_deref(), &output, min_confidence, drop_unknown, &languages, seed, alpha)
+        }
+        Command::Train { lang, input, output, options } => train(&lang, &input, &output, &options),
+        Command::GenProfile { lang, input, output, options } => gen_profile(&lang, &input, &output, &options),
+        Command::ProfileInfo { path, top } => profile_info(&path, top),
+        Command::Convert { input, output, format } => convert(&input, &output, format),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Detects the language of every document and prints the results.
+fn detect(files: &[PathBuf], languages: &[String], seed: Option<u64>, alpha: Option<f64>, batch: bool, output: OutputFormat) -> Result<(), String> {
+    let factory = build_factory(languages, seed)?;
+    let inputs = if files.is_empty() {
+        let mut text = String::new();
+        std::io::stdin().read_to_string(&mut text).map_err(|e| e.to_string())?;
+        vec![("-".to_string(), text)]
+    } else {
+        files.iter()
+            .map(|path| fs::read_to_string(path).map(|text| (path.display().to_string(), text)).map_err(|e| format!("{:?}: {}", path, e)))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    let mut documents: Vec<(String, &str)> = Vec::new();
+    for (source, text) in inputs.iter() {
+        if batch {
+            documents.extend(text.lines().enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| (format!("{}:{}", source, i + 1), line)));
+        } else {
+            documents.push((source.clone(), text.as_str()));
+        }
+    }
+    let texts: Vec<&str> = documents.iter().map(|(_, text)| *text).collect();
+    let results = factory.get_probabilities_batch(&texts, alpha);
+    let mut stdout = std::io::stdout().lock();
+    for ((source, _), result) in documents.iter().zip(results) {
+        writeln!(stdout, "{}", format_result(source, &result, output)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Detects every line of stdin as it arrives and prints NDJSON results, then a throughput summary.
+fn detect_stream(languages: &[String], seed: Option<u64>, alpha: Option<f64>) -> Result<(), String> {
+    let factory = build_factory(languages, seed)?;
+    let start = Instant::now();
+    let mut durations = Vec::new();
+    let mut stdout = std::io::stdout().lock();
+    for (i, line) in std::io::stdin().lock().lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let detect_start = Instant::now();
+        let result = factory.get_probabilities(&line, alpha);
+        durations.push(detect_start.elapsed());
+        writeln!(stdout, "{}", format_result(&format!("-:{}", i + 1), &result, OutputFormat::Json)).map_err(|e| e.to_string())?;
+        stdout.flush().map_err(|e| e.to_string())?;
+    }
+    let elapsed = start.elapsed();
+    let stats = LatencyStats::from_durations(durations);
+    let lines_per_second = if elapsed.as_secs_f64() > 0.0 { stats.count as f64 / elapsed.as_secs_f64() } else { 0.0 };
+    eprintln!(
+        "{} lines in {:?} ({:.1} lines/s), detection p50={:?}, p90={:?}, p99={:?}, max={:?}",
+        stats.count, elapsed, lines_per_second, stats.p50, stats.p90, stats.p99, stats.max
+    );
+    Ok(())
+}
+
+/// Splits a corpus into a file per detected language and prints the line counts.
+fn split(input: Option<&Path>, output: &Path, min_confidence: f64, drop_unknown: bool, languages: &[String], seed: Option<u64>, alpha: Option<f64>) -> Result<(), String> {
+    let factory = build_factory(languages, seed)?;
+    let splitter = CorpusSplitter::new(&factory)
+        .with_min_confidence(min_confidence)
+        .with_unknown_label((!drop_unknown).then_some(CorpusSplitter::UNKNOWN_LABEL))
+        .with_alpha(alpha)
+        .build();
+    let stats = match input {
+        Some(path) => {
+            let mut file = BufReader::new(fs::File::open(path).map_err(|e| format!("{:?}: {}", path, e))?);
+            let gzip = file.fill_buf().map_err(|e| e.to_string())?.starts_with(&[0x1f, 0x8b]);
+            if gzip {
+                splitter.split_to_directory(BufReader::new(GzDecoder::new(file)), output)
+            } else {
+                splitter.split_to_directory(file, output)
+            }.map_err(|e| format!("{:?}: {}", path, e))?
+        }
+        None => splitter.split_to_directory(std::io::stdin().lock(), output).map_err(|e| e.to_string())?,
+    };
+    for (lang, lines) in stats.per_language.iter() {
+        println!("{}: {}", lang, lines);
+    }
+    println!("{} line(s), {} without a language of confidence {} or more, written to {:?}", stats.lines, stats.rejected, min_confidence, output);
+    Ok(())
+}
+
+/// Creates the factory of the built-in profiles, restricted to the given languages if any.
+fn build_factory(languages: &[String], seed: Option<u64>) -> Result<DetectorFactory, String> {
+    // try_default() copies the same cached factory, so the profiles are loaded once
+    let shared = DetectorFactory::try_default_shared().map_err(|e| e.to_string())?;
+    let mut builder = DetectorFactory::try_default().map_err(|e| e.to_string())?.with_seed(seed);
+    if !languages.is_empty() {
+        let available = shared.get_lang_list();
+        if let Some(unknown) = languages.iter().find(|lang| !available.iter().any(|l| l == *lang)) {
+            return Err(format!("Unknown language {:?}, available: {}", unknown, available.join(",")));
+        }
+        builder = builder.with_allowed_languages(languages);
+    }
+    Ok(builder.build())
+}
+
+/// Formats the detection result of a document.
+fn format_result(source: &str, result: &Result<Vec<Language>, DetectorError>, output: OutputFormat) -> String {
+    match (output, result) {
+        (OutputFormat::Text, Ok(probabilities)) => {
+            let probs: Vec<String> = probabilities.iter()
+                .map(|l| format!("{}:{:.4}", l.lang.as_deref().unwrap_or("unknown"), l.prob))
+                .collect();
+            format!("{}: {}", source, probs.join(" "))
+        }
+        (OutputFormat::Text, Err(e)) => format!("{}: error: {}", source, e),
+        (OutputFormat::Json, Ok(probabilities)) => serde_json::json!({
+            "source": source,
+            "lang": probabilities.first().and_then(|l| l.lang.as_deref()),
+            "probabilities": probabilities,
+        }).to_string(),
+        (OutputFormat::Json, Err(e)) => serde_json::json!({ "source": source, "error": e.to_string() }).to_string(),
+    }
+}
+
+/// Trains a profile from a corpus directory and writes it as JSON.
+fn train(lang: &str, input: &Path, output: &Path, options: &TrainOptions) -> Result<(), String> {
+    let mut trainer = options.trainer(lang);
+    let files = trainer.add_directory(input).map_err(|e| format!("{:?}: {}", input, e))?;
+    if files == 0 {
+        return Err(format!("No files found in {:?}", input));
+    }
+    write_trained_profile(trainer, lang, output, &format!("{} file(s)", files))
+}
+
+/// Generates a profile from a Wikipedia abstract dump and writes it as JSON.
+fn gen_profile(lang: &str, input: &Path, output: &Path, options: &TrainOptions) -> Result<(), String> {
+    let mut file = BufReader::new(fs::File::open(input).map_err(|e| format!("{:?}: {}", input, e))?);
+    let gzip = file.fill_buf().map_err(|e| e.to_string())?.starts_with(&[0x1f, 0x8b]);
+    let mut trainer = options.trainer(lang);
+    let abstracts = if gzip {
+        trainer.add_wiki_abstracts(BufReader::new(GzDecoder::new(file)))
+    } else {
+        trainer.add_wiki_abstracts(file)
+    }.map_err(|e| format!("{:?}: {}", input, e))?;
+    if abstracts == 0 {
+        return Err(format!("No abstracts found in {:?}", input));
+    }
+    write_trained_profile(trainer, lang, output, &format!("{} abstract(s)", abstracts))
+}
+
+/// Finishes training and writes the profile as JSON.
+fn write_trained_profile(trainer: LangProfileTrainer, lang: &str, output: &Path, source: &str) -> Result<(), String> {
+    let stats = trainer.stats().clone();
+    let profile = trainer.finish();
+    profile.to_json_file(output).map_err(|e| format!("{:?}", e))?;
+    println!(
+        "Trained {:?} from {}, {} sentence(s) ({} duplicates skipped): {} n-grams written to {:?}",
+        lang, source, stats.sentences, stats.duplicate_sentences, profile.freq.len(), output
+    );
+    Ok(())
+}
+
+/// Prints name, n-gram counts per order, top n-grams and validation warnings of profiles.
+fn profile_info(path: &Path, top: usize) -> Result<(), String> {
+    for (_, json) in read_profiles(path)? {
+        let profile: LangProfileJson = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        print_profile_info(&profile, top);
+    }
+    Ok(())
+}
+
+/// Prints the statistics of a single profile.
+fn print_profile_info(profile: &LangProfileJson, top: usize) {
+    println!("Language: {}", profile.name);
+    let scripts = profile.scripts.clone().unwrap_or_else(|| {
+        let unigrams = profile.freq.iter().filter(|(gram, _)| gram.chars().count() == 1)
+            .filter_map(|(gram, &count)| gram.chars().next().map(|ch| (ch, count as f64)));
+        scripts_by_share(unigrams, ProfileData::SCRIPT_MIN_SHARE)
+    });
+    let names: Vec<&str> = scripts.iter().map(|script| script.name()).collect();
+    println!("Scripts: {}{}", names.join(", "), if profile.scripts.is_some() { " (declared)" } else { "" });
+    let mut by_order: Vec<Vec<(&str, usize)>> = vec![Vec::new(); 3];
+    for (gram, &count) in profile.freq.iter() {
+        let length = gram.chars().count();
+        if (1..=3).contains(&length) {
+            by_order[length - 1].push((gram.as_str(), count));
+        }
+    }
+    for (i, grams) in by_order.iter_mut().enumerate() {
+        let total = profile.n_words.get(i).copied().unwrap_or(0);
+        println!("{}-grams: {} distinct, {} total", i + 1, grams.len(), total);
+        grams.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let shown: Vec<String> = grams.iter().take(top).map(|(gram, count)| format!("{:?}={}", gram, count)).collect();
+        if !shown.is_empty() {
+            println!("  top: {}", shown.join(", "));
+        }
+    }
+    let warnings = profile.validate();
+    if warnings.is_empty() {
+        println!("No warnings");
+    } else {
+        println!("Warnings:");
+        for warning in warnings {
+            println!("  - {}", warning);
+        }
+    }
+}
+
+/// Converts JSON profiles to the requested format.
+fn convert(input: &Path, output: &Path, format: Option<Format>) -> Result<(), String> {
+    let format = format.or_else(|| Format::from_path(output))
+        .ok_or_else(|| format!("Cannot guess the output format of {:?}, use --format", output))?;
+    let profiles = read_profiles(input)?;
+    if profiles.is_empty() {
+        return Err(format!("No profiles found in {:?}", input));
+    }
+    if matches!(format, Format::Json | Format::Gzip) && profiles.len() > 1 {
+        return Err(format!("{} profiles found, but json and gzip outputs hold a single profile; use zip or bincode", profiles.len()));
+    }
+    match format {
+        Format::Json => fs::write(output, &profiles[0].1).map_err(|e| e.to_string())?,
+        Format::Gzip => {
+            let file = fs::File::create(output).map_err(|e| e.to_string())?;
+            let mut encoder = GzEncoder::new(file, Compression::best());
+            encoder.write_all(profiles[0].1.as_bytes()).map_err(|e| e.to_string())?;
+            encoder.finish().map_err(|e| e.to_string())?;
+        }
+        Format::Zip => {
+            let file = fs::File::create(output).map_err(|e| e.to_string())?;
+            let mut archive = zip::ZipWriter::new(file);
+            let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+            for (name, json) in profiles.iter() {
+                archive.start_file(name.as_str(), options).map_err(|e| e.to_string())?;
+                archive.write_all(json.as_bytes()).map_err(|e| e.to_string())?;
+            }
+            archive.finish().map_err(|e| e.to_string())?;
+        }
+        Format::Bincode => {
+            // Profiles are added one by one, so a single profile can be converted too
+            let mut factory = DetectorFactory::new().build();
+            let mut sorted: Vec<&(String, String)> = profiles.iter().collect();
+            sorted.sort_by(|a, b| a.0.cmp(&b.0));
+            for (index, (_, json)) in sorted.into_iter().enumerate() {
+                let profile_json: LangProfileJson = serde_json::from_str(json).map_err(|e| e.to_string())?;
+                let profile = LangProfile::from_json(profile_json)?;
+                factory.add_profile(profile, index, profiles.len()).map_err(|e| e.to_string())?;
+            }
+            factory.save_binary(output).map_err(|e| e.to_string())?;
+        }
+    }
+    println!("Wrote {} profile(s) to {:?}", profiles.len(), output);
+    Ok(())
+}
+
+/// Reads JSON profiles as (language, JSON) pairs from a file, directory, gzip or zstd file or zip archive.
+///
+/// Files in a directory are decoded like a single input file, so they may be compressed too.
+fn read_profiles(input: &Path) -> Result<Vec<(String, String)>, String> {
+    let mut contents = Vec::new();
+    if input.is_dir() {
+        let mut paths: Vec<PathBuf> = fs::read_dir(input).map_err(|e| e.to_string())?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+        for path in paths {
+            decode_profiles(&path, &mut contents).map_err(|e| format!("{:?}: {}", path, e))?;
+        }
+    } else {
+        decode_profiles(input, &mut contents).map_err(|e| format!("{:?}: {}", input, e))?;
+    }
+    contents.into_iter().map(|json| {
+        let profile: LangProfileJson = serde_json::from_str(&json).map_err(|e| format!("Failed to parse JSON profile: {}", e))?;
+        Ok((profile.name, json))
+    }).collect()
+}
+
+/// Reads the JSON profiles of a file, recognizing gzip and zstd compression and zip archives by their magic bytes.
+fn decode_profiles(path: &Path, contents: &mut Vec<String>) -> Result<(), String> {
+    let bytes = fs::read(path).map_err(|e| e.to_string())?;
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut json = String::new();
+        GzDecoder::new(bytes.as_slice()).read_to_string(&mut json).map_err(|e| e.to_string())?;
+        contents.push(json);
+    } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        let json = zstd::decode_all(bytes.as_slice()).map_err(|e| e.to_string())?;
+        contents.push(String::from_utf8(json).map_err(|e| e.to_string())?);
+    } else if bytes.starts_with(b"PK") {
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).map_err(|e| e.to_string())?;
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i).map_err(|e| e.to_string())?;
+            if entry.is_file() {
+                let mut json = String::new();
+                entry.read_to_string(&mut json).map_err(|e| e.to_string())?;
+                contents.push(json);
+            }
+        }
+    } else if bytes.starts_with(b"LDRS") {
+        return Err("Binary profiles only store probabilities and cannot be converted back".to_string());
+    } else {
+        contents.push(String::from_utf8(bytes).map_err(|e| e.to_string())?);
+    }
+    Ok(())
+}
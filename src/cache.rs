@@ -0,0 +1,286 @@
+use std::collections::{HashMap, VecDeque};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::language::Language;
+
+/// Cached detection result.
+struct CacheEntry {
+    probabilities: Vec<Language>,
+    inserted_at: Instant,
+    /// Insertion number, telling the current entry of a key from stale items in `Entries::order`.
+    seq: u64,
+}
+
+/// Cached results with their keys in insertion order, so the oldest one is evicted in O(1).
+#[derive(Default)]
+struct Entries {
+    map: HashMap<u64, CacheEntry>,
+    /// Keys with their insertion numbers, oldest first. Items of replaced or removed
+    /// entries stay until they reach the front or the queue is compacted.
+    order: VecDeque<(u64, u64)>,
+    next_seq: u64,
+}
+
+impl Entries {
+    /// Removes and returns the oldest entry, skipping stale items of the queue.
+    fn pop_oldest(&mut self) -> Option<CacheEntry> {
+        while let Some((key, seq)) = self.order.pop_front() {
+            if self.map.get(&key).is_some_and(|entry| entry.seq == seq) {
+                return self.map.remove(&key);
+            }
+        }
+        None
+    }
+
+    /// Returns the oldest entry, dropping stale items from the front of the queue.
+    fn oldest(&mut self) -> Option<&CacheEntry> {
+        while let Some(&(key, seq)) = self.order.front() {
+            if self.map.get(&key).is_some_and(|entry| entry.seq == seq) {
+                return self.map.get(&key);
+            }
+            self.order.pop_front();
+        }
+        None
+    }
+}
+
+/// Thread-safe cache of detection results keyed by a hash of the content.
+///
+/// Useful for services classifying the same (e.g. templated) messages over and over.
+/// Entries expire after an optional time-to-live, and the oldest entries are evicted
+/// once the capacity is reached. Wrap it in an `Arc` to share one cache between
+/// factories cloned across threads.
+///
+/// The key is derived from the text, alpha and the state of the factory, so a factory
+/// modified after a result was cached does not get it back.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use langdetect_rs::cache::DetectionCache;
+/// use langdetect_rs::detector_factory::DetectorFactory;
+///
+/// let cache = Arc::new(DetectionCache::new()
+///     .with_capacity(10_000)
+///     .with_ttl(Some(Duration::from_secs(600)))
+///     .build());
+/// let factory = DetectorFactory::default()
+///     .with_cache(Some(cache.clone()))
+///     .build();
+/// factory.detect("Your order has been shipped", None).unwrap();
+/// factory.detect("Your order has been shipped", None).unwrap();
+/// assert_eq!(cache.hits(), 1);
+/// ```
+pub struct DetectionCache {
+    capacity: usize,
+    ttl: Option<Duration>,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl DetectionCache {
+    /// Default maximum number of cached results.
+    pub const DEFAULT_CAPACITY: usize = 4096;
+
+    /// Creates a new DetectionCache builder.
+    ///
+    /// By default the cache holds up to `DEFAULT_CAPACITY` results which never expire.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> DetectionCacheBuilder {
+        DetectionCacheBuilder {
+            cache: DetectionCache {
+                capacity: Self::DEFAULT_CAPACITY,
+                ttl: None,
+                entries: Mutex::new(Entries::default()),
+                hits: AtomicU64::new(0),
+                misses: AtomicU64::new(0),
+            },
+        }
+    }
+
+    /// Computes the cache key of a text detected with the given alpha by a factory state.
+    ///
+    /// `generation` identifies the languages and settings the result is computed with,
+    /// so results of a modified factory are never mixed up with earlier ones.
+    pub fn key(text: &str, alpha: Option<f64>, generation: u64) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        alpha.map(f64::to_bits).hash(&mut hasher);
+        generation.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Returns the cached result for a key, if present and not expired.
+    pub fn get(&self, key: u64) -> Option<Vec<Language>> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let result = match entries.map.get(&key) {
+            Some(entry) if self.is_expired(entry) => {
+                entries.map.remove(&key);
+                None
+            }
+            Some(entry) => Some(entry.probabilities.clone()),
+            None => None,
+        };
+        let counter = if result.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    /// Stores a result, evicting expired and then the oldest entries if the cache is full.
+    ///
+    /// Entries expire in insertion order, so both are evicted from the front of a queue.
+    pub fn insert(&self, key: u64, probabilities: Vec<Language>) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        while entries.oldest().is_some_and(|entry| self.is_expired(entry)) {
+            entries.pop_oldest();
+        }
+        if entries.map.len() >= self.capacity && !entries.map.contains_key(&key) {
+            entries.pop_oldest();
+        }
+        let seq = entries.next_seq;
+        entries.next_seq += 1;
+        entries.map.insert(key, CacheEntry { probabilities, inserted_at: Instant::now(), seq });
+        entries.order.push_back((key, seq));
+        // Replaced and expired entries leave stale items behind; drop them once they dominate
+        if entries.order.len() > 2 * entries.map.len() + 16 {
+            let Entries { map, order, .. } = &mut *entries;
+            order.retain(|(key, seq)| map.get(key).is_some_and(|entry| entry.seq == *seq));
+        }
+    }
+
+    /// Number of cached results (including expired ones not evicted yet).
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).map.len()
+    }
+
+    /// Returns true if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all cached results.
+    pub fn clear(&self) {
+        *self.entries.lock().unwrap_or_else(|e| e.into_inner()) = Entries::default();
+    }
+
+    /// Creates an empty cache with the same capacity and time-to-live.
+    pub fn empty_copy(&self) -> DetectionCache {
+        DetectionCache::new().with_capacity(self.capacity).with_ttl(self.ttl).build()
+    }
+
+    /// Number of lookups answered from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of lookups not answered from the cache.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    fn is_expired(&self, entry: &CacheEntry) -> bool {
+        self.ttl.is_some_and(|ttl| entry.inserted_at.elapsed() > ttl)
+    }
+}
+
+/// Builder for `DetectionCache` with fluent setters.
+pub struct DetectionCacheBuilder {
+    cache: DetectionCache,
+}
+
+impl DetectionCacheBuilder {
+    /// Sets the maximum number of cached results.
+    ///
+    /// # Arguments
+    /// * `capacity` - Maximum number of entries; 0 disables caching.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.cache.capacity = capacity;
+        self
+    }
+
+    /// Sets how long results stay valid.
+    ///
+    /// # Arguments
+    /// * `ttl` - Time-to-live of an entry, or None for no expiry.
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.cache.ttl = ttl;
+        self
+    }
+
+    /// Builds the final DetectionCache with the configured properties.
+    pub fn build(self) -> DetectionCache {
+        self.cache
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(lang: &str) -> Vec<Language> {
+        vec![Language::new(Some(lang.into()), 0.99)]
+    }
+
+    #[test]
+    fn test_capacity_eviction() {
+        let cache = DetectionCache::new().with_capacity(2).build();
+        cache.insert(1, result("en"));
+        std::thread::sleep(Duration::from_millis(2));
+        cache.insert(2, result("fr"));
+        cache.insert(3, result("de"));
+        assert_eq!(cache.len(), 2);
+        // The oldest entry was evicted
+        assert!(cache.get(1).is_none());
+        assert_eq!(cache.get(3), Some(result("de")));
+        assert_eq!(cache.hits(), 1);
+        assert_eq!(cache.misses(), 1);
+    }
+
+    #[test]
+    fn test_eviction_order() {
+        let cache = DetectionCache::new().with_capacity(3).build();
+        for key in 1..=3 {
+            cache.insert(key, result("en"));
+        }
+        // Inserting a key again makes it the newest one
+        cache.insert(1, result("fr"));
+        cache.insert(4, result("de"));
+        assert!(cache.get(2).is_none());
+        assert_eq!(cache.get(1), Some(result("fr")));
+        // Stale queue items of replaced entries are dropped
+        for _ in 0..100 {
+            cache.insert(4, result("de"));
+        }
+        assert_eq!(cache.len(), 3);
+        assert!(cache.entries.lock().unwrap().order.len() <= 2 * 3 + 16);
+        cache.insert(5, result("de"));
+        assert!(cache.get(3).is_none());
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn test_ttl() {
+        let cache = DetectionCache::new().with_ttl(Some(Duration::from_millis(1))).build();
+        cache.insert(1, result("en"));
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(cache.get(1).is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_key() {
+        assert_eq!(DetectionCache::key("abc", None, 1), DetectionCache::key("abc", None, 1));
+        assert_ne!(DetectionCache::key("abc", None, 1), DetectionCache::key("abc", Some(0.5), 1));
+        assert_ne!(DetectionCache::key("abc", None, 1), DetectionCache::key("abc", None, 2));
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufWriter, Write};
+use std::path::{Component, Path};
+
+use crate::detector_factory::DetectorFactory;
+use crate::language::Language;
+
+/// Line counts of a corpus split, see `CorpusSplitter::split`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SplitStats {
+    /// Number of non-empty lines read.
+    pub lines: usize,
+    /// Number of lines routed to every language, ordered by language code.
+    pub per_language: BTreeMap<String, usize>,
+    /// Number of lines no language reached the minimum confidence for, undetectable lines included.
+    pub rejected: usize,
+}
+
+/// Partitions a mixed-language corpus by detected language.
+///
+/// Every non-empty line is a document. Lines whose most probable language reaches the
+/// minimum confidence go to that language, the others to the unknown label (or nowhere).
+/// Lines are detected in batches with `DetectorFactory::get_probabilities_batch`, so the
+/// `rayon` feature splits large corpora in parallel while memory stays bounded.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::corpus::CorpusSplitter;
+/// use langdetect_rs::detector_factory::DetectorFactory;
+///
+/// let factory = DetectorFactory::default().with_seed(Some(0)).build();
+/// let splitter = CorpusSplitter::new(&factory).with_min_confidence(0.8).build();
+/// let corpus = "Der Hund schläft im Garten.\nThe dog is sleeping in the garden behind the house.\n42\n";
+/// let mut english = Vec::new();
+/// let stats = splitter.split(corpus.as_bytes(), |lang, line| {
+///     if lang == "en" {
+///         english.push(line.to_string());
+///     }
+///     Ok(())
+/// }).unwrap();
+/// assert_eq!(english, ["The dog is sleeping in the garden behind the house."]);
+/// assert_eq!(stats.rejected, 1);
+/// ```
+pub struct CorpusSplitter<'a> {
+    factory: &'a DetectorFactory,
+    min_confidence: f64,
+    unknown_label: Option<String>,
+    alpha: Option<f64>,
+    batch_size: usize,
+}
+
+impl<'a> CorpusSplitter<'a> {
+    /// Default minimum probability of the language a line is routed to.
+    pub const MIN_CONFIDENCE: f64 = 0.9;
+    /// Default label of the lines without a confident language.
+    pub const UNKNOWN_LABEL: &'static str = "unknown";
+    /// Default number of lines detected at once.
+    pub const BATCH_SIZE: usize = 1024;
+
+    /// Creates a new CorpusSplitter builder detecting with a factory.
+    ///
+    /// # Arguments
+    /// * `factory` - The factory detecting the lines; its language filter, label set and
+    ///   seed apply.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(factory: &'a DetectorFactory) -> CorpusSplitterBuilder<'a> {
+        CorpusSplitterBuilder {
+            splitter: CorpusSplitter {
+                factory,
+                min_confidence: Self::MIN_CONFIDENCE,
+                unknown_label: Some(Self::UNKNOWN_LABEL.to_string()),
+                alpha: None,
+                batch_size: Self::BATCH_SIZE,
+            },
+        }
+    }
+
+    /// Returns the language a document is routed to.
+    ///
+    /// # Arguments
+    /// * `text` - The document text.
+    ///
+    /// # Returns
+    /// The most probable language, or None if it does not reach the minimum confidence
+    /// or the text has no detectable n-grams.
+    pub fn classify(&self, text: &str) -> Option<String> {
+        let probabilities = self.factory.get_probabilities(text, self.alpha).ok()?;
+        self.confident_language(&probabilities)
+    }
+
+    /// Reads a corpus line by line and passes every line to a sink with its label.
+    ///
+    /// Empty lines are skipped. Lines without a confident language are passed with the
+    /// unknown label, or dropped if it is None; they are counted as rejected either way.
+    ///
+    /// # Arguments
+    /// * `reader` - The corpus, one document per line.
+    /// * `sink` - Called with the label and the line (without line break), in corpus order.
+    ///
+    /// # Returns
+    /// The number of lines read, routed to every language and rejected.
+    ///
+    /// # Errors
+    /// Returns the first error of reading the corpus (e.g. invalid UTF-8) or of the sink.
+    pub fn split<R, F>(&self, reader: R, mut sink: F) -> io::Result<SplitStats>
+    where
+        R: BufRead,
+        F: FnMut(&str, &str) -> io::Result<()>,
+    {
+        self.route(reader, |lang, line| match (lang, &self.unknown_label) {
+            (Some(lang), _) => sink(lang, line),
+            (None, Some(label)) => sink(label, line),
+            (None, None) => Ok(()),
+        })
+    }
+
+    /// Reads a corpus line by line and passes every line to a sink with its language,
+    /// or None if no language is confident.
+    fn route<R, F>(&self, reader: R, mut sink: F) -> io::Result<SplitStats>
+    where
+        R: BufRead,
+        F: FnMut(Option<&str>, &str) -> io::Result<()>,
+    {
+        let mut stats = SplitStats::default();
+        let mut lines = reader.lines();
+        loop {
+            let mut batch = Vec::with_capacity(self.batch_size);
+            for line in lines.by_ref() {
+                let line = line?;
+                if !line.trim().is_empty() {
+                    batch.push(line);
+                    if batch.len() >= self.batch_size {
+                        break;
+                    }
+                }
+            }
+            if batch.is_empty() {
+                return Ok(stats);
+            }
+            let texts: Vec<&str> = batch.iter().map(String::as_str).collect();
+            let results = self.factory.get_probabilities_batch(&texts, self.alpha);
+            for (line, result) in texts.into_iter().zip(results) {
+                stats.lines += 1;
+                match result.ok().and_then(|probabilities| self.confident_language(&probabilities)) {
+                    Some(lang) => {
+                        sink(Some(&lang), line)?;
+                        *stats.per_language.entry(lang).or_default() += 1;
+                    }
+                    None => {
+                        sink(None, line)?;
+                        stats.rejected += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Splits a corpus into one `<label>.txt` file per label in a directory.
+    ///
+    /// The directory is created if needed. Files are only created for labels which receive
+    /// lines, and existing files of those labels are overwritten. Labels must be plain file
+    /// names (e.g. no "../x"), and no language may share its file with the unknown label.
+    ///
+    /// # Arguments
+    /// * `reader` - The corpus, one document per line.
+    /// * `directory` - The output directory.
+    ///
+    /// # Returns
+    /// The number of lines read, routed to every language and rejected.
+    ///
+    /// # Errors
+    /// Returns an error of kind `InvalidInput` for a label with path separators or a language
+    /// named like the unknown label, and the first error of reading the corpus or of creating
+    /// and writing the files.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use std::fs::File;
+    /// use std::io::BufReader;
+    /// use langdetect_rs::corpus::CorpusSplitter;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let corpus = BufReader::new(File::open("crawl.txt").unwrap());
+    /// let stats = CorpusSplitter::new(&factory).build().split_to_directory(corpus, "by-language").unwrap();
+    /// println!("{} lines, {} rejected", stats.lines, stats.rejected);
+    /// ```
+    pub fn split_to_directory<R: BufRead, P: AsRef<Path>>(&self, reader: R, directory: P) -> io::Result<SplitStats> {
+        let directory = directory.as_ref();
+        fs::create_dir_all(directory)?;
+        let unknown_file = self.unknown_label.as_deref().map(label_file_name).transpose()?;
+        // Keyed by language, None for the unknown label
+        let mut writers: HashMap<Option<String>, BufWriter<File>> = HashMap::new();
+        let stats = self.route(reader, |lang, line| {
+            let writer = match writers.entry(lang.map(String::from)) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    let file_name = match (lang, &unknown_file) {
+                        (Some(lang), _) => {
+                            let file_name = label_file_name(lang)?;
+                            if unknown_file.as_ref() == Some(&file_name) {
+                                return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("language {:?} is named like the unknown label", lang)));
+                            }
+                            file_name
+                        }
+                        (None, Some(file_name)) => file_name.clone(),
+                        (None, None) => return Ok(()),
+                    };
+                    entry.insert(BufWriter::new(File::create(directory.join(file_name))?))
+                }
+            };
+            writeln!(writer, "{}", line)
+        })?;
+        for writer in writers.values_mut() {
+            writer.flush()?;
+        }
+        Ok(stats)
+    }
+
+    /// Returns the most probable language if it reaches the minimum confidence.
+    fn confident_language(&self, probabilities: &[Language]) -> Option<String> {
+        let top = probabilities.first().filter(|language| language.prob >= self.min_confidence)?;
+        top.lang.as_deref().map(String::from)
+    }
+}
+
+/// Returns the `<label>.txt` file name of a label.
+///
+/// # Errors
+/// Returns an error of kind `InvalidInput` if the file name is not a single path component.
+fn label_file_name(label: &str) -> io::Result<String> {
+    let file_name = format!("{}.txt", label);
+    let mut components = Path::new(&file_name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(file_name),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("label {:?} is not a plain file name", label))),
+    }
+}
+
+/// Builder for `CorpusSplitter` with fluent setters.
+pub struct CorpusSplitterBuilder<'a> {
+    splitter: CorpusSplitter<'a>,
+}
+
+impl<'a> CorpusSplitterBuilder<'a> {
+    /// Sets the minimum probability of the language a line is routed to (default: 0.9).
+    ///
+    /// # Arguments
+    /// * `min_confidence` - The threshold; 0.0 routes every detectable line to its most probable language.
+    pub fn with_min_confidence(mut self, min_confidence: f64) -> Self {
+        self.splitter.min_confidence = min_confidence;
+        self
+    }
+
+    /// Sets the label of the lines without a confident language (default: "unknown").
+    ///
+    /// # Arguments
+    /// * `label` - The label, or None to drop such lines.
+    pub fn with_unknown_label(mut self, label: Option<&str>) -> Self {
+        self.splitter.unknown_label = label.map(String::from);
+        self
+    }
+
+    /// Sets the alpha smoothing parameter of the detection.
+    ///
+    /// # Arguments
+    /// * `alpha` - The parameter, or None for the factory default.
+    pub fn with_alpha(mut self, alpha: Option<f64>) -> Self {
+        self.splitter.alpha = alpha;
+        self
+    }
+
+    /// Sets the number of lines detected at once (default: 1024).
+    ///
+    /// Larger batches keep more threads busy with the `rayon` feature, at the cost of memory.
+    ///
+    /// # Arguments
+    /// * `batch_size` - Lines per batch, at least 1.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.splitter.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Builds the final CorpusSplitter with the configured properties.
+    pub fn build(self) -> CorpusSplitter<'a> {
+        self.splitter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detector_factory::LabelSet;
+
+    #[test]
+    fn test_split() {
+        let factory = DetectorFactory::default().with_seed(Some(0)).build();
+        let corpus = "Der Hund schläft im Garten.\n\nThe dog is sleeping in the garden behind the house.\n12345\nDie Katze trinkt Milch.\n";
+        let mut routed = Vec::new();
+        let splitter = CorpusSplitter::new(&factory).with_batch_size(2).build();
+        let stats = splitter.split(corpus.as_bytes(), |label, line| {
+            routed.push((label.to_string(), line.to_string()));
+            Ok(())
+        }).unwrap();
+        assert_eq!(stats.lines, 4);
+        assert_eq!(stats.rejected, 1);
+        assert_eq!(stats.per_language, BTreeMap::from([("de".to_string(), 2), ("en".to_string(), 1)]));
+        let labels: Vec<&str> = routed.iter().map(|(label, _)| label.as_str()).collect();
+        assert_eq!(labels, ["de", "en", "unknown", "de"]);
+        assert_eq!(routed[3].1, "Die Katze trinkt Milch.");
+
+        let dropping = CorpusSplitter::new(&factory).with_unknown_label(None).with_min_confidence(1.1).build();
+        let mut count = 0;
+        let stats = dropping.split(corpus.as_bytes(), |_, _| { count += 1; Ok(()) }).unwrap();
+        assert_eq!((count, stats.rejected), (0, 4));
+        assert_eq!(splitter.classify("The dog is sleeping in the garden behind the house.").as_deref(), Some("en"));
+        assert_eq!(splitter.classify("12345"), None);
+    }
+
+    #[test]
+    fn test_split_to_directory() {
+        let factory = DetectorFactory::default().with_seed(Some(0)).build();
+        let dir = std::env::temp_dir().join(format!("langdetect-corpus-{}", std::process::id()));
+        let corpus = "Der Hund schläft im Garten.\nThe dog is sleeping in the garden behind the house.\nDie Katze trinkt Milch.\n";
+        let stats = CorpusSplitter::new(&factory).build().split_to_directory(corpus.as_bytes(), &dir).unwrap();
+        assert_eq!(stats.lines, 3);
+        assert_eq!(fs::read_to_string(dir.join("de.txt")).unwrap(), "Der Hund schläft im Garten.\nDie Katze trinkt Milch.\n");
+        assert_eq!(fs::read_to_string(dir.join("en.txt")).unwrap(), "The dog is sleeping in the garden behind the house.\n");
+        assert!(!dir.join("unknown.txt").exists());
+
+        // Labels must stay inside the directory
+        let escaping = CorpusSplitter::new(&factory).with_unknown_label(Some("../unknown")).build();
+        let error = escaping.split_to_directory(corpus.as_bytes(), &dir).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        let labeled = DetectorFactory::default().with_seed(Some(0))
+            .with_label_set(Some(LabelSet::new("x").with_label("../de", &["de"])))
+            .build();
+        let error = CorpusSplitter::new(&labeled).build().split_to_directory(corpus.as_bytes(), &dir).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        assert!(!dir.join("../de.txt").exists());
+
+        // A language named like the unknown label is not mixed with the rejected lines
+        let colliding = CorpusSplitter::new(&factory).with_unknown_label(Some("en")).build();
+        let error = colliding.split_to_directory(corpus.as_bytes(), &dir).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,25 +2,433 @@ use rand::{SeedableRng, Rng};
 use rand::rngs::StdRng;
 use rand_distr::{Normal, Distribution};
 
-use crate::language::Language;
-use crate::utils::ngram::NGram;
+use crate::language::{Lang, Language};
+use crate::utils::ngram::{NGram, Normalization};
+use crate::profile_data::{ProfileData, ProfileMatrix};
+use crate::utils::script::{dominant_script, Script};
+use crate::utils::arabic::{marker_row, marker_writers, normalize_arabic};
+use crate::utils::devanagari::{preferred_language, DEVANAGARI_LANGUAGES};
+use serde::Serialize;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Errors that can occur during language detection.
 #[derive(Debug, Clone)]
 pub enum DetectorError {
     /// No detectable features found in the input text.
     NoFeatures,
+    /// A language code is not among the loaded languages.
+    UnknownLanguage(String),
+    /// Prior probabilities are invalid (e.g. negative or summing to zero).
+    InvalidPrior(String),
+    /// Boost factors are invalid (e.g. negative or not matching the languages).
+    InvalidBoost(String),
 }
 
 impl std::fmt::Display for DetectorError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             DetectorError::NoFeatures => write!(f, "No features found in the input text"),
+            DetectorError::UnknownLanguage(lang) => write!(f, "Unknown language: {}", lang),
+            DetectorError::InvalidPrior(e) => write!(f, "Invalid prior probabilities: {}", e),
+            DetectorError::InvalidBoost(e) => write!(f, "Invalid boost factors: {}", e),
         }
     }
 }
 
+/// Diagnostics of a single EM trial.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrialStats {
+    /// Alpha smoothing value used by the trial (randomized around `Detector::alpha`).
+    pub alpha: f64,
+    /// Final normalized probability vector of the trial, in `langlist` order.
+    pub probabilities: Vec<f64>,
+    /// Number of n-gram updates the trial ran.
+    pub iterations: usize,
+    /// Whether the top language reached `Detector::conv_threshold` before `Detector::iteration_limit`.
+    pub converged: bool,
+}
+
+/// Estimate of whether Latin-script text is a romanization of another language.
+///
+/// Computed from romanized companion profiles: profiles whose language code ends with
+/// `Detector::ROMANIZED_SUFFIX` (e.g. "hi-Latn" for romanized Hindi). No such profiles
+/// are built in; train them from romanized corpora and add them to the factory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransliterationSignal {
+    /// Whether the text is likely transliterated.
+    pub likely: bool,
+    /// Language code of the best covering companion profile (e.g. "hi-Latn").
+    pub romanized_lang: Lang,
+    /// Share of the text's known n-grams found in the best covering companion profile.
+    pub romanized_coverage: f64,
+    /// Share of the text's known n-grams found in the best covering regular profile.
+    pub native_coverage: f64,
+}
+
+/// Statistics collected during the last detection run of a `Detector`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DetectionStats {
+    /// Per-trial results. Only recorded when `Detector::record_trials` is enabled.
+    pub trials: Vec<TrialStats>,
+    /// Transliteration estimate; only present for mostly Latin text when romanized
+    /// companion profiles are loaded.
+    pub transliteration: Option<TransliterationSignal>,
+    /// Number of n-grams extracted from the text that were found in the profiles.
+    pub matched_ngrams: usize,
+    /// Number of n-grams extracted from the text.
+    pub total_ngrams: usize,
+    /// Number of trials stopped by `Detector::iteration_limit` rather than convergence.
+    ///
+    /// Trials regularly hitting the limit point at pathological input or bad profiles.
+    pub unconverged_trials: usize,
+}
+
+impl DetectionStats {
+    /// Returns the share of extracted n-grams found in the profiles.
+    ///
+    /// Low coverage (e.g. below 0.3) means most of the text is unknown to every
+    /// profile, which is the best predictor of an unreliable result.
+    ///
+    /// # Returns
+    /// A value in [0, 1], or 0.0 if no n-gram was extracted.
+    pub fn coverage(&self) -> f64 {
+        if self.total_ngrams > 0 { self.matched_ngrams as f64 / self.total_ngrams as f64 } else { 0.0 }
+    }
+
+    /// Returns the largest spread (max - min) of a single language's probability across trials.
+    ///
+    /// Values close to 0.0 mean the trials agree, values close to 1.0 mean they
+    /// picked different winners, which usually indicates unreliable input.
+    pub fn max_trial_spread(&self) -> f64 {
+        let langsize = self.trials.first().map_or(0, |t| t.probabilities.len());
+        (0..langsize).map(|i| {
+            let (min, max) = self.trials.iter().fold((f64::MAX, f64::MIN), |(min, max), t| {
+                (min.min(t.probabilities[i]), max.max(t.probabilities[i]))
+            });
+            max - min
+        }).fold(0.0, f64::max)
+    }
+}
+
+/// The detected language together with signals telling whether to trust it.
+///
+/// Returned by `Detector::detect_with_confidence` and `DetectorFactory::detect_with_confidence`.
+/// A probability alone says little: 0.57 may be a clear winner among many languages or a
+/// coin flip between two. `is_reliable()` combines the signals like the reliability flag of CLD2.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DetectionResult {
+    /// The most probable language, with `lang: None` if no language exceeds the probability threshold.
+    pub language: Language,
+    /// Probability difference between the most probable language and the runner-up.
+    pub margin: f64,
+    /// Shannon entropy (in bits) of the distribution over all loaded languages.
+    pub entropy: f64,
+    /// Share of the n-grams of the text found in the profiles, see `DetectionStats::coverage`.
+    pub coverage: f64,
+}
+
+impl DetectionResult {
+    /// Minimum margin of a reliable result.
+    pub const RELIABLE_MARGIN: f64 = 0.3;
+    /// Maximum entropy (in bits) of a reliable result.
+    pub const RELIABLE_ENTROPY: f64 = 1.0;
+    /// Minimum n-gram coverage of a reliable result.
+    pub const RELIABLE_COVERAGE: f64 = 0.3;
+
+    /// Returns whether the result can be trusted.
+    ///
+    /// A result is reliable if a language was detected, it leads the runner-up by at least
+    /// `RELIABLE_MARGIN`, the distribution is concentrated (entropy at most `RELIABLE_ENTROPY`)
+    /// and at least `RELIABLE_COVERAGE` of the text's n-grams are known to the profiles.
+    pub fn is_reliable(&self) -> bool {
+        self.language.lang.is_some()
+            && self.margin >= Self::RELIABLE_MARGIN
+            && self.entropy <= Self::RELIABLE_ENTROPY
+            && self.coverage >= Self::RELIABLE_COVERAGE
+    }
+}
+
+/// Answer to "is this text in language X?", see `Detector::verify`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VerificationResult {
+    /// Probability of the language.
+    pub prob: f64,
+    /// Whether the probability reaches `Detector::verify_threshold`.
+    pub is_match: bool,
+    /// Probability of the language minus the probability of the most probable other language.
+    /// Negative if another language is more probable.
+    pub margin: f64,
+}
+
+/// Why a text yields no detectable n-grams, see `FallbackResult`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FallbackReason {
+    /// The text is empty or whitespace only, after preprocessing.
+    EmptyText,
+    /// The text has no letters, e.g. only digits, punctuation or emoji.
+    NoLetters,
+    /// The text has letters, but none of its n-grams is known to the loaded profiles,
+    /// e.g. a script no profile is written in.
+    UnknownNGrams,
+}
+
+/// What is known about a text the model cannot detect, see `Detector::detect_with_fallback`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FallbackResult {
+    /// Why no language could be detected.
+    pub reason: FallbackReason,
+    /// The script most letters of the text are written in, if any.
+    pub script: Option<Script>,
+    /// The only detectable language written in `script`, e.g. Korean for Hangul.
+    /// None if no language or several languages use the script.
+    pub guess: Option<Lang>,
+}
+
+/// Outcome of `Detector::detect_with_fallback`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum Detection {
+    /// The language was detected from the n-grams of the text.
+    Language(Language),
+    /// The text has no detectable n-grams.
+    Fallback(FallbackResult),
+}
+
+/// Influence of one n-gram of the text on the probability of a language, see `Detector::explain`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NGramContribution {
+    /// The n-gram.
+    pub ngram: String,
+    /// Number of occurrences in the text.
+    pub count: usize,
+    /// Log-likelihood ratio of the language against the other candidates, times `count`.
+    /// Positive values favor the language.
+    pub score: f64,
+    /// Probability of the n-gram in the profile of every explained language, in the order of the explanations.
+    pub probabilities: Vec<Language>,
+}
+
+/// The n-grams which contributed most to the probability of a language, see `Detector::explain`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageExplanation {
+    /// The language and its detected probability.
+    pub language: Language,
+    /// N-grams favoring the language, highest score first.
+    pub ngrams: Vec<NGramContribution>,
+}
+
+/// Per-call options of `DetectorFactory::detect_with_options`.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::detector::DetectOptions;
+/// use langdetect_rs::detector_factory::DetectorFactory;
+/// use langdetect_rs::utils::script::Script;
+///
+/// let factory = DetectorFactory::default().build();
+/// let options = DetectOptions::new().with_script_hint(Script::Cyrillic);
+/// assert_eq!(factory.detect_with_options("Добрый день", &options).unwrap(), "ru");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DetectOptions {
+    /// Alpha smoothing parameter; the factory default if None.
+    pub alpha: Option<f64>,
+    /// Script the text is known to be written in; only languages using it are candidates.
+    pub script_hint: Option<Script>,
+    /// Factors multiplying the final probability of languages, see `with_boost`.
+    pub boosts: Vec<(Lang, f64)>,
+    /// Languages of earlier texts of the same source with their shares, see `with_history`.
+    pub history: Vec<(Lang, f64)>,
+}
+
+impl DetectOptions {
+    /// Minimum prior of every language when a history is given.
+    pub const HISTORY_FLOOR: f64 = 0.01;
+
+    /// Creates options with the factory defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the alpha smoothing parameter.
+    ///
+    /// # Arguments
+    /// * `alpha` - The smoothing parameter.
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    /// Restrict the candidates to languages written in a script, see `Detector::restrict_to_script`.
+    ///
+    /// Useful for callers that already know the script from upstream processing.
+    ///
+    /// # Arguments
+    /// * `script` - The script of the text.
+    pub fn with_script_hint(mut self, script: Script) -> Self {
+        self.script_hint = Some(script);
+        self
+    }
+
+    /// Multiply the final probability of a language by a factor before ranking.
+    ///
+    /// Unlike priors, which weight the EM estimation, boosts reweight its result: the
+    /// probabilities are multiplied and renormalized. A pragmatic knob for a language which
+    /// is systematically under-detected in some traffic. Languages which are not loaded are
+    /// ignored; a later boost of the same language replaces the earlier one.
+    ///
+    /// # Arguments
+    /// * `lang` - The language, as a `Lang` value or code.
+    /// * `factor` - Non-negative factor; above 1.0 favors the language, below disfavors it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector::DetectOptions;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().with_seed(Some(42)).build();
+    /// let options = DetectOptions::new().with_boost("pt", 10.0);
+    /// assert_eq!(factory.detect_with_options("obrigado", &options).unwrap(), "pt");
+    /// ```
+    pub fn with_boost(mut self, lang: impl Into<Lang>, factor: f64) -> Self {
+        let lang = lang.into();
+        self.boosts.retain(|(l, _)| *l != lang);
+        self.boosts.push((lang, factor));
+        self
+    }
+
+    /// Use the languages of earlier texts of the same source (e.g. a chat user) as priors.
+    ///
+    /// The shares are normalized and replace the factory priors for this detection. Every
+    /// language keeps a prior of at least `HISTORY_FLOOR` (or the factory prior floor if
+    /// larger), so a message in a language missing from the history is still detected when
+    /// the evidence is strong. Languages which are not loaded are ignored.
+    ///
+    /// # Arguments
+    /// * `history` - Languages with their share (or count) of earlier detections.
+    ///
+    /// # Errors
+    /// Detection fails with `DetectorError::InvalidPrior` if a share is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector::DetectOptions;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().with_seed(Some(0)).build();
+    /// let options = DetectOptions::new().with_history(&[("en", 0.7), ("ru", 0.3)]);
+    /// assert_eq!(factory.detect_with_options("ok thanks", &options).unwrap(), "en");
+    /// ```
+    pub fn with_history<L: Into<Lang> + Clone>(mut self, history: &[(L, f64)]) -> Self {
+        self.history = history.iter().map(|(lang, share)| (lang.clone().into(), *share)).collect();
+        self
+    }
+}
+
+/// Restriction of detection to a subset of the loaded languages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LanguageFilter {
+    /// Only the listed languages can be detected.
+    Allow(Vec<Lang>),
+    /// All languages except the listed ones can be detected.
+    Deny(Vec<Lang>),
+}
+
+impl LanguageFilter {
+    /// Returns true if the filter lets the language through.
+    pub fn allows(&self, lang: &str) -> bool {
+        match self {
+            LanguageFilter::Allow(langs) => langs.iter().any(|l| l == lang),
+            LanguageFilter::Deny(langs) => !langs.iter().any(|l| l == lang),
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref URL_RE: regex::Regex = regex::Regex::new(r"https?://[-_.?&~;+=/#0-9A-Za-z]{1,2076}").unwrap();
+    static ref MAIL_RE: regex::Regex = regex::Regex::new(r"[-_.0-9A-Za-z]{1,64}@[-_0-9A-Za-z]{1,255}[-_.0-9A-Za-z]{1,255}").unwrap();
+}
+
+/// Removal of URLs, email addresses or other noise from appended text.
+///
+/// Matches are replaced by a space before the text is added to the detector.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::detector::TextScrubbing;
+/// use langdetect_rs::detector_factory::DetectorFactory;
+///
+/// // Also drop @mentions and #hashtags of social media posts
+/// let tags = regex::Regex::new(r"[@#]\w+").unwrap();
+/// let factory = DetectorFactory::default().with_scrubbing(TextScrubbing::default().with_pattern(tags)).build();
+/// let mut detector = factory.create(None);
+/// detector.append("@alice #rustlang Bonjour !");
+/// assert_eq!(detector.preprocessed_text(), "Bonjour !");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub enum TextScrubbing {
+    /// Remove URLs and email addresses with the built-in patterns (default).
+    #[default]
+    UrlsAndEmails,
+    /// Keep the text as is, e.g. for text known to be clean.
+    Disabled,
+    /// Remove the matches of custom patterns, applied in order.
+    Custom(Vec<regex::Regex>),
+}
+
+impl TextScrubbing {
+    /// Adds a pattern to remove, keeping the patterns applied so far.
+    ///
+    /// # Arguments
+    /// * `pattern` - The pattern whose matches are replaced by a space.
+    pub fn with_pattern(self, pattern: regex::Regex) -> Self {
+        let mut patterns = match self {
+            TextScrubbing::UrlsAndEmails => vec![URL_RE.clone(), MAIL_RE.clone()],
+            TextScrubbing::Disabled => Vec::new(),
+            TextScrubbing::Custom(patterns) => patterns,
+        };
+        patterns.push(pattern);
+        TextScrubbing::Custom(patterns)
+    }
+
+    /// Removes the matches from a text; texts without matches are not copied.
+    fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        match self {
+            TextScrubbing::UrlsAndEmails => {
+                // Cheap checks first, most texts contain neither
+                if text.contains("http") {
+                    text = replace_cow(text, |t| URL_RE.replace_all(t, " "));
+                }
+                if text.contains('@') {
+                    text = replace_cow(text, |t| MAIL_RE.replace_all(t, " "));
+                }
+            }
+            TextScrubbing::Disabled => {}
+            TextScrubbing::Custom(patterns) => {
+                for pattern in patterns {
+                    text = replace_cow(text, |t| pattern.replace_all(t, " "));
+                }
+            }
+        }
+        text
+    }
+}
+
+/// Probability row of an EM observation.
+#[derive(Debug, Clone, Copy)]
+enum RowRef {
+    /// The row of an n-gram id in the profile matrix.
+    Profile(usize),
+    /// A row gathered for the detection.
+    Gathered(usize),
+}
+
 /// Core language detection engine.
 ///
 /// The Detector performs the actual language identification using n-gram analysis
@@ -45,10 +453,10 @@ impl std::fmt::Display for DetectorError {
 /// let language = detector.detect().unwrap();
 /// ```
 pub struct Detector {
-    /// Word-to-language probability mapping.
-    pub word_lang_prob_map: HashMap<String, Vec<f64>>,
-    /// List of language identifiers.
-    pub langlist: Vec<String>,
+    /// Per-language n-gram probabilities, shared with the factory and other detectors.
+    pub profile_data: Arc<ProfileData>,
+    /// List of language identifiers, shared with the factory and other detectors.
+    pub langlist: Arc<Vec<Lang>>,
     /// Optional seed for reproducible randomization.
     pub seed: Option<u64>,
     /// Accumulated text for analysis.
@@ -61,10 +469,66 @@ pub struct Detector {
     pub n_trial: usize,
     /// Maximum text length to process.
     pub max_text_length: usize,
-    /// Prior probabilities for languages (optional).
+    /// Minimum probability for a language to be reported.
+    pub prob_threshold: f64,
+    /// Minimum probability for `verify` to accept a text as written in a language.
+    pub verify_threshold: f64,
+    /// Maximum number of EM iterations per trial.
+    pub iteration_limit: usize,
+    /// Probability of the top language at which a trial stops early.
+    pub conv_threshold: f64,
+    /// Smoothing denominator: every observed n-gram adds `alpha / base_freq` to the
+    /// probability of each language, see `DetectorFactory::derived_base_freq`.
+    pub base_freq: f64,
+    /// Prior probabilities for languages (optional), in `langlist` order.
+    ///
+    /// Validated and renormalized before detection, see `set_priors` for a checked setter.
     pub prior_map: Option<Vec<f64>>,
-    /// Whether to enable verbose logging.
+    /// Factors multiplying the final probabilities (then renormalized), in `langlist` order.
+    ///
+    /// Validated at detection, see `set_boosts` for a checked setter and `DetectOptions::with_boost`.
+    pub boost_map: Option<Vec<f64>>,
+    /// Minimum prior of every language, applied before renormalization.
+    ///
+    /// With the default of 0.0 a language with a zero prior can never be detected;
+    /// a small floor (e.g. 0.01) lets strong evidence overrule the prior.
+    pub prior_floor: f64,
+    /// Whether to log the EM algorithm through the `log` facade (needs the `log` feature).
+    ///
+    /// Every trial reports its alpha, iteration count and convergence at debug level, and
+    /// the averaged probabilities follow at the end; the top probability of every
+    /// convergence check is logged at trace level.
     pub verbose: bool,
+    /// Label returned by `detect()` when no language exceeds the probability threshold.
+    pub unknown_label: String,
+    /// Whether to record each trial's probability vector, alpha and iteration count in the detection stats.
+    pub record_trials: bool,
+    /// Whether letters lost by n-gram normalization (Arabic and Farsi yeh, ZWNJ) are used as
+    /// additional evidence to tell Arabic, Persian, Urdu and Pashto apart, see `utils::arabic`.
+    pub arabic_markers: bool,
+    /// Whether function words decide between Hindi, Marathi and Nepali when two of them are
+    /// the most probable languages, see `utils::devanagari`.
+    pub devanagari_tiebreak: bool,
+    /// Whether only languages written in the dominant script of the text are candidates.
+    ///
+    /// Like `restrict_to_script` with the script detected per text: Cyrillic text only
+    /// competes among the Cyrillic languages, which makes detection of non-Latin text
+    /// much faster. The filter is skipped if no language uses the script.
+    pub script_prefilter: bool,
+    /// Removal of URLs, emails or other noise from appended text.
+    pub scrubbing: TextScrubbing,
+    /// Character normalization rules of n-gram extraction, see `Normalization`.
+    pub normalization: Normalization,
+    /// Per-language flags of the languages allowed by the language filter, in `langlist` order.
+    allowed: Option<Vec<bool>>,
+    /// Languages disabled in the factory, masked whatever the language filter.
+    disabled: Vec<Lang>,
+    /// Statistics of the last detection run.
+    stats: Option<DetectionStats>,
+    /// Ids of the known n-grams added with `append_ngrams`.
+    appended_ngrams: Vec<usize>,
+    /// Number of all n-grams added with `append_ngrams`, known or not.
+    appended_total: usize,
 }
 
 impl Detector {
@@ -72,16 +536,32 @@ impl Detector {
     pub const ALPHA_DEFAULT: f64 = 0.5;
     /// Width of alpha variation during randomization.
     pub const ALPHA_WIDTH: f64 = 0.05;
-    /// Maximum iterations for the EM algorithm.
+    /// Default number of trials for the EM algorithm.
+    pub const N_TRIAL_DEFAULT: usize = 7;
+    /// Default maximum text length to process.
+    pub const MAX_TEXT_LENGTH_DEFAULT: usize = 10000;
+    /// Default maximum iterations for the EM algorithm.
     pub const ITERATION_LIMIT: usize = 1000;
-    /// Minimum probability threshold for reporting languages.
+    /// Default minimum probability threshold for reporting languages.
     pub const PROB_THRESHOLD: f64 = 0.1;
-    /// Convergence threshold for the EM algorithm.
+    /// Default minimum probability for `verify` to accept a language.
+    ///
+    /// Verification is a one-vs-rest decision: the language against all others together,
+    /// so it is accepted once it is more probable than the rest.
+    pub const VERIFY_THRESHOLD: f64 = 0.5;
+    /// Default convergence threshold for the EM algorithm.
     pub const CONV_THRESHOLD: f64 = 0.99999;
-    /// Base frequency for probability calculations.
+    /// Default base frequency for probability calculations.
     pub const BASE_FREQ: f64 = 10000.0;
-    /// Language identifier for unknown/undetected languages.
+    /// Base frequency per n-gram of a profile, used to derive the base frequency from profile sizes.
+    /// About 2000 n-grams per language of the built-in profiles give the default `BASE_FREQ`.
+    pub const BASE_FREQ_PER_NGRAM: f64 = 5.0;
+    /// Default label for unknown/undetected languages.
     pub const UNKNOWN_LANG: &'static str = "unknown";
+    /// Language code suffix of romanized companion profiles (e.g. "hi-Latn").
+    pub const ROMANIZED_SUFFIX: &'static str = "-Latn";
+    /// Coverage lead a companion profile needs over regular profiles to flag transliteration.
+    pub const TRANSLITERATION_MARGIN: f64 = 0.1;
 
     /// Creates a new Detector with the given language profiles.
     ///
@@ -89,26 +569,96 @@ impl Detector {
     /// * `word_lang_prob_map` - Pre-computed word-to-language probability mapping.
     /// * `langlist` - List of language identifiers.
     /// * `seed` - Optional seed for reproducible randomization.
-    pub fn new(word_lang_prob_map: HashMap<String, Vec<f64>>, langlist: Vec<String>, seed: Option<u64>) -> Self {
+    pub fn new(word_lang_prob_map: HashMap<String, Vec<f64>>, langlist: Vec<Lang>, seed: Option<u64>) -> Self {
+        Self::from_profile_data(ProfileData::from_word_lang_prob_map(&word_lang_prob_map), langlist, seed)
+    }
+
+    /// Creates a new Detector from column-oriented profile data.
+    ///
+    /// # Arguments
+    /// * `profile_data` - Per-language n-gram probabilities.
+    /// * `langlist` - List of language identifiers, in column order.
+    /// * `seed` - Optional seed for reproducible randomization.
+    pub fn from_profile_data(profile_data: ProfileData, langlist: Vec<Lang>, seed: Option<u64>) -> Self {
+        Self::from_shared(Arc::new(profile_data), Arc::new(langlist), seed)
+    }
+
+    /// Creates a new Detector sharing already loaded profile data.
+    ///
+    /// Only the reference counts are increased, so this is cheap enough to call per request.
+    ///
+    /// # Arguments
+    /// * `profile_data` - Shared per-language n-gram probabilities.
+    /// * `langlist` - Shared list of language identifiers, in column order.
+    /// * `seed` - Optional seed for reproducible randomization.
+    pub fn from_shared(profile_data: Arc<ProfileData>, langlist: Arc<Vec<Lang>>, seed: Option<u64>) -> Self {
         Detector {
-            word_lang_prob_map,
+            profile_data,
             langlist,
             seed,
             text: String::new(),
             langprob: None,
             alpha: Self::ALPHA_DEFAULT,
-            n_trial: 7,
-            max_text_length: 10000,
+            n_trial: Self::N_TRIAL_DEFAULT,
+            max_text_length: Self::MAX_TEXT_LENGTH_DEFAULT,
+            prob_threshold: Self::PROB_THRESHOLD,
+            verify_threshold: Self::VERIFY_THRESHOLD,
+            iteration_limit: Self::ITERATION_LIMIT,
+            conv_threshold: Self::CONV_THRESHOLD,
+            base_freq: Self::BASE_FREQ,
             prior_map: None,
+            boost_map: None,
+            prior_floor: 0.0,
             verbose: false,
+            unknown_label: Self::UNKNOWN_LANG.to_string(),
+            record_trials: false,
+            arabic_markers: false,
+            devanagari_tiebreak: false,
+            script_prefilter: false,
+            scrubbing: TextScrubbing::default(),
+            normalization: Normalization::default(),
+            allowed: None,
+            disabled: Vec::new(),
+            stats: None,
+            appended_ngrams: Vec::new(),
+            appended_total: 0,
         }
     }
 
+    /// Creates a DetectorBuilder sharing already loaded profile data.
+    ///
+    /// Use the builder to tune the algorithm parameters before calling `build()`.
+    /// `DetectorFactory::detector_builder()` starts from the factory's settings instead.
+    ///
+    /// # Arguments
+    /// * `profile_data` - Shared per-language n-gram probabilities.
+    /// * `langlist` - Shared list of language identifiers, in column order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector::Detector;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let detector = Detector::builder(factory.profile_data.clone(), factory.langlist.clone())
+    ///     .with_alpha(0.3)
+    ///     .with_trials(3)
+    ///     .build();
+    /// assert_eq!(detector.n_trial, 3);
+    /// ```
+    pub fn builder(profile_data: Arc<ProfileData>, langlist: Arc<Vec<Lang>>) -> DetectorBuilder {
+        DetectorBuilder { detector: Self::from_shared(profile_data, langlist, None) }
+    }
+
     /// Appends text to the detector for analysis.
     ///
-    /// The text is preprocessed to remove URLs, emails, and normalize whitespace.
+    /// The text is preprocessed to remove URLs and emails (see `scrubbing`) and normalize whitespace.
     /// Vietnamese text is also normalized for better detection.
     ///
+    /// Any previous estimate is discarded, so text can be appended after a detection
+    /// and the next detection covers all text appended so far.
+    ///
     /// # Arguments
     /// * `text` - The text to append for language detection.
     ///
@@ -122,12 +672,10 @@ impl Detector {
     /// detector.append("Hello world!");
     /// ```
     pub fn append(&mut self, text: &str) {
-        // Remove URLs and emails (simple regex)
-        let url_re = regex::Regex::new(r"https?://[-_.?&~;+=/#0-9A-Za-z]{1,2076}").unwrap();
-        let mail_re = regex::Regex::new(r"[-_.0-9A-Za-z]{1,64}@[-_0-9A-Za-z]{1,255}[-_.0-9A-Za-z]{1,255}").unwrap();
-        let mut text = url_re.replace_all(text, " ").to_string();
-        text = mail_re.replace_all(&text, " ").to_string();
-        text = NGram::normalize_vi(&text);
+        let text = self.scrubbing.apply(text);
+        let text = replace_cow(text, NGram::normalize_vi);
+        let text = replace_cow(text, NGram::normalize_sea);
+        let text = replace_cow(text, normalize_arabic);
         let mut pre = ' ';
         for ch in text.chars().take(self.max_text_length) {
             if ch != ' ' || pre != ' ' {
@@ -135,190 +683,1196 @@ impl Detector {
             }
             pre = ch;
         }
+        self.langprob = None;
     }
 
-    /// Cleans the text by removing Latin characters if they are outnumbered by non-Latin characters.
+    /// Clears the appended text and the results of the last detection.
     ///
-    /// This helps improve detection accuracy for texts that mix scripts.
-    fn cleaning_text(&mut self) {
-        let mut latin_count = 0;
-        let mut non_latin_count = 0;
-        for ch in self.text.chars() {
-            if ('A'..='z').contains(&ch) {
-                latin_count += 1;
-            } else if ch >= '\u{0300}' {
-                if let Some(block) = crate::utils::unicode_block::unicode_block(ch) {
-                    if block != crate::utils::unicode_block::UNICODE_LATIN_EXTENDED_ADDITIONAL {
-                        non_latin_count += 1;
-                    }
-                }
-            }
-        }
-        if latin_count * 2 < non_latin_count {
-            let mut text_without_latin = String::new();
-            for ch in self.text.chars() {
-                if ch < 'A' || ch > 'z' {
-                    text_without_latin.push(ch);
-                }
-            }
-            self.text = text_without_latin;
-        }
+    /// Settings (alpha, priors, language filter, ...) are kept, so a single detector can be
+    /// reused for many inputs instead of creating one per text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// for text in ["Hello world!", "Bonjour le monde!"] {
+    ///     detector.reset();
+    ///     detector.append(text);
+    ///     println!("{}", detector.detect().unwrap());
+    /// }
+    /// ```
+    pub fn reset(&mut self) {
+        self.text.clear();
+        self.appended_ngrams.clear();
+        self.appended_total = 0;
+        self.langprob = None;
+        self.stats = None;
     }
 
-    /// Performs language detection on the accumulated text.
+    /// Appends already extracted n-grams, bypassing text preprocessing.
     ///
-    /// # Returns
-    /// The detected language code, or "unknown" if detection fails.
+    /// For callers which tokenize and normalize text upstream (e.g. search indexers) and
+    /// want to reuse the detection on their own features. The n-grams must be normalized
+    /// like the profiles (see `utils::ngram::NGram`), with spaces marking word boundaries
+    /// (" a", "ab "). N-grams unknown to the profiles are counted in the statistics only.
+    /// They are detected together with any appended text.
     ///
-    /// # Errors
-    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found.
+    /// # Arguments
+    /// * `ngrams` - The n-grams, one entry per occurrence.
     ///
     /// # Examples
     ///
     /// ```rust
     /// use langdetect_rs::detector_factory::DetectorFactory;
     ///
-    /// let factory = DetectorFactory::default().build();
+    /// let factory = DetectorFactory::default().with_seed(Some(0)).build();
     /// let mut detector = factory.create(None);
-    /// detector.append("Bonjour le monde!");
-    /// let language = detector.detect().unwrap();
-    /// assert_eq!(language, "fr");
+    /// detector.append_ngrams(["d", "de", "der", " d", " de", "r", "er", "er ", "i", "ie", "die"]);
+    /// assert_eq!(detector.detect().unwrap(), "de");
     /// ```
-    pub fn detect(&mut self) -> Result<String, DetectorError> {
-        let probabilities = self.get_probabilities()?;
-        if !probabilities.is_empty() {
-            Ok(probabilities[0].lang.clone().unwrap_or_else(|| Self::UNKNOWN_LANG.to_string()))
-        } else {
-            Ok(Self::UNKNOWN_LANG.to_string())
+    pub fn append_ngrams<I, S>(&mut self, ngrams: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        for ngram in ngrams {
+            self.appended_total += 1;
+            if let Some(id) = self.profile_data.ngram_id(ngram.as_ref()) {
+                self.appended_ngrams.push(id);
+            }
         }
+        self.langprob = None;
     }
 
-    /// Gets detailed language probabilities for the accumulated text.
+    /// Sets prior probabilities of languages by language code.
     ///
-    /// Returns all languages with probability above the threshold, sorted by probability descending.
+    /// Like `set_prior_map` of the Python langdetect, the probabilities are normalized to
+    /// sum up to 1.0 and languages missing from the map get a prior of zero.
     ///
-    /// # Returns
-    /// A vector of `Language` structs with language codes and probabilities.
+    /// # Arguments
+    /// * `priors` - Language code to (relative) prior probability mapping.
     ///
     /// # Errors
-    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found.
+    /// Returns `DetectorError::UnknownLanguage` for a language which is not loaded, and
+    /// `DetectorError::InvalidPrior` for a negative probability or if they sum up to zero.
     ///
     /// # Examples
     ///
     /// ```rust
+    /// use std::collections::HashMap;
     /// use langdetect_rs::detector_factory::DetectorFactory;
     ///
     /// let factory = DetectorFactory::default().build();
     /// let mut detector = factory.create(None);
-    /// detector.append("Hello world!");
-    /// let probabilities = detector.get_probabilities().unwrap();
-    /// for lang in probabilities {
-    ///     println!("{}: {:.3}", lang.lang.unwrap_or_default(), lang.prob);
-    /// }
+    /// detector.set_priors(HashMap::from([("en", 0.6), ("fr", 0.3), ("de", 0.1)])).unwrap();
+    /// assert!(detector.set_priors(HashMap::from([("xx", 1.0)])).is_err());
     /// ```
-    pub fn get_probabilities(&mut self) -> Result<Vec<Language>, DetectorError> {
-        if self.langprob.is_none() {
-            self.detect_block()?;
-        }
-        Ok(self.sort_probability(self.langprob.as_ref().unwrap()))
+    pub fn set_priors(&mut self, priors: HashMap<&str, f64>) -> Result<(), DetectorError> {
+        self.prior_map = Some(Self::prior_vector(&self.langlist, &priors)?);
+        self.langprob = None;
+        Ok(())
     }
 
-    /// Runs the core detection algorithm on the accumulated text.
+    /// Sets factors multiplying the final probability of languages by language code.
     ///
-    /// This method implements the expectation-maximization algorithm for language detection.
+    /// Languages missing from the map keep a factor of 1.0, see `DetectOptions::with_boost`.
     ///
-    /// # Returns
-    /// Ok(()) on successful detection, or an error if no features are found.
-    fn detect_block(&mut self) -> Result<(), DetectorError> {
-        self.cleaning_text();
-        let ngrams = self.extract_ngrams();
-        if ngrams.is_empty() {
-            return Err(DetectorError::NoFeatures);
-        }
-        self.langprob = Some(vec![0.0; self.langlist.len()]);
-        let mut rng = if let Some(seed) = self.seed {
-            StdRng::seed_from_u64(seed)
-        } else {
-            let mut thread_rng = rand::rng();
-            StdRng::from_rng(&mut thread_rng)
-        };
-        for _t in 0..self.n_trial {
-            let mut prob = self.init_probability();
-            let normal = Normal::new(0.0, 1.0).unwrap();
-            let alpha = self.alpha + normal.sample(&mut rng) * Self::ALPHA_WIDTH;
-            let mut i = 0;
-            loop {
-                let word = ngrams[rng.random_range(0..ngrams.len())].clone();
-                self.update_lang_prob(&mut prob, &word, alpha);
-                if i % 5 == 0 {
-                    if self.normalize_prob(&mut prob) > Self::CONV_THRESHOLD || i >= Self::ITERATION_LIMIT {
-                        break;
-                    }
-                }
-                i += 1;
-            }
-            for j in 0..self.langprob.as_ref().unwrap().len() {
-                self.langprob.as_mut().unwrap()[j] += prob[j] / self.n_trial as f64;
+    /// # Arguments
+    /// * `boosts` - Language code to factor mapping.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::UnknownLanguage` for a language which is not loaded, and
+    /// `DetectorError::InvalidBoost` for a negative or non-finite factor.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::collections::HashMap;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.set_boosts(HashMap::from([("en", 1.5)])).unwrap();
+    /// assert!(detector.set_boosts(HashMap::from([("en", -1.0)])).is_err());
+    /// ```
+    pub fn set_boosts(&mut self, boosts: HashMap<&str, f64>) -> Result<(), DetectorError> {
+        let mut boost = vec![1.0; self.langlist.len()];
+        for (&lang, &factor) in boosts.iter() {
+            let index = self.langlist.iter().position(|l| l == lang)
+                .ok_or_else(|| DetectorError::UnknownLanguage(lang.to_string()))?;
+            if !(factor >= 0.0 && factor.is_finite()) {
+                return Err(DetectorError::InvalidBoost(format!("factor of {} is {}", lang, factor)));
             }
+            boost[index] = factor;
         }
+        self.boost_map = Some(boost);
+        self.langprob = None;
         Ok(())
     }
 
-    /// Initializes probability estimates for the EM algorithm.
-    ///
-    /// Uses prior probabilities if available, otherwise uniform distribution.
-    fn init_probability(&self) -> Vec<f64> {
-        if let Some(ref prior) = self.prior_map {
-            prior.clone()
-        } else {
-            vec![1.0 / self.langlist.len() as f64; self.langlist.len()]
+    /// Validates priors and converts them into a normalized vector in `langlist` order.
+    pub(crate) fn prior_vector(langlist: &[Lang], priors: &HashMap<&str, f64>) -> Result<Vec<f64>, DetectorError> {
+        let mut prior = vec![0.0; langlist.len()];
+        for (&lang, &p) in priors.iter() {
+            let index = langlist.iter().position(|l| l == lang)
+                .ok_or_else(|| DetectorError::UnknownLanguage(lang.to_string()))?;
+            if !(p >= 0.0 && p.is_finite()) {
+                return Err(DetectorError::InvalidPrior(format!("probability of {} is {}", lang, p)));
+            }
+            prior[index] = p;
         }
+        let sum: f64 = prior.iter().sum();
+        if sum <= 0.0 {
+            return Err(DetectorError::InvalidPrior("probabilities sum up to zero".to_string()));
+        }
+        prior.iter_mut().for_each(|p| *p /= sum);
+        Ok(prior)
     }
 
-    /// Extracts n-grams from the text for language detection.
+    /// Restricts detection to a subset of the loaded languages.
     ///
-    /// Only includes n-grams that exist in the language profiles.
-    fn extract_ngrams(&self) -> Vec<String> {
-        let range = 1..=NGram::N_GRAM;
-        let mut result = Vec::new();
-        let mut ngram = NGram::new();
-        for ch in self.text.chars() {
-            ngram.add_char(ch);
-            if ngram.capitalword {
-                continue;
-            }
-            for n in range.clone() {
-                if ngram.grams.len() < n {
-                    break;
-                }
-                let w: String = ngram.grams.chars().rev().take(n).collect::<Vec<_>>().into_iter().rev().collect();
-                if !w.is_empty() && w != " " && self.word_lang_prob_map.contains_key(&w) {
-                    result.push(w);
-                }
-            }
-        }
-        result
+    /// The other languages keep a probability of zero, so they are never reported.
+    /// Codes of languages which are not loaded are ignored.
+    ///
+    /// # Arguments
+    /// * `langs` - The languages which can be detected, as `Lang` values or codes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::language::Lang;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.restrict_languages(&[Lang::En, Lang::De, Lang::Fr]);
+    /// detector.append("Bonjour le monde!");
+    /// assert_eq!(detector.detect().unwrap(), "fr");
+    /// detector.restrict_languages(&["en", "de"]);
+    /// assert_ne!(detector.detect().unwrap(), "fr");
+    /// ```
+    pub fn restrict_languages<L: Into<Lang> + Clone>(&mut self, langs: &[L]) {
+        let langs = langs.iter().cloned().map(Into::into).collect();
+        self.set_language_filter(Some(&LanguageFilter::Allow(langs)));
+    }
+
+    /// Excludes some of the loaded languages from detection.
+    ///
+    /// # Arguments
+    /// * `langs` - The languages which can not be detected, as `Lang` values or codes.
+    pub fn exclude_languages<L: Into<Lang> + Clone>(&mut self, langs: &[L]) {
+        let langs = langs.iter().cloned().map(Into::into).collect();
+        self.set_language_filter(Some(&LanguageFilter::Deny(langs)));
+    }
+
+    /// Replaces the language filter; `None` makes all loaded languages detectable again.
+    ///
+    /// Languages disabled with `DetectorFactory::set_language_enabled` stay masked.
+    /// Any previous estimate is discarded, so the next detection uses the new filter.
+    ///
+    /// # Arguments
+    /// * `filter` - The language filter to apply.
+    pub fn set_language_filter(&mut self, filter: Option<&LanguageFilter>) {
+        self.allowed = if filter.is_none() && self.disabled.is_empty() {
+            None
+        } else {
+            Some(self.langlist.iter().map(|lang| {
+                filter.is_none_or(|filter| filter.allows(lang)) && !self.disabled.contains(lang)
+            }).collect())
+        };
+        self.langprob = None;
+    }
+
+    /// Sets the languages disabled in the factory; takes effect with the next `set_language_filter`.
+    pub(crate) fn set_disabled_languages(&mut self, langs: &[Lang]) {
+        self.disabled = langs.to_vec();
+    }
+
+    /// Returns the script most letters of the appended text are written in.
+    ///
+    /// Needs no profiles and no EM run, so it is a cheap pre-filter, e.g. to pick the
+    /// script passed to `restrict_to_script` or to route text to a dedicated detector.
+    ///
+    /// # Returns
+    /// The dominant script, or None if the text contains no letters of a known script.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::utils::script::Script;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Добрый день, Bob");
+    /// assert_eq!(detector.detect_script(), Some(Script::Cyrillic));
+    /// assert_eq!(detector.detect_script().unwrap().iso15924(), "Cyrl");
+    /// ```
+    pub fn detect_script(&self) -> Option<Script> {
+        dominant_script(&self.text)
+    }
+
+    /// Narrows the detectable languages down to those written in a script.
+    ///
+    /// Combines with the language filter: only languages allowed by both can be detected.
+    /// The scripts of a language are derived from its profile, see
+    /// `ProfileData::language_scripts`. Any previous estimate is discarded.
+    ///
+    /// # Arguments
+    /// * `script` - The script the text is known to be written in.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::utils::script::Script;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.restrict_to_script(Script::Cyrillic);
+    /// detector.append("Добрый день");
+    /// assert_eq!(detector.detect().unwrap(), "ru");
+    /// ```
+    pub fn restrict_to_script(&mut self, script: Script) {
+        let allowed = (0..self.langlist.len()).map(|index| {
+            self.allowed.as_ref().is_none_or(|allowed| allowed[index])
+                && self.profile_data.language_scripts(index).contains(&script)
+        }).collect();
+        self.allowed = Some(allowed);
+        self.langprob = None;
+    }
+
+    /// Returns the text the model consumes, after preprocessing.
+    ///
+    /// This is the appended text with URLs and emails removed, Vietnamese normalized
+    /// and whitespace collapsed, truncated to `max_text_length` characters.
+    /// Useful for debugging surprising results.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Visit   https://example.com or mail me@example.com");
+    /// assert_eq!(detector.preprocessed_text(), "Visit or mail ");
+    /// ```
+    pub fn preprocessed_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the text with Latin characters removed if they are outnumbered by non-Latin characters.
+    ///
+    /// This helps improve detection accuracy for texts that mix scripts. The appended text
+    /// itself is kept, so text appended later is weighed against all of it.
+    fn cleaning_text(&self) -> Cow<'_, str> {
+        let mut latin_count = 0;
+        let mut non_latin_count = 0;
+        for ch in self.text.chars() {
+            if ('A'..='z').contains(&ch) {
+                latin_count += 1;
+            } else if ch >= '\u{0300}'
+                && let Some(block) = crate::utils::unicode_block::unicode_block(ch)
+                && block != crate::utils::unicode_block::UNICODE_LATIN_EXTENDED_ADDITIONAL
+            {
+                non_latin_count += 1;
+            }
+        }
+        if latin_count * 2 < non_latin_count {
+            Cow::Owned(self.text.chars().filter(|ch| !('A'..='z').contains(ch)).collect())
+        } else {
+            Cow::Borrowed(&self.text)
+        }
+    }
+
+    /// Performs language detection on the accumulated text.
+    ///
+    /// # Returns
+    /// The detected language code, or `unknown_label` ("unknown" by default) if detection fails.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Bonjour le monde!");
+    /// let language = detector.detect().unwrap();
+    /// assert_eq!(language, "fr");
+    /// ```
+    pub fn detect(&mut self) -> Result<String, DetectorError> {
+        let language = self.detect_lang()?;
+        Ok(language.lang.map(String::from).unwrap_or_else(|| self.unknown_label.clone()))
+    }
+
+    /// Performs language detection and returns the winning language together with its probability.
+    ///
+    /// The top language is picked directly from the probability estimates, so unlike
+    /// `get_probabilities()` no sorting or cloning of the whole distribution is done.
+    ///
+    /// # Returns
+    /// The most probable `Language`, or a `Language` with `lang: None` if no language
+    /// exceeds the probability threshold.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Bonjour le monde!");
+    /// let language = detector.detect_lang().unwrap();
+    /// assert_eq!(language.lang.as_deref(), Some("fr"));
+    /// assert!(language.prob > 0.5);
+    /// ```
+    pub fn detect_lang(&mut self) -> Result<Language, DetectorError> {
+        if self.langprob.is_none() {
+            self.detect_block()?;
+        }
+        let prob = self.langprob.as_ref().unwrap();
+        let best = prob.iter().enumerate()
+            .filter(|(_, p)| **p > self.prob_threshold)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b));
+        match best {
+            Some((index, &p)) => Ok(Language::new(Some(self.langlist[index].clone()), p)),
+            None => Ok(Language::new(None, 0.0)),
+        }
+    }
+
+    /// Performs language detection and returns the winning language with reliability signals.
+    ///
+    /// # Returns
+    /// The most probable language, its margin over the runner-up, the entropy of the
+    /// distribution and the n-gram coverage of the text, see `DetectionResult`.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().with_seed(Some(0)).build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Le chat dort sur le canapé du salon pendant que nous mangeons.");
+    /// let result = detector.detect_with_confidence().unwrap();
+    /// assert_eq!(result.language.lang.as_deref(), Some("fr"));
+    /// assert!(result.is_reliable());
+    /// ```
+    pub fn detect_with_confidence(&mut self) -> Result<DetectionResult, DetectorError> {
+        let probabilities = self.get_probabilities_full()?;
+        let language = self.detect_lang()?;
+        let top = probabilities.first().map_or(0.0, |l| l.prob);
+        let runner_up = probabilities.get(1).map_or(0.0, |l| l.prob);
+        Ok(DetectionResult {
+            language,
+            margin: top - runner_up,
+            entropy: Language::entropy(&probabilities),
+            coverage: self.stats.as_ref().map_or(0.0, DetectionStats::coverage),
+        })
+    }
+
+    /// Performs language detection, describing the text instead of failing if it has no n-grams.
+    ///
+    /// Emoji-only messages, numbers or text in a script without a profile make `detect_lang`
+    /// fail with `DetectorError::NoFeatures`. This method returns a `FallbackResult` for them
+    /// instead, with the reason, the dominant script and a guess if exactly one detectable
+    /// language is written in that script.
+    ///
+    /// # Returns
+    /// `Detection::Language` as returned by `detect_lang`, or `Detection::Fallback`.
+    ///
+    /// # Errors
+    /// Returns the errors of `detect_lang` other than `DetectorError::NoFeatures`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector::{Detection, FallbackReason};
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("👍 2024");
+    /// match detector.detect_with_fallback().unwrap() {
+    ///     Detection::Fallback(fallback) => assert_eq!(fallback.reason, FallbackReason::NoLetters),
+    ///     Detection::Language(language) => panic!("detected {}", language),
+    /// }
+    /// ```
+    pub fn detect_with_fallback(&mut self) -> Result<Detection, DetectorError> {
+        match self.detect_lang() {
+            Ok(language) => Ok(Detection::Language(language)),
+            Err(DetectorError::NoFeatures) => Ok(Detection::Fallback(self.fallback())),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Describes the appended text for `detect_with_fallback`.
+    fn fallback(&self) -> FallbackResult {
+        let reason = if self.text.trim().is_empty() {
+            FallbackReason::EmptyText
+        } else if !self.text.chars().any(char::is_alphabetic) {
+            FallbackReason::NoLetters
+        } else {
+            FallbackReason::UnknownNGrams
+        };
+        let script = self.detect_script();
+        let mut writers = (0..self.langlist.len()).filter(|&index| {
+            script.is_some_and(|script| self.profile_data.language_scripts(index).contains(&script))
+                && self.allowed.as_ref().is_none_or(|allowed| allowed[index])
+        });
+        let guess = match (writers.next(), writers.next()) {
+            (Some(index), None) => Some(self.langlist[index].clone()),
+            _ => None,
+        };
+        FallbackResult { reason, script, guess }
+    }
+
+    /// Consumes text chunks until the detection confidence exceeds a threshold.
+    ///
+    /// The language is re-estimated at checkpoints: after the first chunk with detectable
+    /// n-grams, then whenever the appended text has doubled since the last estimate, so the
+    /// total work stays linear in the length of the text. The method returns as soon as the
+    /// top language of an estimate reaches `confidence`, so the remaining chunks are not read.
+    /// Pass `iter.by_ref()` to keep using the iterator afterwards.
+    ///
+    /// # Arguments
+    /// * `chunks` - Text chunks, e.g. lines of a large file.
+    /// * `confidence` - Probability the top language must reach to stop early.
+    ///
+    /// # Returns
+    /// The first estimate reaching `confidence`, or the estimate for all chunks otherwise.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found in any chunk.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// let mut lines = ["Bonjour tout le monde.", "Comment allez-vous ?", "Très bien, merci."].into_iter();
+    /// let language = detector.detect_until(lines.by_ref(), 0.99).unwrap();
+    /// assert_eq!(language.lang.as_deref(), Some("fr"));
+    /// println!("{} lines left unread", lines.count());
+    /// ```
+    pub fn detect_until<I, S>(&mut self, chunks: I, confidence: f64) -> Result<Language, DetectorError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut checkpoint = 0;
+        for chunk in chunks {
+            self.append(chunk.as_ref());
+            // append() drops leading spaces, so chunks are separated here
+            if !self.text.is_empty() && !self.text.ends_with(' ') {
+                self.text.push(' ');
+            }
+            if self.text.len() < checkpoint {
+                continue;
+            }
+            match self.detect_lang() {
+                Ok(language) if language.prob >= confidence => return Ok(language),
+                Ok(_) => checkpoint = self.text.len() * 2,
+                Err(_) => continue,
+            }
+        }
+        // Reuses the last estimate unless chunks were appended after it
+        self.detect_lang()
+    }
+
+    /// Gets detailed language probabilities for the accumulated text.
+    ///
+    /// Returns all languages with probability above the threshold, sorted by probability descending.
+    ///
+    /// # Returns
+    /// A vector of `Language` structs with language codes and probabilities.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Hello world!");
+    /// let probabilities = detector.get_probabilities().unwrap();
+    /// for lang in probabilities {
+    ///     println!("{}: {:.3}", lang.lang.as_deref().unwrap_or_default(), lang.prob);
+    /// }
+    /// ```
+    pub fn get_probabilities(&mut self) -> Result<Vec<Language>, DetectorError> {
+        if self.langprob.is_none() {
+            self.detect_block()?;
+        }
+        Ok(self.sort_probability(self.langprob.as_ref().unwrap(), Some(self.prob_threshold)))
+    }
+
+    /// Gets the probabilities of all loaded languages for the accumulated text.
+    ///
+    /// Unlike `get_probabilities()` no language is dropped, so the result is a complete
+    /// distribution (summing to 1.0) for calibration and debugging.
+    ///
+    /// # Returns
+    /// One `Language` per loaded language, sorted by probability descending.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Hello world!");
+    /// let probabilities = detector.get_probabilities_full().unwrap();
+    /// assert_eq!(probabilities.len(), factory.get_lang_list().len());
+    /// ```
+    pub fn get_probabilities_full(&mut self) -> Result<Vec<Language>, DetectorError> {
+        if self.langprob.is_none() {
+            self.detect_block()?;
+        }
+        Ok(self.sort_probability(self.langprob.as_ref().unwrap(), None))
+    }
+
+    /// Gets the `k` most probable languages for the accumulated text, regardless of the
+    /// probability threshold.
+    ///
+    /// # Arguments
+    /// * `k` - Maximum number of languages to return.
+    ///
+    /// # Returns
+    /// Up to `k` languages, sorted by probability descending.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Hello world!");
+    /// assert_eq!(detector.top_k(3).unwrap().len(), 3);
+    /// ```
+    pub fn top_k(&mut self, k: usize) -> Result<Vec<Language>, DetectorError> {
+        let mut probabilities = self.get_probabilities_full()?;
+        probabilities.truncate(k);
+        Ok(probabilities)
+    }
+
+    /// Gets the languages which are almost as probable as the detected one.
+    ///
+    /// Intended for "Did you mean this was in X?" override suggestions in user interfaces.
+    ///
+    /// # Arguments
+    /// * `max_gap` - Maximum probability difference to the winner (e.g. 0.3).
+    ///
+    /// # Returns
+    /// The languages other than the winner with a non-zero probability of at least
+    /// the winner's minus `max_gap`, sorted by probability descending.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Dit is een kort zinnetje");
+    /// for lang in detector.alternatives(0.5).unwrap() {
+    ///     println!("Did you mean {}?", lang.lang.as_deref().unwrap_or_default());
+    /// }
+    /// ```
+    pub fn alternatives(&mut self, max_gap: f64) -> Result<Vec<Language>, DetectorError> {
+        let mut probabilities = self.get_probabilities_full()?.into_iter();
+        let Some(winner) = probabilities.next() else {
+            return Ok(Vec::new());
+        };
+        Ok(probabilities.take_while(|lang| lang.prob > 0.0 && winner.prob - lang.prob <= max_gap).collect())
+    }
+
+    /// Explains the detection by the n-grams which favored each of the most probable languages.
+    ///
+    /// An n-gram occurring `count` times scores `count * ln((w + p) / (w + q))` for a language,
+    /// where `p` is its probability in the language's profile, `q` the mean probability in the
+    /// other explained languages (all other languages if only one is explained) and `w` the
+    /// smoothing weight `alpha / base_freq`. Explaining two confused languages (e.g. Ukrainian
+    /// and Russian) thus shows the n-grams telling them apart.
+    ///
+    /// # Arguments
+    /// * `top_k` - Number of most probable languages to explain.
+    /// * `top_n` - Maximum number of n-grams reported per language.
+    ///
+    /// # Returns
+    /// One explanation per language, most probable first.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::NoFeatures` if no detectable n-grams are found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().with_seed(Some(0)).build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Привіт, як справи?");
+    /// for explanation in detector.explain(2, 5).unwrap() {
+    ///     let ngrams: Vec<&str> = explanation.ngrams.iter().map(|c| c.ngram.as_str()).collect();
+    ///     println!("{}: {:?}", explanation.language, ngrams);
+    /// }
+    /// ```
+    pub fn explain(&mut self, top_k: usize, top_n: usize) -> Result<Vec<LanguageExplanation>, DetectorError> {
+        let candidates = self.top_k(top_k)?;
+        let indexes: Vec<usize> = candidates.iter()
+            .filter_map(|language| self.langlist.iter().position(|lang| Some(lang) == language.lang.as_ref()))
+            .collect();
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+        for id in self.extract_ngrams().0 {
+            *counts.entry(id).or_insert(0) += 1;
+        }
+        let weight = self.alpha / self.base_freq;
+        let explanations = candidates.into_iter().zip(indexes.iter()).map(|(language, &index)| {
+            let others: Vec<usize> = if indexes.len() > 1 {
+                indexes.iter().copied().filter(|i| *i != index).collect()
+            } else {
+                (0..self.langlist.len()).filter(|i| *i != index).collect()
+            };
+            let mut ngrams: Vec<NGramContribution> = counts.iter().filter_map(|(&id, &count)| {
+                let p = self.profile_data.probability(id, index);
+                let q = others.iter().map(|&i| self.profile_data.probability(id, i)).sum::<f64>() / others.len().max(1) as f64;
+                let score = count as f64 * ((weight + p) / (weight + q)).ln();
+                (score > 0.0).then(|| NGramContribution {
+                    ngram: self.profile_data.ngram(id).unwrap_or_default().to_string(),
+                    count,
+                    score,
+                    probabilities: indexes.iter()
+                        .map(|&i| Language::new(Some(self.langlist[i].clone()), self.profile_data.probability(id, i)))
+                        .collect(),
+                })
+            }).collect();
+            ngrams.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.ngram.cmp(&b.ngram)));
+            ngrams.truncate(top_n);
+            LanguageExplanation { language, ngrams }
+        }).collect();
+        Ok(explanations)
+    }
+
+    /// Decides whether the appended text is written in a language.
+    ///
+    /// A one-vs-rest decision: the text matches if the probability of the language reaches
+    /// `verify_threshold`, independently of `prob_threshold`. Useful to filter a crawl down
+    /// to a single language.
+    ///
+    /// # Arguments
+    /// * `lang` - The language, as a `Lang` value or code.
+    ///
+    /// # Returns
+    /// The probability of the language, whether it matches and its margin over the most
+    /// probable other language.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::UnknownLanguage` if the language is not loaded, and
+    /// `DetectorError::NoFeatures` if no detectable n-grams are found.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().with_seed(Some(0)).build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("Das Wetter ist heute sehr schön.");
+    /// assert!(detector.verify("de").unwrap().is_match);
+    /// assert!(!detector.verify("nl").unwrap().is_match);
+    /// ```
+    pub fn verify(&mut self, lang: impl Into<Lang>) -> Result<VerificationResult, DetectorError> {
+        let lang = lang.into();
+        let index = self.langlist.iter().position(|l| *l == lang)
+            .ok_or_else(|| DetectorError::UnknownLanguage(lang.to_string()))?;
+        if self.langprob.is_none() {
+            self.detect_block()?;
+        }
+        let langprob = self.langprob.as_ref().unwrap();
+        let prob = langprob[index];
+        let best_other = langprob.iter().enumerate().filter(|(i, _)| *i != index).map(|(_, p)| *p).fold(0.0, f64::max);
+        Ok(VerificationResult { prob, is_match: prob >= self.verify_threshold, margin: prob - best_other })
     }
 
-    /// Updates language probabilities based on an n-gram observation.
+    /// Scores the text against a few languages only, e.g. to verify that it is English.
+    ///
+    /// Unlike detection no EM trials are run over all languages: the log-likelihood of the
+    /// text's n-grams is summed for the requested languages only (smoothed like the EM by
+    /// `alpha / base_freq`) and turned into probabilities among them. The result is
+    /// deterministic. N-grams unknown to all requested languages are skipped.
     ///
     /// # Arguments
-    /// * `prob` - Current probability estimates (modified in-place).
-    /// * `word` - The n-gram to use for updating.
-    /// * `alpha` - Smoothing parameter.
+    /// * `langs` - The languages to score, as `Lang` values or codes.
+    ///
+    /// # Returns
+    /// The requested languages with their probability among them, sorted by probability descending.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::UnknownLanguage` if a language is not loaded, and
+    /// `DetectorError::NoFeatures` if no n-gram of the text is known to the requested languages.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::language::Lang;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.append("The weather is nice today");
+    /// let scores = detector.score_languages(&["en", "fr", "de"]).unwrap();
+    /// assert_eq!(scores[0].0, Lang::En);
+    /// ```
+    pub fn score_languages<L: Into<Lang> + Clone>(&mut self, langs: &[L]) -> Result<Vec<(Lang, f64)>, DetectorError> {
+        let mut indexes = Vec::with_capacity(langs.len());
+        for lang in langs {
+            let lang: Lang = lang.clone().into();
+            let index = self.langlist.iter().position(|l| *l == lang)
+                .ok_or_else(|| DetectorError::UnknownLanguage(lang.to_string()))?;
+            indexes.push(index);
+        }
+        let weight = self.alpha / self.base_freq;
+        let mut log_likelihoods = vec![0.0; indexes.len()];
+        let mut observed = false;
+        for id in self.extract_ngrams().0 {
+            if indexes.iter().all(|&i| self.profile_data.probability(id, i) == 0.0) {
+                continue;
+            }
+            observed = true;
+            for (log_likelihood, &i) in log_likelihoods.iter_mut().zip(indexes.iter()) {
+                *log_likelihood += (weight + self.profile_data.probability(id, i)).ln();
+            }
+        }
+        if !observed {
+            return Err(DetectorError::NoFeatures);
+        }
+        let max = log_likelihoods.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let sum: f64 = log_likelihoods.iter().map(|l| (l - max).exp()).sum();
+        let mut scores: Vec<(Lang, f64)> = indexes.iter().zip(log_likelihoods.iter())
+            .map(|(&i, l)| (self.langlist[i].clone(), (l - max).exp() / sum))
+            .collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+        Ok(scores)
+    }
+
+    /// Returns statistics collected during the last detection run.
+    ///
+    /// Per-trial distributions are only present when `record_trials` was enabled
+    /// before detection. The n-gram coverage is also reported when detection failed
+    /// with `DetectorError::NoFeatures`.
+    ///
+    /// # Returns
+    /// `None` if no detection has been run yet.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.create(None);
+    /// detector.record_trials = true;
+    /// detector.append("Hello world!");
+    /// detector.detect().unwrap();
+    /// let stats = detector.last_stats().unwrap();
+    /// for trial in stats.trials.iter() {
+    ///     println!("alpha={:.4} iterations={} probabilities={:?}", trial.alpha, trial.iterations, trial.probabilities);
+    /// }
+    /// println!("max spread between trials: {:.3}", stats.max_trial_spread());
+    /// println!("{} trials hit the iteration limit", stats.unconverged_trials);
+    /// println!("{} of {} n-grams known", stats.matched_ngrams, stats.total_ngrams);
+    /// ```
+    pub fn last_stats(&self) -> Option<&DetectionStats> {
+        self.stats.as_ref()
+    }
+
+    /// Runs the core detection algorithm on the accumulated text.
+    ///
+    /// This method implements the expectation-maximization algorithm for language detection.
+    ///
+    /// # Returns
+    /// Ok(()) on successful detection, or an error if no features are found.
+    fn detect_block(&mut self) -> Result<(), DetectorError> {
+        let (ngram_ids, total_ngrams) = self.extract_ngrams();
+        self.stats = Some(DetectionStats { matched_ngrams: ngram_ids.len(), total_ngrams, ..DetectionStats::default() });
+        if ngram_ids.is_empty() {
+            return Err(DetectorError::NoFeatures);
+        }
+        let transliteration = self.transliteration_signal(&ngram_ids);
+        // Excluded languages keep a zero probability, so the EM only updates the columns of the others
+        let columns: Option<Vec<usize>> = self.candidate_languages()
+            .map(|allowed| (0..allowed.len()).filter(|&i| allowed[i]).collect());
+        // Without a restriction the EM reads the rows of the profile matrix directly. Otherwise
+        // (or for a mapped table) the row of every distinct n-gram is gathered once into one
+        // contiguous matrix
+        let direct = columns.is_none() && !self.profile_data.is_mapped();
+        let mut row_index: HashMap<usize, Option<RowRef>> = HashMap::new();
+        let mut rows = ProfileMatrix::new(columns.as_ref().map_or(self.profile_data.lang_count(), Vec::len));
+        let mut ngrams: Vec<RowRef> = ngram_ids.iter().filter_map(|&id| {
+            if direct {
+                return Some(RowRef::Profile(id));
+            }
+            *row_index.entry(id).or_insert_with(|| {
+                let row = self.profile_data.push_row(id, columns.as_deref(), &mut rows);
+                // N-grams known to filtered out languages only carry no evidence
+                if columns.is_some() && rows.row(row).iter().all(|p| *p == 0.0) {
+                    rows.pop_row();
+                    return None;
+                }
+                Some(RowRef::Gathered(row))
+            })
+        }).collect();
+        if ngrams.is_empty() {
+            return Err(DetectorError::NoFeatures);
+        }
+        if self.arabic_markers {
+            self.add_marker_rows(&mut rows, &mut ngrams, columns.as_deref());
+        }
+        let mut initial = self.init_probability(columns.as_deref())?;
+        if let Some(columns) = &columns {
+            initial = columns.iter().map(|&i| initial[i]).collect();
+        }
+        let mut langprob = vec![0.0; self.langlist.len()];
+        let mut stats = DetectionStats { transliteration, matched_ngrams: ngram_ids.len(), total_ngrams, ..DetectionStats::default() };
+        let mut rng = if let Some(seed) = self.seed {
+            StdRng::seed_from_u64(seed)
+        } else {
+            let mut thread_rng = rand::rng();
+            StdRng::from_rng(&mut thread_rng)
+        };
+        for trial in 0..self.n_trial {
+            let mut prob = initial.clone();
+            let normal = Normal::new(0.0, 1.0).unwrap();
+            let alpha = self.alpha + normal.sample(&mut rng) * Self::ALPHA_WIDTH;
+            let mut i = 0;
+            let converged = loop {
+                let row = match ngrams[rng.random_range(0..ngrams.len())] {
+                    RowRef::Profile(id) => self.profile_data.row_slice(id).unwrap_or_default(),
+                    RowRef::Gathered(index) => rows.row(index),
+                };
+                self.update_lang_prob(&mut prob, row, alpha);
+                if i % 5 == 0 {
+                    let maxp = self.normalize_prob(&mut prob);
+                    self.log_iteration(trial, i, maxp);
+                    if maxp > self.conv_threshold {
+                        break true;
+                    }
+                    if i >= self.iteration_limit {
+                        break false;
+                    }
+                }
+                i += 1;
+            };
+            if !converged {
+                stats.unconverged_trials += 1;
+            }
+            if let Some(columns) = &columns {
+                let mut full = vec![0.0; self.langlist.len()];
+                columns.iter().zip(prob.iter()).for_each(|(&i, &p)| full[i] = p);
+                prob = full;
+            }
+            self.log_trial(trial, alpha, i + 1, converged, &prob);
+            for (total, p) in langprob.iter_mut().zip(prob.iter()) {
+                *total += p / self.n_trial as f64;
+            }
+            if self.record_trials {
+                stats.trials.push(TrialStats { alpha, probabilities: prob, iterations: i + 1, converged });
+            }
+        }
+        if self.devanagari_tiebreak {
+            self.break_devanagari_tie(&mut langprob);
+        }
+        if let Some(boost) = &self.boost_map {
+            Self::apply_boost(&mut langprob, boost)?;
+        }
+        self.log_result(&langprob);
+        self.langprob = Some(langprob);
+        self.stats = Some(stats);
+        Ok(())
+    }
+
+    /// Logs a convergence check of an EM trial at trace level, if `verbose` is enabled.
+    #[cfg_attr(not(feature = "log"), allow(unused_variables))]
+    fn log_iteration(&self, trial: usize, iteration: usize, maxp: f64) {
+        #[cfg(feature = "log")]
+        if self.verbose {
+            log::trace!("EM trial {} iteration {}: top probability {:.6}", trial, iteration, maxp);
+        }
+    }
+
+    /// Logs the outcome of an EM trial at debug level, if `verbose` is enabled.
+    #[cfg_attr(not(feature = "log"), allow(unused_variables))]
+    fn log_trial(&self, trial: usize, alpha: f64, iterations: usize, converged: bool, prob: &[f64]) {
+        #[cfg(feature = "log")]
+        if self.verbose {
+            log::debug!(
+                "EM trial {}: alpha {:.4}, {} iterations, {}, {}",
+                trial, alpha, iterations, if converged { "converged" } else { "hit the iteration limit" }, self.format_probabilities(prob)
+            );
+        }
+    }
+
+    /// Logs the averaged probabilities of a detection at debug level, if `verbose` is enabled.
+    #[cfg_attr(not(feature = "log"), allow(unused_variables))]
+    fn log_result(&self, langprob: &[f64]) {
+        #[cfg(feature = "log")]
+        if self.verbose {
+            log::debug!("EM result over {} trials: {}", self.n_trial, self.format_probabilities(langprob));
+        }
+    }
+
+    /// Formats the non-zero probabilities as `lang:prob` pairs, most probable first.
+    #[cfg(feature = "log")]
+    fn format_probabilities(&self, prob: &[f64]) -> String {
+        let pairs: Vec<String> = self.sort_probability(prob, Some(0.0)).iter()
+            .map(|language| format!("{}:{:.4}", language.lang.as_deref().unwrap_or(Self::UNKNOWN_LANG), language.prob))
+            .collect();
+        pairs.join(" ")
+    }
+
+    /// Multiplies the probabilities by boost factors and renormalizes them.
+    ///
+    /// # Arguments
+    /// * `langprob` - Probabilities of the languages (modified in-place).
+    /// * `boost` - Factor of every language, in `langlist` order.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::InvalidBoost` if the factors do not match the languages or
+    /// one of them is negative or not finite.
+    fn apply_boost(langprob: &mut [f64], boost: &[f64]) -> Result<(), DetectorError> {
+        if boost.len() != langprob.len() {
+            return Err(DetectorError::InvalidBoost(format!("{} factors for {} languages", boost.len(), langprob.len())));
+        }
+        if let Some(factor) = boost.iter().find(|f| !(**f >= 0.0 && f.is_finite())) {
+            return Err(DetectorError::InvalidBoost(format!("factor {} is not a non-negative number", factor)));
+        }
+        let boosted: Vec<f64> = langprob.iter().zip(boost.iter()).map(|(p, f)| p * f).collect();
+        let sum: f64 = boosted.iter().sum();
+        // All evidence boosted away: keep the unboosted estimate
+        if sum > 0.0 {
+            langprob.iter_mut().zip(boosted).for_each(|(p, b)| *p = b / sum);
+        }
+        Ok(())
+    }
+
+    /// Lets function words decide when the two most probable languages are Devanagari languages.
+    ///
+    /// The language with the most function words swaps its probability with the most
+    /// probable language, so the distribution keeps its shape.
+    ///
+    /// # Arguments
+    /// * `langprob` - Probabilities of the languages (modified in-place).
+    fn break_devanagari_tie(&self, langprob: &mut [f64]) {
+        let mut order: Vec<usize> = (0..langprob.len()).collect();
+        order.sort_by(|a, b| langprob[*b].total_cmp(&langprob[*a]));
+        let is_candidate = |index: &usize| DEVANAGARI_LANGUAGES.contains(&self.langlist[*index].as_str());
+        if order.len() < 2 || !order[..2].iter().all(is_candidate) {
+            return;
+        }
+        let Some(preferred) = preferred_language(&self.text) else {
+            return;
+        };
+        if let Some(index) = self.langlist.iter().position(|lang| lang == preferred)
+            && self.allowed.as_ref().is_none_or(|allowed| allowed[index])
+        {
+            langprob.swap(order[0], index);
+        }
+    }
+
+    /// Adds an observation for every Arabic-script marker letter of the text.
+    ///
+    /// # Arguments
+    /// * `rows` - Probability rows of the observations, extended by one row per distinct marker.
+    /// * `ngrams` - Rows of the observations, extended by one row per marker occurrence.
+    /// * `columns` - Indices of the candidate languages the rows are restricted to, None for all languages.
+    fn add_marker_rows(&self, rows: &mut ProfileMatrix, ngrams: &mut Vec<RowRef>, columns: Option<&[usize]>) {
+        let mut marker_index: HashMap<char, usize> = HashMap::new();
+        for ch in self.text.chars() {
+            let Some(writers) = marker_writers(ch) else {
+                continue;
+            };
+            let index = *marker_index.entry(ch).or_insert_with(|| {
+                let row = marker_row(writers, &self.langlist);
+                match columns {
+                    Some(columns) => rows.push_row(columns.iter().map(|&i| row[i])),
+                    None => rows.push_row(row),
+                }
+            });
+            ngrams.push(RowRef::Gathered(index));
+        }
+    }
+
+    /// Compares how well romanized companion profiles and regular profiles cover the n-grams.
     ///
     /// # Returns
-    /// true if the n-gram was found in profiles, false otherwise.
-    fn update_lang_prob(&self, prob: &mut [f64], word: &str, alpha: f64) -> bool {
-        if !self.word_lang_prob_map.contains_key(word) {
-            return false;
+    /// None unless the text is mostly Latin and companion profiles are loaded.
+    fn transliteration_signal(&self, ngram_ids: &[usize]) -> Option<TransliterationSignal> {
+        let companions: Vec<bool> = self.langlist.iter().map(|lang| lang.ends_with(Self::ROMANIZED_SUFFIX)).collect();
+        if !companions.contains(&true) || dominant_script(&self.text) != Some(Script::Latin) {
+            return None;
         }
-        let lang_prob_map = &self.word_lang_prob_map[word];
-        let weight = alpha / Self::BASE_FREQ;
-        for i in 0..prob.len() {
-            prob[i] *= weight + lang_prob_map[i];
+        let coverage = |index: usize| {
+            let covered = ngram_ids.iter().filter(|&&id| self.profile_data.probability(id, index) > 0.0).count();
+            covered as f64 / ngram_ids.len() as f64
+        };
+        let mut best_romanized: Option<(usize, f64)> = None;
+        let mut native_coverage: f64 = 0.0;
+        for (index, &companion) in companions.iter().enumerate() {
+            let c = coverage(index);
+            if !companion {
+                native_coverage = native_coverage.max(c);
+            } else if best_romanized.is_none_or(|(_, best)| c > best) {
+                best_romanized = Some((index, c));
+            }
+        }
+        let (index, romanized_coverage) = best_romanized?;
+        Some(TransliterationSignal {
+            likely: romanized_coverage > native_coverage + Self::TRANSLITERATION_MARGIN,
+            romanized_lang: self.langlist[index].clone(),
+            romanized_coverage,
+            native_coverage,
+        })
+    }
+
+    /// Returns the per-language flags of the languages the text can be detected as.
+    ///
+    /// Combines the language filter with the script pre-filter. The pre-filter is skipped
+    /// if no candidate language uses the dominant script of the text.
+    ///
+    /// # Returns
+    /// None if every language is a candidate.
+    fn candidate_languages(&self) -> Option<Vec<bool>> {
+        let script = self.script_prefilter.then(|| dominant_script(&self.text)).flatten();
+        let Some(script) = script else {
+            return self.allowed.clone();
+        };
+        let allowed: Vec<bool> = (0..self.langlist.len()).map(|index| {
+            self.allowed.as_ref().is_none_or(|allowed| allowed[index])
+                && self.profile_data.language_scripts(index).contains(&script)
+        }).collect();
+        if !allowed.contains(&true) {
+            return self.allowed.clone();
+        }
+        Some(allowed)
+    }
+
+    /// Initializes probability estimates for the EM algorithm.
+    ///
+    /// Uses prior probabilities (raised to `prior_floor`) if available, otherwise uniform
+    /// distribution. Languages which are not candidates start (and stay) at zero.
+    /// The result is normalized.
+    ///
+    /// # Arguments
+    /// * `columns` - Indices of the candidate languages, None for all languages.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::InvalidPrior` if `prior_map` does not match the languages,
+    /// holds negative or non-finite values, or leaves no detectable language with a
+    /// non-zero prior, and if `prior_floor` is not within [0, 1).
+    fn init_probability(&self, columns: Option<&[usize]>) -> Result<Vec<f64>, DetectorError> {
+        let mut prob = if let Some(ref prior) = self.prior_map {
+            if prior.len() != self.langlist.len() {
+                return Err(DetectorError::InvalidPrior(format!("{} priors for {} languages", prior.len(), self.langlist.len())));
+            }
+            if let Some(p) = prior.iter().find(|p| !(**p >= 0.0 && p.is_finite())) {
+                return Err(DetectorError::InvalidPrior(format!("probability {} is not a non-negative number", p)));
+            }
+            if !(0.0..1.0).contains(&self.prior_floor) {
+                return Err(DetectorError::InvalidPrior(format!("floor {} is not within [0, 1)", self.prior_floor)));
+            }
+            prior.iter().map(|p| p.max(self.prior_floor)).collect()
+        } else {
+            vec![1.0 / self.langlist.len() as f64; self.langlist.len()]
+        };
+        if let Some(columns) = columns {
+            prob.iter_mut().enumerate().filter(|(i, _)| !columns.contains(i)).for_each(|(_, p)| *p = 0.0);
+        }
+        let sum: f64 = prob.iter().sum();
+        if sum <= 0.0 {
+            return Err(DetectorError::InvalidPrior("no detectable language has a non-zero prior".to_string()));
+        }
+        prob.iter_mut().for_each(|p| *p /= sum);
+        Ok(prob)
+    }
+
+    /// Extracts n-grams from the text for language detection.
+    ///
+    /// Only includes n-grams that exist in the language profiles. The text is cleaned by
+    /// `cleaning_text` first. N-grams are looked up
+    /// packed into integers (see `NGram::pack`) and returned as their `ProfileData` ids,
+    /// so no string is allocated per n-gram. N-grams added
+    /// with `append_ngrams` are included.
+    ///
+    /// # Returns
+    /// The ids of the known n-grams and the number of all extracted n-grams.
+    fn extract_ngrams(&self) -> (Vec<usize>, usize) {
+        let mut result = self.appended_ngrams.clone();
+        let mut total = self.appended_total;
+        let cleaned = self.cleaning_text();
+        let text = match self.normalization {
+            Normalization::Strict => Cow::Borrowed(cleaned.as_ref()),
+            Normalization::Lenient => NGram::normalize_lenient(&cleaned),
+        };
+        let mut ngram = NGram::with_normalization(self.normalization);
+        for ch in text.chars() {
+            ngram.add_char(ch);
+            if ngram.capitalword {
+                continue;
+            }
+            for n in 1..=NGram::N_GRAM {
+                if let Some(key) = ngram.get_packed(n) {
+                    total += 1;
+                    if let Some(id) = self.profile_data.ngram_id_packed(key) {
+                        result.push(id);
+                    }
+                }
+            }
+        }
+        (result, total)
+    }
+
+    /// Updates language probabilities based on an n-gram observation.
+    ///
+    /// # Arguments
+    /// * `prob` - Current probability estimates (modified in-place).
+    /// * `lang_prob` - Probabilities of the observed n-gram for every language.
+    /// * `alpha` - Smoothing parameter.
+    fn update_lang_prob(&self, prob: &mut [f64], lang_prob: &[f64], alpha: f64) {
+        let weight = alpha / self.base_freq;
+        for (p, &q) in prob.iter_mut().zip(lang_prob.iter()) {
+            *p *= weight + q;
         }
-        true
     }
 
     /// Normalizes probability estimates and returns the maximum probability.
@@ -342,26 +1896,220 @@ impl Detector {
 
     /// Converts probability estimates to a sorted list of Language structs.
     ///
-    /// Only includes languages with probability above the threshold.
-    ///
     /// # Arguments
     /// * `prob` - Raw probability estimates.
+    /// * `threshold` - Only languages with a probability above it are included; None includes all.
     ///
     /// # Returns
     /// Sorted vector of Language structs.
-    fn sort_probability(&self, prob: &[f64]) -> Vec<Language> {
+    fn sort_probability(&self, prob: &[f64], threshold: Option<f64>) -> Vec<Language> {
         let mut result: Vec<Language> = self.langlist.iter().zip(prob.iter())
-            .filter(|(_, p)| **p > Self::PROB_THRESHOLD)
+            .filter(|(_, p)| threshold.is_none_or(|threshold| **p > threshold))
             .map(|(lang, &p)| Language::new(Some(lang.clone()), p)).collect();
-        result.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        result.sort_by(|a, b| b.prob.total_cmp(&a.prob));
         result
     }
 }
 
+/// Builder for `Detector` with fluent setters for the algorithm parameters.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::detector_factory::DetectorFactory;
+///
+/// let factory = DetectorFactory::default().build();
+/// let mut detector = factory.detector_builder()
+///     .with_trials(10)
+///     .with_prob_threshold(0.5)
+///     .build();
+/// detector.append("Bonjour le monde!");
+/// assert_eq!(detector.detect().unwrap(), "fr");
+/// ```
+pub struct DetectorBuilder {
+    detector: Detector,
+}
+
+impl DetectorBuilder {
+    /// Set the seed for reproducible randomization.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed, or None for a random one.
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.detector.seed = seed;
+        self
+    }
+
+    /// Set the alpha smoothing parameter (default: 0.5).
+    ///
+    /// Higher values smooth the per-n-gram probabilities more, which favors languages
+    /// with sparse profiles.
+    ///
+    /// # Arguments
+    /// * `alpha` - The smoothing parameter.
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.detector.alpha = alpha;
+        self
+    }
+
+    /// Set the number of EM trials averaged per detection (default: 7).
+    ///
+    /// # Arguments
+    /// * `n_trial` - Number of trials; values below 1 are raised to 1.
+    pub fn with_trials(mut self, n_trial: usize) -> Self {
+        self.detector.n_trial = n_trial.max(1);
+        self
+    }
+
+    /// Set the maximum number of characters taken from every appended text (default: 10000).
+    ///
+    /// # Arguments
+    /// * `max_text_length` - Maximum number of characters.
+    pub fn with_max_text_length(mut self, max_text_length: usize) -> Self {
+        self.detector.max_text_length = max_text_length;
+        self
+    }
+
+    /// Set the minimum probability for a language to be reported (default: 0.1).
+    ///
+    /// # Arguments
+    /// * `prob_threshold` - The threshold; `detect()` returns the unknown label if no language exceeds it.
+    pub fn with_prob_threshold(mut self, prob_threshold: f64) -> Self {
+        self.detector.prob_threshold = prob_threshold;
+        self
+    }
+
+    /// Set the minimum probability for `verify` to accept a language (default: 0.5).
+    ///
+    /// # Arguments
+    /// * `verify_threshold` - The threshold, see `Detector::verify_threshold`.
+    pub fn with_verify_threshold(mut self, verify_threshold: f64) -> Self {
+        self.detector.verify_threshold = verify_threshold;
+        self
+    }
+
+    /// Set the maximum number of EM iterations per trial (default: 1000).
+    ///
+    /// # Arguments
+    /// * `iteration_limit` - Maximum number of iterations.
+    pub fn with_iteration_limit(mut self, iteration_limit: usize) -> Self {
+        self.detector.iteration_limit = iteration_limit;
+        self
+    }
+
+    /// Set the probability of the top language at which a trial stops early (default: 0.99999).
+    ///
+    /// # Arguments
+    /// * `conv_threshold` - The convergence threshold.
+    pub fn with_conv_threshold(mut self, conv_threshold: f64) -> Self {
+        self.detector.conv_threshold = conv_threshold;
+        self
+    }
+
+    /// Set the smoothing denominator (default: 10000.0).
+    ///
+    /// # Arguments
+    /// * `base_freq` - The base frequency; lower values smooth more.
+    pub fn with_base_freq(mut self, base_freq: f64) -> Self {
+        self.detector.base_freq = base_freq;
+        self
+    }
+
+    /// Use Arabic-script letter markers as additional evidence (default: false).
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether markers are used, see `Detector::arabic_markers`.
+    pub fn with_arabic_markers(mut self, enabled: bool) -> Self {
+        self.detector.arabic_markers = enabled;
+        self
+    }
+
+    /// Let function words decide between Hindi, Marathi and Nepali (default: false).
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether the tie-breaker is used, see `Detector::devanagari_tiebreak`.
+    pub fn with_devanagari_tiebreak(mut self, enabled: bool) -> Self {
+        self.detector.devanagari_tiebreak = enabled;
+        self
+    }
+
+    /// Only consider languages written in the dominant script of the text (default: false).
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether the pre-filter is used, see `Detector::script_prefilter`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut detector = factory.detector_builder().with_script_prefilter(true).build();
+    /// detector.append("Добрый день");
+    /// assert_eq!(detector.detect().unwrap(), "ru");
+    /// ```
+    pub fn with_script_prefilter(mut self, enabled: bool) -> Self {
+        self.detector.script_prefilter = enabled;
+        self
+    }
+
+    /// Log the EM algorithm (default: false).
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether the EM algorithm is logged, see `Detector::verbose`.
+    pub fn with_verbose(mut self, enabled: bool) -> Self {
+        self.detector.verbose = enabled;
+        self
+    }
+
+    /// Set the removal of URLs, emails or other noise from appended text.
+    ///
+    /// # Arguments
+    /// * `scrubbing` - The scrubbing step, see `TextScrubbing`.
+    pub fn with_scrubbing(mut self, scrubbing: TextScrubbing) -> Self {
+        self.detector.scrubbing = scrubbing;
+        self
+    }
+
+    /// Set the character normalization rules of n-gram extraction (default: strict).
+    ///
+    /// # Arguments
+    /// * `normalization` - The rules, see `Normalization`.
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.detector.normalization = normalization;
+        self
+    }
+
+    /// Builds the final `Detector` object with the configured parameters.
+    pub fn build(self) -> Detector {
+        self.detector
+    }
+}
+
+impl From<Detector> for DetectorBuilder {
+    /// Starts from the settings of an existing detector.
+    fn from(detector: Detector) -> Self {
+        DetectorBuilder { detector }
+    }
+}
+
+/// Applies a transformation which borrows its input when nothing changes, keeping `text` in that case.
+fn replace_cow<'a>(text: Cow<'a, str>, transform: impl Fn(&str) -> Cow<'_, str>) -> Cow<'a, str> {
+    let changed = match transform(&text) {
+        Cow::Owned(changed) => Some(changed),
+        Cow::Borrowed(_) => None,
+    };
+    changed.map_or(text, Cow::Owned)
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::detector_factory::DetectorFactory;
-    use crate::utils::lang_profile::LangProfile;
+    use super::{DetectOptions, Detection, Detector, DetectorError, FallbackReason, FallbackResult, LanguageExplanation, LanguageFilter, Normalization, ProfileData, TextScrubbing};
+    use crate::language::Lang;
+    use crate::utils::script::Script;
+    use std::collections::HashMap;
+    use crate::detector_factory::{DetectorFactory, DetectorFactoryError, LabelSet};
+    use crate::utils::lang_profile::{LangProfile, ProfileLimits};
 
     fn setup_factory() -> DetectorFactory {
         let mut factory = DetectorFactory::new().build();
@@ -437,6 +2185,440 @@ mod tests {
         assert_eq!(lang, "ja");
     }
 
+    #[test]
+    fn test_detect_lang() {
+        let factory = setup_factory();
+        let mut detect = factory.create(None);
+        detect.append("b d");
+        let result = detect.detect_lang();
+        assert!(result.is_ok(), "Unexpected error: {:?}", result);
+        let language = result.unwrap();
+        assert_eq!(language.lang.as_deref(), Some("fr"));
+        let probabilities = detect.get_probabilities().unwrap();
+        assert_eq!(probabilities[0], language);
+
+        // A NaN estimate is skipped instead of panicking
+        detect.langprob = Some(vec![f64::NAN, 0.7, 0.3]);
+        assert_eq!(detect.detect_lang().unwrap().prob, 0.7);
+        assert_eq!(detect.get_probabilities_full().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_record_trials() {
+        let factory = setup_factory();
+        let mut detect = factory.create(None);
+        detect.append("b d");
+        detect.detect().unwrap();
+        assert!(detect.last_stats().unwrap().trials.is_empty());
+
+        let mut detect = factory.create(None);
+        detect.record_trials = true;
+        detect.append("b d");
+        detect.detect().unwrap();
+        let stats = detect.last_stats().unwrap();
+        assert_eq!(stats.trials.len(), detect.n_trial);
+        for trial in stats.trials.iter() {
+            assert_eq!(trial.probabilities.len(), 3);
+            assert!((trial.probabilities.iter().sum::<f64>() - 1.0).abs() < 0.0001);
+            assert!(trial.converged && trial.iterations % 5 == 1);
+        }
+        assert!(stats.max_trial_spread() >= 0.0 && stats.max_trial_spread() <= 1.0);
+        assert_eq!(stats.unconverged_trials, 0);
+
+        // "b" is as frequent in en as in fr, so the trials never converge
+        let mut detect = factory.create(None);
+        detect.record_trials = true;
+        detect.iteration_limit = 20;
+        detect.append("b");
+        detect.detect().unwrap();
+        let stats = detect.last_stats().unwrap();
+        assert_eq!(stats.unconverged_trials, detect.n_trial);
+        assert!(stats.trials.iter().all(|trial| !trial.converged && trial.iterations == 21));
+    }
+
+    #[test]
+    fn test_verbose() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let quiet = factory.get_probabilities_full("b d", None).unwrap();
+        factory.params.verbose = true;
+        assert!(factory.create(None).verbose);
+        // Logging does not change results or the model description
+        assert_eq!(factory.get_probabilities_full("b d", None).unwrap(), quiet);
+        assert!(!factory.model_info().to_json().contains("verbose"));
+        assert!(!factory.detector_builder().with_verbose(false).build().verbose);
+    }
+
+    #[test]
+    fn test_load_mapped() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let path = std::env::temp_dir().join(format!("langdetect-test-{}.mapped", std::process::id()));
+        factory.save_mapped(&path).unwrap();
+        let mut loaded = DetectorFactory::new().with_seed(Some(0)).build();
+        loaded.load_mapped(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(loaded.profile_data.is_mapped());
+        assert_eq!(loaded.get_lang_list(), factory.get_lang_list());
+        for text in ["a b d", "\u{3042}\u{3044}", "c c d"] {
+            assert_eq!(loaded.get_probabilities_full(text, None).unwrap(), factory.get_probabilities_full(text, None).unwrap());
+        }
+        loaded.delete_profile("fr").unwrap();
+        assert!(!loaded.profile_data.is_mapped());
+        assert_eq!(loaded.profile_data.probabilities("a"), Some(vec![3.0 / 9.0, 0.0]));
+        assert!(matches!(loaded.load_mapped(&path), Err(DetectorFactoryError::IoError(_))));
+    }
+
+    #[test]
+    fn test_model_limits() {
+        let factory = setup_factory();
+        let dir = std::env::temp_dir();
+        let binary = dir.join(format!("langdetect-test-limits-{}.bin", std::process::id()));
+        let mapped = dir.join(format!("langdetect-test-limits-{}.mapped", std::process::id()));
+        factory.save_binary(&binary).unwrap();
+        factory.save_mapped(&mapped).unwrap();
+        let mut loaded = DetectorFactory::new().with_profile_limits(ProfileLimits::default().with_max_model_size(64)).build();
+        assert!(matches!(loaded.load_binary(&binary), Err(DetectorFactoryError::LimitExceeded(_))));
+        assert!(matches!(loaded.load_mapped(&mapped), Err(DetectorFactoryError::LimitExceeded(_))));
+        loaded.profile_limits = ProfileLimits::default().with_max_entries(1);
+        assert!(matches!(loaded.load_binary(&binary), Err(DetectorFactoryError::LimitExceeded(_))));
+        loaded.profile_limits = ProfileLimits::default().with_max_ngram_length(1);
+        assert!(matches!(loaded.load_binary(&binary), Err(DetectorFactoryError::LimitExceeded(_))));
+        assert!(matches!(loaded.load_mapped(&mapped), Err(DetectorFactoryError::LimitExceeded(_))));
+        loaded.profile_limits = ProfileLimits::default();
+        loaded.load_binary(&binary).unwrap();
+        loaded.load_mapped(&mapped).unwrap();
+        // A duplicated n-gram is an invalid model, not a panic: one language "en", the n-grams ["a", "a"] and an entry for the second one
+        let mut bytes = ProfileData::BINARY_MAGIC.to_vec();
+        let mut push = |value: &[u8]| bytes.extend_from_slice(value);
+        push(&ProfileData::BINARY_VERSION.to_le_bytes());
+        push(&1u32.to_le_bytes());
+        push(&2u32.to_le_bytes());
+        push(b"en");
+        push(&2u32.to_le_bytes());
+        for word in ["a", "a"] {
+            push(&1u32.to_le_bytes());
+            push(word.as_bytes());
+        }
+        push(&1u32.to_le_bytes());
+        push(&1u32.to_le_bytes());
+        push(&0.5f64.to_le_bytes());
+        std::fs::write(&binary, &bytes).unwrap();
+        assert!(matches!(loaded.load_binary(&binary), Err(DetectorFactoryError::InvalidFormat(_))));
+        std::fs::remove_file(&binary).unwrap();
+        std::fs::remove_file(&mapped).unwrap();
+
+        // JSON profiles are checked against the matrix they would build: 4 n-grams x 2 languages
+        let json_profiles = [
+            r#"{"name":"en","freq":{"a":2,"b":1},"n_words":[3,0,0]}"#,
+            r#"{"name":"fr","freq":{"c":2,"d":1},"n_words":[3,0,0]}"#,
+        ];
+        let mut loaded = DetectorFactory::new().with_profile_limits(ProfileLimits::default().with_max_model_size(4 * 2 * 8 - 1)).build();
+        assert!(matches!(loaded.load_json_profile(&json_profiles), Err(DetectorFactoryError::LimitExceeded(_))));
+        let mut loaded = DetectorFactory::new().with_profile_limits(ProfileLimits::default().with_max_model_size(4 * 2 * 8)).build();
+        loaded.load_json_profile(&json_profiles).unwrap();
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_export_import() {
+        let factory = setup_factory();
+        let path = std::env::temp_dir().join(format!("langdetect-test-snapshot-{}.bin.gz", std::process::id()));
+        factory.export(&path).unwrap();
+        let mut loaded = DetectorFactory::new().build();
+        loaded.import(&path).unwrap();
+        assert_eq!(loaded.get_lang_list(), factory.get_lang_list());
+        assert_eq!(loaded.profile_data.to_word_lang_prob_map(), factory.profile_data.to_word_lang_prob_map());
+        // Decompression stops at the model size limit
+        loaded.profile_limits = ProfileLimits::default().with_max_model_size(64);
+        assert!(matches!(loaded.import(&path), Err(DetectorFactoryError::LimitExceeded(_))));
+        loaded.profile_limits = ProfileLimits::default();
+
+        // Uncompressed binary profiles are rejected
+        factory.save_binary(&path).unwrap();
+        assert!(matches!(loaded.import(&path), Err(DetectorFactoryError::IoError(_))));
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(loaded.import(&path), Err(DetectorFactoryError::IoError(_))));
+    }
+
+    #[cfg(feature = "log")]
+    #[test]
+    fn test_verbose_log() {
+        use std::sync::Mutex;
+
+        struct Capture(Mutex<Vec<String>>);
+
+        impl log::Log for Capture {
+            fn enabled(&self, _: &log::Metadata) -> bool {
+                true
+            }
+
+            fn log(&self, record: &log::Record) {
+                self.0.lock().unwrap().push(record.args().to_string());
+            }
+
+            fn flush(&self) {}
+        }
+
+        static CAPTURE: Capture = Capture(Mutex::new(Vec::new()));
+        log::set_logger(&CAPTURE).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+        let factory = setup_factory();
+        let mut detector = factory.detector_builder().with_seed(Some(0)).with_trials(2).with_verbose(true).build();
+        detector.append("b d");
+        detector.detect().unwrap();
+        let messages = CAPTURE.0.lock().unwrap();
+        let trials: Vec<&String> = messages.iter().filter(|m| m.starts_with("EM trial") && m.contains("alpha")).collect();
+        assert_eq!(trials.len(), 2);
+        assert!(trials[0].contains("converged") && trials[0].contains("fr:"));
+        assert!(messages.iter().any(|m| m.starts_with("EM trial 0 iteration 0: top probability")));
+        assert!(messages.iter().any(|m| m.starts_with("EM result over 2 trials: fr:")));
+    }
+
+    #[test]
+    fn test_ngram_coverage() {
+        let factory = setup_factory();
+        let mut detect = factory.create(None);
+        // The toy profiles only know the unigrams "b" and "d" of this text
+        detect.append("b d x");
+        detect.detect().unwrap();
+        let stats = detect.last_stats().unwrap();
+        assert_eq!(stats.matched_ngrams, 2);
+        assert!(stats.total_ngrams > stats.matched_ngrams);
+        assert_eq!(stats.coverage(), 2.0 / stats.total_ngrams as f64);
+
+        let mut detect = factory.create(None);
+        detect.append("x y z");
+        assert!(matches!(detect.detect(), Err(DetectorError::NoFeatures)));
+        let stats = detect.last_stats().unwrap();
+        assert_eq!(stats.matched_ngrams, 0);
+        assert!(stats.total_ngrams > 0);
+        assert_eq!(stats.coverage(), 0.0);
+    }
+
+    #[test]
+    fn test_coverage_report() {
+        let factory = setup_factory();
+        let report = factory.coverage_report();
+        assert_eq!(report.len(), 3);
+        assert_eq!(report[0].lang, "en");
+        // en: a, b, c, d, e
+        assert_eq!(report[0].ngram_counts, [5, 0, 0]);
+        assert_eq!(report[0].shared, 4);
+        assert_eq!(report[0].exclusive, 1);
+        assert_eq!(report[0].most_overlapping, Some((Lang::Fr, 4)));
+        // ja shares nothing with the others
+        assert_eq!(report[2].ngram_counts, [4, 0, 0]);
+        assert_eq!(report[2].shared, 0);
+        assert_eq!(report[2].most_overlapping, None);
+        assert_eq!(report[0].scripts, vec![Script::Latin]);
+        assert_eq!(report[2].scripts, vec![Script::Hiragana]);
+    }
+
+    #[test]
+    fn test_model_info() {
+        let mut factory = setup_factory();
+        let info = factory.model_info();
+        assert_eq!(info.crate_version, env!("CARGO_PKG_VERSION"));
+        let langs: Vec<&str> = info.languages.iter().map(|profile| profile.lang.as_str()).collect();
+        assert_eq!(langs, vec!["en", "fr", "ja"]);
+        assert_eq!(info.languages[0].ngrams, 5);
+        assert_eq!(info.languages[0].checksum.len(), 16);
+        assert_ne!(info.languages[0].checksum, info.languages[1].checksum);
+        assert_eq!(factory.model_info(), info);
+        assert!(info.to_json().contains(&format!(r#""snapshot_hash":"{}""#, info.snapshot_hash)));
+
+        factory.set_seed(7);
+        let seeded = factory.model_info();
+        assert_eq!(seeded.languages, info.languages);
+        assert_ne!(seeded.snapshot_hash, info.snapshot_hash);
+        factory.delete_profile("ja").unwrap();
+        assert_ne!(factory.model_info().snapshot_hash, seeded.snapshot_hash);
+    }
+
+    #[test]
+    fn test_default_shared() {
+        let first = DetectorFactory::default_shared();
+        let second = DetectorFactory::default_shared();
+        assert!(std::sync::Arc::ptr_eq(&first, &second));
+        assert!(first.get_lang_list().len() >= 55);
+        assert_eq!(DetectorFactory::default().build().get_lang_list(), first.get_lang_list());
+        assert!(std::sync::Arc::ptr_eq(&DetectorFactory::try_default_shared().unwrap(), &first));
+        assert_eq!(DetectorFactory::try_default().unwrap().build().get_lang_list(), first.get_lang_list());
+    }
+
+    #[test]
+    fn test_create_shares_profile_data() {
+        let mut factory = setup_factory();
+        let detector = factory.create(None);
+        assert!(std::sync::Arc::ptr_eq(&detector.profile_data, &factory.profile_data));
+        assert!(std::sync::Arc::ptr_eq(&detector.langlist, &factory.langlist));
+        // Modifying the factory leaves existing detectors untouched
+        factory.delete_profile("ja").unwrap();
+        assert_eq!(factory.get_lang_list().len(), 2);
+        assert_eq!(detector.langlist.len(), 3);
+        assert_eq!(detector.profile_data.lang_count(), 3);
+    }
+
+    #[test]
+    fn test_retain_languages() {
+        let mut factory = setup_factory();
+        factory.set_language_enabled("ja", false).unwrap();
+        let detector = factory.create(None);
+        assert_eq!(factory.retain_languages(&["fr", "en", "xx"]), 1);
+        assert_eq!(factory.get_lang_list(), vec![Lang::En, Lang::Fr]);
+        assert_eq!(factory.profile_data.lang_count(), 2);
+        assert!(!factory.profile_data.contains("\u{3042}"));
+        assert_eq!(factory.profile_data.probabilities("c").unwrap().len(), 2);
+        assert!(factory.disabled_languages.is_empty());
+        assert_eq!(detector.profile_data.lang_count(), 3);
+        assert_eq!(factory.retain_languages(&[Lang::En, Lang::Fr]), 0);
+        assert_eq!(factory.retain_languages::<&str>(&[]), 2);
+        assert!(factory.get_lang_list().is_empty());
+
+        // Columns reserved by add_profile for languages not loaded yet are dropped
+        let mut factory = DetectorFactory::new().build();
+        let mut aa = LangProfile::new().with_name("aa").build();
+        aa.add("a");
+        factory.add_profile(aa, 0, 3).unwrap();
+        factory.add_profile(LangProfile::new().with_name("bb").build(), 1, 3).unwrap();
+        assert_eq!(factory.retain_languages(&["aa"]), 1);
+        assert_eq!(factory.get_lang_list(), vec!["aa"]);
+        assert_eq!(factory.profile_data.lang_count(), 1);
+        assert_eq!(factory.profile_data.probabilities("a").unwrap(), vec![1.0]);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut base = setup_factory();
+        base.retain_languages(&["en", "fr"]);
+        let mut tenant = setup_factory();
+        tenant.retain_languages(&["fr", "ja"]);
+
+        assert!(matches!(base.merge(&tenant, false), Err(DetectorFactoryError::DuplicatedLanguage(lang)) if lang == "fr"));
+        assert_eq!(base.get_lang_list(), vec![Lang::En, Lang::Fr]);
+
+        let mut fr = LangProfile::new().with_name("fr").build();
+        fr.add("z");
+        tenant.delete_profile("fr").unwrap();
+        tenant.push_profile(fr).unwrap();
+        base.merge(&tenant, true).unwrap();
+        assert_eq!(base.get_lang_list(), vec![Lang::En, Lang::Fr, Lang::Ja]);
+        assert_eq!(base.profile_data.probabilities("z"), Some(vec![0.0, 1.0, 0.0]));
+        assert_eq!(base.profile_data.probabilities("b"), Some(vec![2.0 / 9.0, 0.0, 0.0]));
+        assert_eq!(base.profile_data.probabilities("\u{3042}"), tenant.profile_data.probabilities("\u{3042}").map(|probs| vec![0.0, 0.0, probs[0]]));
+        base.seed = Some(0);
+        assert_eq!(base.detect("\u{3042}\u{3044}", None).unwrap(), "ja");
+    }
+
+    #[test]
+    fn test_label_set() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        factory.label_set = Some(LabelSet::new("other").with_label("latin", &["en", "fr"]));
+        assert_eq!(factory.detect("b d", None).unwrap(), "latin");
+        let probabilities = factory.get_probabilities("b d", None).unwrap();
+        assert_eq!(probabilities.len(), 1);
+        assert!((probabilities[0].prob - 1.0).abs() < 1e-6);
+        assert_eq!(factory.detect("\u{3042}\u{3044}", None).unwrap(), "other");
+        // Labels are filtered with the factory's threshold
+        let label_set = factory.label_set.clone().unwrap();
+        let langlist: Vec<Lang> = vec!["en".into(), "fr".into(), "ja".into()];
+        let labels = label_set.collapse(&langlist, &[0.2, 0.2, 0.6], 0.5);
+        assert_eq!(labels.len(), 1);
+        assert_eq!(labels[0].lang.as_deref(), Some("other"));
+        factory.params.prob_threshold = 1.0;
+        assert!(factory.get_probabilities("b d", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_detect_paragraphs() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let text = "  a a\nb\n\n \t \n\u{3042}\u{3044}\n\n!!!\n";
+        let paragraphs = factory.detect_paragraphs(text, None);
+        assert_eq!(paragraphs.len(), 3);
+        assert_eq!(&text[paragraphs[0].0.clone()], "a a\nb");
+        assert_eq!(paragraphs[0].1.lang.as_deref(), Some("en"));
+        assert_eq!(&text[paragraphs[1].0.clone()], "\u{3042}\u{3044}");
+        assert_eq!(paragraphs[1].1.lang.as_deref(), Some("ja"));
+        assert_eq!(&text[paragraphs[2].0.clone()], "!!!");
+        assert_eq!(paragraphs[2].1.lang, None);
+    }
+
+    #[test]
+    fn test_detect_sentences() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let text = "a a b. \u{3042}\u{3044}\u{3002}d e!\n!!! ";
+        let sentences = factory.detect_sentences(text, None);
+        let parts: Vec<&str> = sentences.iter().map(|(range, _)| &text[range.clone()]).collect();
+        assert_eq!(parts, vec!["a a b.", "\u{3042}\u{3044}\u{3002}", "d e!", "!!!"]);
+        assert_eq!(sentences[0].1.lang.as_deref(), Some("en"));
+        assert_eq!(sentences[1].1.lang.as_deref(), Some("ja"));
+        assert!(sentences[1].1.prob > 0.5);
+        assert_eq!(sentences[3].1.lang, None);
+
+        let by_comma = |text: &str| vec![0..text.find(',').unwrap(), text.find(',').unwrap() + 1..text.len()];
+        let segments = factory.detect_sentences_with("a a,\u{3042}", by_comma, None);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].1.lang.as_deref(), Some("ja"));
+        // Ranges splitting a character or past the end are skipped instead of panicking
+        let broken = |_: &str| vec![0..1, 1..5, 4..5, 4..7, 7..9];
+        let segments = factory.detect_sentences_with("a a,\u{3042}", broken, None);
+        let ranges: Vec<_> = segments.iter().map(|(range, _)| range.clone()).collect();
+        assert_eq!(ranges, vec![0..1, 4..7]);
+    }
+
+    #[test]
+    fn test_segmenter() {
+        use crate::segmenter::{split_words, Segmenter};
+        use std::ops::Range;
+        use std::sync::Arc;
+
+        let text = "don't-stop, «e-mail» \u{3042}\u{3044}\u{3002}'x -- y";
+        let words: Vec<&str> = split_words(text).into_iter().map(|range| &text[range]).collect();
+        assert_eq!(words, vec!["don't-stop", "e-mail", "\u{3042}\u{3044}", "x", "y"]);
+
+        // Without spaces the default keeps a run of kana as one word
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let text = "a a d \u{3042}\u{3044}\u{3042}";
+        let words = factory.detect_words(text, None);
+        assert_eq!(words.len(), 4);
+        assert_eq!(words[0].1.lang.as_deref(), Some("en"));
+        assert_eq!(words[3].1.lang.as_deref(), Some("ja"));
+
+        struct CharSegmenter;
+        impl Segmenter for CharSegmenter {
+            fn sentences(&self, text: &str) -> Vec<Range<usize>> {
+                std::iter::once(0..text.len()).collect()
+            }
+            fn words(&self, text: &str) -> Vec<Range<usize>> {
+                text.char_indices().filter(|(_, ch)| !ch.is_whitespace()).map(|(i, ch)| i..i + ch.len_utf8()).collect()
+            }
+        }
+        factory.segmenter = Arc::new(CharSegmenter);
+        assert_eq!(factory.detect_sentences("a. b. c.", None).len(), 1);
+        let words = factory.detect_words(text, None);
+        assert_eq!(words.len(), 6);
+        assert_eq!(words[4].0, 9..12);
+        assert_eq!(words[5].1.lang.as_deref(), Some("ja"));
+    }
+
+    #[test]
+    fn test_unknown_label() {
+        let factory = setup_factory();
+        assert_eq!(factory.unknown_label, "unknown");
+        let mut detector = factory.create(None);
+        detector.unknown_label = "und".to_string();
+        // No language above the probability threshold
+        detector.langprob = Some(vec![0.05, 0.05, 0.05]);
+        assert_eq!(detector.detect().unwrap(), "und");
+        let factory = DetectorFactory::new().with_unknown_label("und").build();
+        assert_eq!(factory.create(None).unknown_label, "und");
+    }
+
     #[test]
     fn test_lang_list() {
         let factory = setup_factory();
@@ -453,12 +2635,744 @@ mod tests {
         factory.clear();
         let json_lang1 = "{\"freq\":{\"A\":3,\"B\":6,\"C\":3,\"AB\":2,\"BC\":1,\"ABC\":2,\"BBC\":1,\"CBA\":1},\"n_words\":[12,3,4],\"name\":\"lang1\"}";
         let json_lang2 = "{\"freq\":{\"A\":6,\"B\":3,\"C\":3,\"AA\":3,\"AB\":2,\"ABC\":1,\"ABA\":1,\"CAA\":1},\"n_words\":[12,5,3],\"name\":\"lang2\"}";
-        let profiles = vec![json_lang1, json_lang2];
-        let profiles_ref: Vec<&str> = profiles.iter().map(|s| *s).collect();
+        let profiles = [json_lang1, json_lang2];
+        let profiles_ref: Vec<&str> = profiles.to_vec();
         factory.load_json_profile(&profiles_ref).unwrap();
         let langlist = factory.get_lang_list();
         assert_eq!(langlist.len(), 2);
         assert_eq!(langlist[0], "lang1");
         assert_eq!(langlist[1], "lang2");
+        assert_eq!(factory.language_scripts("lang1"), Some(vec![Script::Latin]));
+        assert_eq!(factory.language_scripts("lang3"), None);
+
+        // Declared scripts take precedence over the derived ones
+        let json_lang3 = "{\"freq\":{\"A\":3},\"n_words\":[3,0,0],\"name\":\"lang3\",\"scripts\":[\"Cyrillic\",\"Latin\"]}";
+        let profile_json: crate::utils::lang_profile::LangProfileJson = serde_json::from_str(json_lang3).unwrap();
+        factory.push_profile(LangProfile::from_json(profile_json).unwrap()).unwrap();
+        assert_eq!(factory.language_scripts("lang3"), Some(vec![Script::Cyrillic, Script::Latin]));
+        assert_eq!(factory.languages_for_script(Script::Cyrillic), vec!["lang3"]);
+        assert_eq!(factory.languages_for_script(Script::Latin), vec!["lang1", "lang2", "lang3"]);
+    }
+
+    #[test]
+    fn test_load_json_profile_order() {
+        let json_a = "{\"freq\":{\"A\":3},\"n_words\":[3,0,0],\"name\":\"aa\"}";
+        let json_b = "{\"freq\":{\"B\":3},\"n_words\":[3,0,0],\"name\":\"bb\"}";
+        let mut factory = DetectorFactory::new().build();
+        factory.load_json_profile(&[json_b, json_a]).unwrap();
+        assert_eq!(factory.get_lang_list(), vec!["aa", "bb"]);
+        assert_eq!(factory.profile_data.probabilities("A"), Some(vec![1.0, 0.0]));
+
+        let mut factory = DetectorFactory::new().with_sorted_profiles(false).build();
+        factory.load_json_profile(&[json_b, json_a]).unwrap();
+        assert_eq!(factory.get_lang_list(), vec!["bb", "aa"]);
+    }
+
+    #[test]
+    fn test_load_json_profile_appends() {
+        let json_c = "{\"freq\":{\"C\":3},\"n_words\":[3,0,0],\"name\":\"cc\"}";
+        let json_d = "{\"freq\":{\"a\":3},\"n_words\":[3,0,0],\"name\":\"dd\"}";
+        let mut factory = setup_factory();
+        factory.load_json_profile(&[json_d, json_c]).unwrap();
+        assert_eq!(factory.get_lang_list(), vec!["en", "fr", "ja", "cc", "dd"]);
+        assert_eq!(factory.profile_data.probabilities("a").unwrap()[4], 1.0);
+        assert!(matches!(factory.load_json_profile(&[json_c, json_d]), Err(DetectorFactoryError::DuplicatedLanguage(_))));
+
+        // n_words must have one total per n-gram length, and none of the used ones may be 0
+        let short = "{\"freq\":{\"A\":3},\"n_words\":[3],\"name\":\"aa\"}";
+        let zero = "{\"freq\":{\"AB\":3},\"n_words\":[3,0,0],\"name\":\"bb\"}";
+        let mut factory = DetectorFactory::new().build();
+        assert!(matches!(factory.load_json_profile(&[short, json_c]), Err(DetectorFactoryError::InvalidFormat(_))));
+        assert!(matches!(factory.load_json_profile(&[zero, json_c]), Err(DetectorFactoryError::InvalidFormat(_))));
+        assert!(factory.get_lang_list().is_empty());
+    }
+
+    #[test]
+    fn test_load_json_profile_parse_error() {
+        let json_a = "{\"freq\":{\"A\":3},\"n_words\":[3,0,0],\"name\":\"aa\"}";
+        let mut factory = DetectorFactory::new().build();
+        let result = factory.load_json_profile(&[json_a, "{\"freq\":"]);
+        assert!(matches!(result, Err(DetectorFactoryError::InvalidFormat(e)) if e.contains("#1")));
+        let result = factory.load_json_profile(&[json_a, "{\"freq\":{},\"n_words\":[0,0,0]}"]);
+        assert!(matches!(result, Err(DetectorFactoryError::InvalidFormat(_))));
+
+        // Profiles from a directory are reported by file name
+        let dir = std::env::temp_dir().join(format!("langdetect-test-parse-error-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("aa"), json_a).unwrap();
+        std::fs::write(dir.join("bb"), "not json").unwrap();
+        let error = factory.load_profile(&dir).unwrap_err();
+        assert!(error.contains("InvalidFormat") && error.contains("bb"), "{}", error);
+        std::fs::remove_dir_all(&dir).unwrap();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_add_profile_langsize_guards() {
+        let mut factory = setup_factory();
+        let profile = || {
+            let mut profile = LangProfile::new().with_name("de").build();
+            profile.add("a");
+            profile
+        };
+        assert!(matches!(
+            factory.add_profile(profile(), 3, 3),
+            Err(DetectorFactoryError::IndexOutOfRange { index: 3, langsize: 3 })
+        ));
+        assert!(matches!(
+            factory.add_profile(profile(), 1, 2),
+            Err(DetectorFactoryError::LangsizeMismatch { expected: 2, found: 3 })
+        ));
+        assert!(matches!(
+            factory.add_profile(profile(), 1, 4),
+            Err(DetectorFactoryError::IndexMismatch { index: 1, expected: 3 })
+        ));
+        assert_eq!(factory.get_lang_list().len(), 3);
+        // Growing the factory resizes existing vectors
+        factory.add_profile(profile(), 3, 4).unwrap();
+        assert_eq!(factory.profile_data.lang_count(), 4);
+        assert_eq!(factory.profile_data.probabilities("a").unwrap()[3], 1.0);
+    }
+
+    #[test]
+    fn test_add_profile_rejects_reused_index() {
+        let mut factory = DetectorFactory::new().build();
+        let mut aa = LangProfile::new().with_name("aa").build();
+        aa.add("a");
+        let mut bb = LangProfile::new().with_name("bb").build();
+        bb.add("b");
+        factory.add_profile(aa, 0, 2).unwrap();
+        assert!(matches!(
+            factory.add_profile(bb, 0, 2),
+            Err(DetectorFactoryError::IndexMismatch { index: 0, expected: 1 })
+        ));
+        assert_eq!(factory.get_lang_list(), vec!["aa"]);
+        assert_eq!(factory.profile_data.probabilities("a").unwrap(), vec![1.0, 0.0]);
+        assert!(factory.profile_data.probabilities("b").is_none());
+    }
+
+    #[test]
+    fn test_language_filter() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.append("a");
+        assert_eq!(detector.detect().unwrap(), "en");
+        detector.exclude_languages(&["en"]);
+        assert_eq!(detector.detect().unwrap(), "fr");
+        detector.restrict_languages(&["en", "ja"]);
+        assert_eq!(detector.detect().unwrap(), "en");
+        // N-grams of filtered out languages only are no features
+        detector.restrict_languages(&["ja"]);
+        assert!(detector.detect().is_err());
+        detector.set_language_filter(None);
+        assert_eq!(detector.detect().unwrap(), "en");
+
+        factory.language_filter = Some(LanguageFilter::Allow(vec![Lang::Fr, Lang::Ja]));
+        let probabilities = factory.get_probabilities("a b", None).unwrap();
+        assert!(probabilities.iter().all(|l| l.lang.as_deref() != Some("en")));
+        assert_eq!(probabilities[0].lang.as_deref(), Some("fr"));
+    }
+
+    #[test]
+    fn test_detect_iter() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let texts = ["a", "c d", "あ", "1"];
+        let results: Vec<_> = factory.detect_iter(texts.iter().map(|t| t.to_string()), None).collect();
+        assert_eq!(results.len(), texts.len());
+        for (result, text) in results.iter().zip(texts.iter()) {
+            assert_eq!(result.as_ref().ok(), factory.detect_lang(text, None).as_ref().ok());
+        }
+        assert!(matches!(results[3], Err(DetectorError::NoFeatures)));
+        // Documents are pulled lazily, so an endless stream works
+        let first: Vec<_> = factory.detect_iter(std::iter::repeat("あ".to_string()), None).take(3).collect();
+        assert!(first.iter().all(|result| result.as_ref().unwrap().lang.as_deref() == Some("ja")));
+    }
+
+    #[test]
+    fn test_language_enabled() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        assert!(matches!(factory.set_language_enabled("xx", false), Err(DetectorError::UnknownLanguage(lang)) if lang == "xx"));
+        factory.set_language_enabled("en", false).unwrap();
+        assert!(!factory.is_language_enabled("en"));
+        assert!(factory.is_language_enabled("fr"));
+        assert_eq!(factory.detect("a", None).unwrap(), "fr");
+        // Disabled languages stay masked whatever the detector's own filter
+        let mut detector = factory.create(None);
+        detector.append("a");
+        detector.restrict_languages(&["en", "fr"]);
+        assert_eq!(detector.detect().unwrap(), "fr");
+        detector.set_language_filter(None);
+        assert_eq!(detector.detect().unwrap(), "fr");
+
+        factory.set_language_enabled("en", true).unwrap();
+        assert!(factory.disabled_languages.is_empty());
+        assert_eq!(factory.detect("a", None).unwrap(), "en");
+    }
+
+    #[test]
+    fn test_detect_batch() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let texts = ["a", "b d", "d e", "\u{3042}", "1234"];
+        let results = factory.detect_batch(&texts, None);
+        assert_eq!(results.len(), texts.len());
+        for (text, result) in texts.iter().zip(results.iter()) {
+            assert_eq!(result.as_ref().ok(), factory.detect(text, None).ok().as_ref());
+        }
+        assert_eq!(results[3].as_ref().unwrap(), "ja");
+        assert!(results[4].is_err());
+        let probabilities = factory.get_probabilities_batch(&texts, None);
+        assert_eq!(probabilities[3].as_ref().unwrap()[0].lang.as_deref(), Some("ja"));
+
+        // The cache sees every distinct text, so its misses count the classified texts
+        factory.batch_dedup = true;
+        factory.cache = Some(std::sync::Arc::new(crate::cache::DetectionCache::new().build()));
+        let texts = ["b d", "b  d", " b d\n", "\u{3042}", "1234", "1234"];
+        let results = factory.detect_batch(&texts, None);
+        assert_eq!(factory.cache.as_ref().unwrap().misses(), 3);
+        assert_eq!(results.len(), texts.len());
+        assert_eq!(results[0].as_ref().ok(), results[2].as_ref().ok());
+        assert_eq!(results[3].as_ref().unwrap(), "ja");
+        assert!(results[5].is_err());
+    }
+
+    #[test]
+    fn test_cache_invalidation() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let cache = std::sync::Arc::new(crate::cache::DetectionCache::new().build());
+        factory.cache = Some(cache.clone());
+        factory.invalidate_cache();
+        let clone = factory.clone();
+        assert_eq!(factory.detect("a", None).unwrap(), "en");
+        let en = factory.delete_profile("en").unwrap();
+        assert_ne!(factory.detect("a", None).unwrap(), "en");
+        factory.restore_profile(en).unwrap();
+        assert_eq!(factory.detect("a", None).unwrap(), "en");
+        // An unmodified clone still shares the entries of the original state
+        let hits = cache.hits();
+        assert_eq!(clone.detect("a", None).unwrap(), "en");
+        assert_eq!(cache.hits(), hits + 1);
+    }
+
+    #[test]
+    fn test_push_profile() {
+        let mut factory = setup_factory();
+        let mut profile_xx = LangProfile::new().with_name("xx").build();
+        for w in ["x", "x", "y", "z"].iter() {
+            profile_xx.add(w);
+        }
+        factory.push_profile(profile_xx).unwrap();
+        assert_eq!(factory.get_lang_list(), vec!["en", "fr", "ja", "xx"]);
+        assert_eq!(factory.profile_data.lang_count(), 4);
+        factory.seed = Some(0);
+        assert_eq!(factory.detect("x y", None).unwrap(), "xx");
+        assert_eq!(factory.detect("a", None).unwrap(), "en");
+
+        // Duplicates leave the factory unchanged
+        let profiles = vec![LangProfile::new().with_name("yy").build(), LangProfile::new().with_name("en").build()];
+        assert!(matches!(factory.add_profiles(profiles), Err(DetectorFactoryError::DuplicatedLanguage(lang)) if lang == "en"));
+        assert_eq!(factory.get_lang_list().len(), 4);
+        // Unnamed profiles are rejected instead of panicking
+        assert!(matches!(factory.push_profile(LangProfile::new().build()), Err(DetectorFactoryError::UnnamedProfile)));
+        assert!(matches!(factory.override_profile(LangProfile::new().build(), 0, 5), Err(DetectorFactoryError::UnnamedProfile)));
+        assert!(matches!(factory.add_profiles(vec![LangProfile::new().build()]), Err(DetectorFactoryError::UnnamedProfile)));
+        assert_eq!(factory.get_lang_list().len(), 4);
+
+        // Columns reserved with add_profile are filled first
+        let mut factory = DetectorFactory::new().build();
+        factory.add_profile(LangProfile::new().with_name("a").build(), 0, 3).unwrap();
+        let profiles = vec![LangProfile::new().with_name("b").build(), LangProfile::new().with_name("c").build()];
+        factory.add_profiles(profiles).unwrap();
+        assert_eq!(factory.get_lang_list(), vec!["a", "b", "c"]);
+        assert_eq!(factory.profile_data.lang_count(), 3);
+    }
+
+    #[test]
+    fn test_transliteration_signal() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.append("x y");
+        assert!(detector.detect().is_err());
+        detector.append("a b");
+        detector.detect().unwrap();
+        // No companion profile loaded
+        assert_eq!(detector.last_stats().unwrap().transliteration, None);
+
+        let mut profile_xx = LangProfile::new().with_name("ja-Latn").build();
+        for w in ["x", "x", "y", "z", "a"].iter() {
+            profile_xx.add(w);
+        }
+        factory.push_profile(profile_xx).unwrap();
+        let mut detector = factory.create(None);
+        detector.append("x y z a");
+        detector.detect().unwrap();
+        let signal = detector.last_stats().unwrap().transliteration.clone().unwrap();
+        assert!(signal.likely);
+        assert_eq!(signal.romanized_lang, "ja-Latn");
+        assert_eq!(signal.romanized_coverage, 1.0);
+        assert_eq!(signal.native_coverage, 0.25);
+
+        let mut detector = factory.create(None);
+        detector.append("a b c d");
+        detector.detect().unwrap();
+        assert!(!detector.last_stats().unwrap().transliteration.as_ref().unwrap().likely);
+    }
+
+    #[test]
+    fn test_set_priors() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.append("a b c d");
+        assert_eq!(detector.detect().unwrap(), "fr");
+        detector.set_priors(HashMap::from([("en", 3.0), ("fr", 1.0)])).unwrap();
+        assert_eq!(detector.prior_map, Some(vec![0.75, 0.25, 0.0]));
+        // A language without a prior can not be detected
+        detector.set_priors(HashMap::from([("en", 1.0), ("ja", 1.0)])).unwrap();
+        assert_eq!(detector.detect().unwrap(), "en");
+        assert!(matches!(detector.set_priors(HashMap::from([("xx", 1.0)])), Err(DetectorError::UnknownLanguage(lang)) if lang == "xx"));
+        assert!(matches!(detector.set_priors(HashMap::from([("en", -1.0)])), Err(DetectorError::InvalidPrior(_))));
+        assert!(matches!(detector.set_priors(HashMap::from([("en", 0.0)])), Err(DetectorError::InvalidPrior(_))));
+
+        factory.set_priors(HashMap::from([("ja", 1.0), ("en", 1.0)])).unwrap();
+        assert_eq!(factory.create(None).prior_map, Some(vec![0.5, 0.0, 0.5]));
+        assert!(factory.set_priors(HashMap::from([("xx", 1.0)])).is_err());
+        // The previous priors are kept on error
+        assert_eq!(factory.create(None).prior_map, Some(vec![0.5, 0.0, 0.5]));
+    }
+
+    #[test]
+    fn test_prior_validation() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.append("\u{3042}");
+        detector.prior_map = Some(vec![1.0, -1.0, 0.0]);
+        assert!(matches!(detector.detect(), Err(DetectorError::InvalidPrior(_))));
+        detector.prior_map = Some(vec![1.0, 1.0]);
+        assert!(matches!(detector.detect(), Err(DetectorError::InvalidPrior(_))));
+        detector.prior_map = Some(vec![f64::NAN, 1.0, 1.0]);
+        assert!(matches!(detector.detect(), Err(DetectorError::InvalidPrior(_))));
+        // Priors are renormalized, and ja has no chance without a floor
+        detector.prior_map = Some(vec![2.0, 2.0, 0.0]);
+        assert_ne!(detector.detect().unwrap(), "ja");
+        detector.langprob = None;
+        detector.prior_floor = 0.05;
+        assert_eq!(detector.detect().unwrap(), "ja");
+        detector.langprob = None;
+        detector.prior_floor = 1.0;
+        assert!(matches!(detector.detect(), Err(DetectorError::InvalidPrior(_))));
+        // Filtering out all languages with a prior leaves nothing to detect
+        detector.prior_floor = 0.0;
+        detector.prior_map = Some(vec![1.0, 0.0, 0.0]);
+        detector.restrict_languages(&["ja"]);
+        assert!(matches!(detector.detect(), Err(DetectorError::InvalidPrior(_))));
+
+        let factory = DetectorFactory::new()
+            .with_word_lang_prob_map(factory.profile_data.to_word_lang_prob_map())
+            .with_langlist(factory.get_lang_list())
+            .with_prior_floor(0.05)
+            .build();
+        assert_eq!(factory.create(None).prior_floor, 0.05);
+    }
+
+    #[test]
+    fn test_detector_builder() {
+        let mut factory = setup_factory();
+        factory.params.prob_threshold = 0.99;
+        factory.params.iteration_limit = 10;
+        let detector = factory.create(Some(0.2));
+        assert_eq!(detector.alpha, 0.2);
+        assert_eq!(detector.prob_threshold, 0.99);
+        assert_eq!(detector.iteration_limit, 10);
+
+        let mut detector = factory.detector_builder()
+            .with_seed(Some(1))
+            .with_trials(0)
+            .with_max_text_length(1)
+            .with_prob_threshold(1.0)
+            .build();
+        assert_eq!(detector.n_trial, 1);
+        assert_eq!(detector.iteration_limit, 10);
+        detector.append("ab");
+        assert_eq!(detector.preprocessed_text(), "a");
+        // No language can exceed a threshold of 1.0
+        assert_eq!(detector.detect().unwrap(), "unknown");
+    }
+
+    #[test]
+    fn test_base_freq() {
+        let mut factory = setup_factory();
+        // 5 + 4 + 4 n-grams over 3 languages
+        assert!((factory.derived_base_freq() - 5.0 * 13.0 / 3.0).abs() < 1e-9);
+        assert_eq!(DetectorFactory::new().build().derived_base_freq(), Detector::BASE_FREQ);
+
+        let top_prob = |factory: &DetectorFactory| {
+            let mut detector = factory.detector_builder().with_seed(Some(0)).with_trials(1).with_iteration_limit(5).build();
+            detector.append("d");
+            detector.get_probabilities_full().unwrap()[0].prob
+        };
+        let sharp = top_prob(&factory);
+        factory.set_base_freq(factory.derived_base_freq());
+        assert_eq!(factory.create(None).base_freq, factory.params.base_freq);
+        assert!(top_prob(&factory) < sharp);
+    }
+
+    #[test]
+    fn test_append_preprocessing() {
+        let factory = setup_factory();
+        let mut detector = factory.create(None);
+        detector.append("a  b https://example.com/x?y=1 c mail@example.com d");
+        assert_eq!(detector.preprocessed_text(), "a b c d");
+        detector.reset();
+        detector.append("Ca\u{0300} http");
+        assert_eq!(detector.preprocessed_text(), "C\u{00E0} http");
+
+        detector.reset();
+        detector.scrubbing = TextScrubbing::Disabled;
+        detector.append("a https://b.c");
+        assert_eq!(detector.preprocessed_text(), "a https://b.c");
+        detector.reset();
+        detector.scrubbing = TextScrubbing::Disabled.with_pattern(regex::Regex::new("[0-9]+").unwrap());
+        detector.append("a 12 b@c.d");
+        assert_eq!(detector.preprocessed_text(), "a b@c.d");
+        let factory = DetectorFactory::new().with_scrubbing(TextScrubbing::default().with_pattern(regex::Regex::new("x").unwrap())).build();
+        assert!(matches!(&factory.create(None).scrubbing, TextScrubbing::Custom(patterns) if patterns.len() == 3));
+    }
+
+    #[test]
+    fn test_normalization() {
+        let factory = setup_factory();
+        let mut detector = factory.create(None);
+        detector.append("ab-c");
+        assert_eq!(detector.extract_ngrams().1, 9);
+        detector.normalization = Normalization::Lenient;
+        // "-", "b-", "ab-", "-c" and "b-c" instead of "b ", "ab " and " c"
+        assert_eq!(detector.extract_ngrams().1, 11);
+        let factory = DetectorFactory::new().with_normalization(Normalization::Lenient).build();
+        assert_eq!(factory.create(None).normalization, Normalization::Lenient);
+    }
+
+    #[test]
+    fn test_probabilities_with_stats() {
+        let factory = setup_factory();
+        let (probabilities, stats) = factory.get_probabilities_with_stats("a xyz", None).unwrap();
+        assert_eq!(probabilities[0].lang, Some(Lang::En));
+        // Only "a" of "a", " a", "a ", " a ", "x", " x", "y", "xy", " xy", "z", "yz" and "xyz"
+        assert_eq!((stats.matched_ngrams, stats.total_ngrams), (1, 12));
+        assert!(matches!(factory.get_probabilities_with_stats("xyz", None), Err(DetectorError::NoFeatures)));
+    }
+
+    #[test]
+    fn test_detect_with_confidence() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let result = factory.detect_with_confidence("a", None).unwrap();
+        assert_eq!(result.language.lang, Some(Lang::En));
+        assert!(result.margin > 0.99 && result.entropy < 0.01);
+        assert!((result.coverage - 0.5).abs() < 1e-9);
+        assert!(result.is_reliable());
+        // "b" is as frequent in English as in French
+        let result = factory.detect_with_confidence("b", None).unwrap();
+        assert!(result.margin < 0.01 && (result.entropy - 1.0).abs() < 0.01);
+        assert!(!result.is_reliable());
+        assert!(matches!(factory.detect_with_confidence("1", None), Err(DetectorError::NoFeatures)));
+    }
+
+    #[test]
+    fn test_append_after_detection() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.append("a");
+        assert_eq!(detector.detect().unwrap(), "en");
+        detector.append(" \u{3042}\u{3042}\u{3042}\u{3044}\u{3046}\u{3048}");
+        assert_eq!(detector.detect().unwrap(), "ja");
+        // Detecting again without appending reuses the estimate
+        assert_eq!(detector.detect().unwrap(), "ja");
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.exclude_languages(&["fr"]);
+        detector.append("\u{3042}\u{3044}");
+        assert_eq!(detector.detect().unwrap(), "ja");
+        detector.reset();
+        assert_eq!(detector.preprocessed_text(), "");
+        assert!(detector.langprob.is_none());
+        assert!(detector.last_stats().is_none());
+        assert!(matches!(detector.detect(), Err(DetectorError::NoFeatures)));
+        detector.reset();
+        // The language filter survives a reset
+        detector.append("d d d");
+        assert_eq!(detector.detect().unwrap(), "en");
+    }
+
+    #[test]
+    fn test_full_probabilities() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.append("b d");
+        let thresholded = detector.get_probabilities().unwrap();
+        let full = detector.get_probabilities_full().unwrap();
+        assert_eq!(full.len(), 3);
+        assert!(thresholded.len() < full.len());
+        assert!((full.iter().map(|l| l.prob).sum::<f64>() - 1.0).abs() < 1e-9);
+        assert!(full.windows(2).all(|w| w[0].prob >= w[1].prob));
+        assert_eq!(full[0], thresholded[0]);
+        let top = detector.top_k(2).unwrap();
+        assert_eq!(top, full[..2].to_vec());
+        assert_eq!(detector.top_k(10).unwrap().len(), 3);
+        assert_eq!(factory.top_k("b d", 1, None).unwrap(), full[..1].to_vec());
+        assert_eq!(factory.get_probabilities_full("b d", None).unwrap(), full);
+    }
+
+    #[test]
+    fn test_alternatives() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.append("b d");
+        let full = detector.get_probabilities_full().unwrap();
+        assert!(detector.alternatives(0.0).unwrap().is_empty());
+        let alternatives = detector.alternatives(1.0).unwrap();
+        assert_eq!(alternatives, full[1..].iter().filter(|l| l.prob > 0.0).cloned().collect::<Vec<_>>());
+        let gap = full[0].prob - full[1].prob;
+        assert_eq!(detector.alternatives(gap + 1e-9).unwrap(), full[1..2].to_vec());
+        assert_eq!(factory.alternatives("b d", gap + 1e-9, None).unwrap(), full[1..2].to_vec());
+    }
+
+    #[test]
+    fn test_script_hint() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        assert_eq!(detector.detect_script(), None);
+        detector.restrict_to_script(Script::Hiragana);
+        detector.append("a b");
+        assert_eq!(detector.detect_script(), Some(Script::Latin));
+        assert!(matches!(detector.detect(), Err(DetectorError::NoFeatures)));
+
+        let options = DetectOptions::new().with_script_hint(Script::Latin);
+        assert_eq!(factory.detect_with_options("a \u{3042}", &options).unwrap(), "en");
+        assert_eq!(factory.detect_with_options("a \u{3042}", &DetectOptions::new()).unwrap(), "ja");
+        // The hint combines with the language filter
+        factory.language_filter = Some(LanguageFilter::Deny(vec![Lang::En]));
+        assert_eq!(factory.detect_with_options("a \u{3042}", &options).unwrap(), "fr");
+    }
+
+    #[test]
+    fn test_script_prefilter() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let text = "\u{3042}\u{3042}\u{3042} a b";
+        let plain = factory.get_probabilities_full(text, None).unwrap();
+        let mut detector = factory.detector_builder().with_script_prefilter(true).build();
+        detector.record_trials = true;
+        detector.append(text);
+        let filtered = detector.get_probabilities_full().unwrap();
+        assert_eq!(filtered[0].lang, Some(Lang::Ja));
+        // Latin languages are no candidates for mostly Hiragana text
+        assert!(plain[1].prob > 0.0);
+        assert!(filtered[1..].iter().all(|l| l.prob == 0.0));
+        assert!(detector.last_stats().unwrap().trials.iter().all(|trial| trial.probabilities.len() == 3));
+        // Without a language using the script the filter is skipped
+        detector.reset();
+        detector.append("\u{0436} a");
+        assert_eq!(detector.detect().unwrap(), "en");
+        assert!(DetectorFactory::new().with_script_prefilter(true).build().create(None).script_prefilter);
+        // Language filters give the same probabilities with or without compaction
+        let mut detector = factory.create(None);
+        detector.restrict_languages(&["en", "fr"]);
+        detector.append("a b c d");
+        let restricted = detector.get_probabilities_full().unwrap();
+        let mut detector = factory.create(None);
+        detector.prior_map = Some(vec![1.0, 1.0, 0.0]);
+        detector.append("a b c d");
+        assert_eq!(detector.get_probabilities_full().unwrap(), restricted);
+    }
+
+    #[test]
+    fn test_boost() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        // "b" is as frequent in en as in fr
+        let plain = factory.get_probabilities("b", None).unwrap();
+        assert!((plain[0].prob - 0.5).abs() < 1e-9);
+        let options = DetectOptions::new().with_boost("fr", 3.0).with_boost(Lang::Fr, 1.5).with_boost("xx", 2.0);
+        assert_eq!(options.boosts.len(), 2);
+        let boosted = factory.detect_lang_with_options("b", &options).unwrap();
+        assert_eq!(boosted.lang.as_deref(), Some("fr"));
+        assert!((boosted.prob - 0.6).abs() < 1e-9);
+        // Probabilities stay a distribution
+        let mut detector = factory.create_with_options(&options);
+        assert_eq!(detector.boost_map, Some(vec![1.0, 1.5, 1.0]));
+        detector.append("a");
+        detector.detect().unwrap();
+        assert!((detector.langprob.as_ref().unwrap().iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        assert!(matches!(detector.set_boosts(HashMap::from([("xx", 1.0)])), Err(DetectorError::UnknownLanguage(_))));
+        assert!(matches!(detector.set_boosts(HashMap::from([("en", f64::NAN)])), Err(DetectorError::InvalidBoost(_))));
+        detector.boost_map = Some(vec![1.0]);
+        detector.langprob = None;
+        assert!(matches!(detector.detect(), Err(DetectorError::InvalidBoost(_))));
+        // Boosting every candidate to zero keeps the estimate
+        detector.set_boosts(HashMap::from([("en", 0.0), ("fr", 0.0), ("ja", 0.0)])).unwrap();
+        assert_eq!(detector.detect().unwrap(), "en");
+    }
+
+    #[test]
+    fn test_history() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let options = DetectOptions::new().with_history(&[("fr", 9.0), ("en", 1.0), ("xx", 5.0)]);
+        let detector = factory.create_with_options(&options);
+        assert_eq!(detector.prior_map, Some(vec![0.1, 0.9, 0.0]));
+        assert_eq!(detector.prior_floor, DetectOptions::HISTORY_FLOOR);
+        // "b" is as frequent in en as in fr, the history decides
+        assert_eq!(factory.detect_with_options("b", &options).unwrap(), "fr");
+        // Languages missing from the history are still detected
+        assert_eq!(factory.detect_with_options("\u{3042}\u{3042}", &options).unwrap(), "ja");
+        let options = DetectOptions::new().with_history(&[("fr", -1.0)]);
+        assert!(matches!(factory.detect_with_options("b", &options), Err(DetectorError::InvalidPrior(_))));
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.append("d d c a");
+        let explanations = detector.explain(2, 3).unwrap();
+        assert_eq!(explanations.len(), 2);
+        let (fr, en) = (&explanations[0], &explanations[1]);
+        assert_eq!(fr.language.lang, Some(Lang::Fr));
+        assert_eq!(en.language.lang, Some(Lang::En));
+        // "d" is three times as frequent in fr as in en, "a" the other way round
+        assert_eq!(fr.ngrams.iter().map(|c| c.ngram.as_str()).collect::<Vec<_>>(), ["d", "c"]);
+        assert_eq!(fr.ngrams[0].count, 2);
+        assert_eq!(fr.ngrams[0].probabilities.iter().map(|l| l.lang.clone().unwrap()).collect::<Vec<_>>(), [Lang::Fr, Lang::En]);
+        assert_eq!(en.ngrams.iter().map(|c| c.ngram.as_str()).collect::<Vec<_>>(), ["a"]);
+        // A single language is explained against the mean of all others
+        let mut detector = factory.create(None);
+        detector.append("a c");
+        let ngrams = |explanation: &LanguageExplanation| explanation.ngrams.iter().map(|c| c.ngram.clone()).collect::<Vec<_>>();
+        assert_eq!(ngrams(&detector.explain(2, 10).unwrap()[0]), ["a"]);
+        assert_eq!(ngrams(&detector.explain(1, 10).unwrap()[0]), ["a", "c"]);
+    }
+
+    #[test]
+    fn test_append_ngrams() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.append_ngrams(["d", "d", "xyz"]);
+        assert_eq!(detector.detect().unwrap(), "fr");
+        assert_eq!(detector.last_stats().map(|stats| (stats.matched_ngrams, stats.total_ngrams)), Some((2, 3)));
+        // Text is detected together with the n-grams
+        detector.append("\u{3042}\u{3042}\u{3042}\u{3042}\u{3042}\u{3042}");
+        assert_eq!(detector.detect().unwrap(), "ja");
+        detector.reset();
+        detector.append_ngrams(vec![String::from("a")]);
+        assert_eq!(detector.detect().unwrap(), "en");
+        detector.reset();
+        detector.append_ngrams(["x"]);
+        assert!(matches!(detector.detect(), Err(DetectorError::NoFeatures)));
+    }
+
+    #[test]
+    fn test_score_languages() {
+        let factory = setup_factory();
+        let mut detector = factory.create(None);
+        // "d" is three times as frequent in fr as in en, "x" is unknown
+        detector.append("d d x");
+        let scores = detector.score_languages(&["en", "fr"]).unwrap();
+        assert_eq!(scores[0].0, Lang::Fr);
+        assert!((scores[0].1 - 0.9).abs() < 0.001);
+        assert!((scores[0].1 + scores[1].1 - 1.0).abs() < 1e-9);
+        assert!(matches!(detector.score_languages(&["en", "xx"]), Err(DetectorError::UnknownLanguage(lang)) if lang == "xx"));
+        assert!(matches!(detector.score_languages(&[Lang::Ja]), Err(DetectorError::NoFeatures)));
+        assert_eq!(factory.score_languages("a", &["fr", "en"], None).unwrap()[0].0, Lang::En);
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        detector.append("d d c");
+        let fr = detector.verify("fr").unwrap();
+        let en = detector.verify(Lang::En).unwrap();
+        assert!(fr.is_match && !en.is_match);
+        assert!((fr.margin + en.margin).abs() < 1e-9 && fr.margin > 0.0);
+        assert!(matches!(detector.verify("xx"), Err(DetectorError::UnknownLanguage(_))));
+        // "b" is as frequent in en as in fr, so neither is more probable than the rest
+        factory.params.verify_threshold = 0.4;
+        let result = factory.verify("b", "en", None).unwrap();
+        assert!(result.is_match && result.margin.abs() < 1e-9);
+        let mut detector = factory.detector_builder().with_verify_threshold(0.6).build();
+        detector.append("b");
+        assert!(!detector.verify("en").unwrap().is_match);
+    }
+
+    #[test]
+    fn test_detect_with_fallback() {
+        let factory = setup_factory();
+        let fallback = |text: &str| match factory.detect_with_fallback(text, None).unwrap() {
+            Detection::Fallback(fallback) => fallback,
+            Detection::Language(language) => panic!("detected {}", language),
+        };
+        assert_eq!(fallback(" "), FallbackResult { reason: FallbackReason::EmptyText, script: None, guess: None });
+        assert_eq!(fallback("12 :)").reason, FallbackReason::NoLetters);
+        assert_eq!(fallback("жж"), FallbackResult { reason: FallbackReason::UnknownNGrams, script: Some(Script::Cyrillic), guess: None });
+        // Latin is written by both en and fr
+        assert_eq!(fallback("zz").guess, None);
+        let mut detector = factory.create(None);
+        detector.restrict_languages(&["fr"]);
+        detector.append("zz");
+        assert!(matches!(detector.detect_with_fallback(), Ok(Detection::Fallback(fallback)) if fallback.guess == Some(Lang::Fr)));
+        assert!(matches!(factory.detect_with_fallback("a a", None), Ok(Detection::Language(language)) if language.lang == Some(Lang::En)));
+    }
+
+    #[test]
+    fn test_detect_until() {
+        let mut factory = setup_factory();
+        factory.seed = Some(0);
+        let mut detector = factory.create(None);
+        let mut chunks = ["!!!", "a a a", "b", "c"].into_iter();
+        let language = detector.detect_until(chunks.by_ref(), 0.9).unwrap();
+        assert_eq!(language.lang.as_deref(), Some("en"));
+        // Stopped right after the second chunk
+        assert_eq!(chunks.count(), 2);
+
+        let mut detector = factory.create(None);
+        assert!(detector.detect_until(["!!!", "???"], 0.9).is_err());
+
+        // Chunks between checkpoints are only appended, the last estimate covers all of them
+        let mut detector = factory.create(None);
+        let language = detector.detect_until(["a", "b", "c", "a a a a"], 1.1).unwrap();
+        assert_eq!(language.lang.as_deref(), Some("en"));
+        assert_eq!(detector.preprocessed_text(), "a b c a a a a ");
+    }
+
+    #[test]
+    fn test_cleaning_text() {
+        let factory = setup_factory();
+        let mut detector = factory.create(None);
+        detector.append("\u{3042}\u{3044}\u{3046}\u{3048}\u{304A} ab");
+        assert_eq!(detector.cleaning_text(), "\u{3042}\u{3044}\u{3046}\u{3048}\u{304A} ");
+        assert_eq!(detector.detect().unwrap(), "ja");
+        // Detection does not drop the Latin letters of the appended text
+        assert_eq!(detector.preprocessed_text(), "\u{3042}\u{3044}\u{3046}\u{3048}\u{304A} ab");
+        detector.append(" abcdefghijklmnop");
+        assert_eq!(detector.cleaning_text(), detector.preprocessed_text());
+    }
+}
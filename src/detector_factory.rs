@@ -1,11 +1,34 @@
 use std::fs;
+use std::ops::Range;
 use std::path::Path;
+use serde::Serialize;
 use serde_json;
 use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicU64, Ordering};
 use crate::utils::lang_profile::LangProfile;
-use crate::detector::{Detector, DetectorError};
-use crate::language::Language;
-use crate::utils::lang_profile::LangProfileJson;
+use crate::detector::{DetectOptions, DetectionResult, DetectionStats, Detector, Detection, DetectorBuilder, DetectorError, LanguageExplanation, LanguageFilter, TextScrubbing, VerificationResult};
+use crate::language::{Lang, Language};
+use crate::utils::lang_profile::{profile_file_stem, read_limited, LangProfileJson, LangProfileJsonError, ProfileLimits};
+use crate::utils::email::strip_email;
+use crate::profile_data::ProfileData;
+use crate::cache::DetectionCache;
+use crate::utils::script::Script;
+use crate::utils::ngram::Normalization;
+use crate::segmenter::{DefaultSegmenter, Segmenter};
+
+/// Built-in profiles compiled into the binary by `build.rs`.
+#[cfg(feature = "embedded-profiles")]
+mod embedded {
+    include!(concat!(env!("OUT_DIR"), "/embedded_profiles.rs"));
+}
+
+/// Built-in languages selected with `lang-*` features, generated by `build.rs`.
+/// Embedded profiles are already filtered by `build.rs`.
+#[cfg_attr(feature = "embedded-profiles", allow(dead_code))]
+mod built_in_languages {
+    include!(concat!(env!("OUT_DIR"), "/languages.rs"));
+}
 
 /// Errors that can occur when working with DetectorFactory.
 #[derive(Debug, Clone)]
@@ -14,6 +37,24 @@ pub enum DetectorFactoryError {
     DuplicatedLanguage(String),
     /// At least 2 languages are required for detection.
     NotEnoughProfiles,
+    /// The profile index does not fit into the requested number of languages.
+    IndexOutOfRange { index: usize, langsize: usize },
+    /// The profile index is not the next free column of the factory.
+    IndexMismatch { index: usize, expected: usize },
+    /// The requested number of languages does not match the probability vectors already stored.
+    LangsizeMismatch { expected: usize, found: usize },
+    /// Reading or writing a file failed.
+    IoError(String),
+    /// Binary profile data is malformed.
+    InvalidFormat(String),
+    /// A profile exceeds the factory's `ProfileLimits`.
+    LimitExceeded(String),
+    /// A language profile has no name.
+    UnnamedProfile,
+    /// The built-in profiles could not be loaded (e.g. the profiles directory is missing).
+    BuiltInProfiles(String),
+    /// The language is not loaded in the factory.
+    LanguageNotFound(String),
 }
 
 impl std::fmt::Display for DetectorFactoryError {
@@ -25,7 +66,228 @@ impl std::fmt::Display for DetectorFactoryError {
             DetectorFactoryError::NotEnoughProfiles => {
                 write!(f, "Two languages at least are required")
             }
+            DetectorFactoryError::IndexOutOfRange { index, langsize } => {
+                write!(f, "Profile index {} is out of range for {} languages", index, langsize)
+            }
+            DetectorFactoryError::IndexMismatch { index, expected } => {
+                write!(f, "Profile index {} does not match the next language index {}", index, expected)
+            }
+            DetectorFactoryError::LangsizeMismatch { expected, found } => {
+                write!(f, "Requested {} languages, but the factory already holds {} languages/probabilities", expected, found)
+            }
+            DetectorFactoryError::IoError(e) => write!(f, "I/O error: {}", e),
+            DetectorFactoryError::InvalidFormat(e) => write!(f, "Invalid binary profile: {}", e),
+            DetectorFactoryError::LimitExceeded(e) => write!(f, "Profile limit exceeded: {}", e),
+            DetectorFactoryError::UnnamedProfile => write!(f, "Language profile has no name"),
+            DetectorFactoryError::BuiltInProfiles(e) => write!(f, "Failed to load built-in profiles: {}", e),
+            DetectorFactoryError::LanguageNotFound(lang) => write!(f, "Language not found: {}", lang),
+        }
+    }
+}
+
+/// The probabilities of a single language as stored in a factory.
+///
+/// Returned by `DetectorFactory::delete_profile()`; pass it to `restore_profile()` to add
+/// the language again without the original `LangProfile`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProfileColumn {
+    /// The language identifier.
+    pub lang: Lang,
+    /// Probability of every n-gram the language knows.
+    pub probabilities: HashMap<String, f64>,
+    /// Declared scripts of the language; None if they were derived from the probabilities.
+    pub scripts: Option<Vec<Script>>,
+}
+
+/// Summary of the n-grams a single language contributes to a factory.
+///
+/// Returned by `DetectorFactory::coverage_report()` to help diagnose why a
+/// language rarely wins (e.g. a custom profile whose n-grams are mostly shared
+/// with a bigger, closely related language).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageCoverage {
+    /// The language identifier.
+    pub lang: Lang,
+    /// Number of n-grams with non-zero probability for each length: [1-gram, 2-gram, 3-gram].
+    pub ngram_counts: [usize; 3],
+    /// Number of the language's n-grams that are also present in at least one other language.
+    pub shared: usize,
+    /// Number of the language's n-grams that no other language has.
+    pub exclusive: usize,
+    /// The language sharing the most n-grams with this one and the size of that overlap.
+    pub most_overlapping: Option<(Lang, usize)>,
+    /// Scripts the language is written in, see `DetectorFactory::language_scripts`.
+    pub scripts: Vec<Script>,
+}
+
+impl LanguageCoverage {
+    /// Total number of n-grams contributed by the language.
+    pub fn total(&self) -> usize {
+        self.ngram_counts.iter().sum()
+    }
+}
+
+impl std::fmt::Display for LanguageCoverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: 1-grams={}, 2-grams={}, 3-grams={}, shared={}, exclusive={}",
+            self.lang, self.ngram_counts[0], self.ngram_counts[1], self.ngram_counts[2], self.shared, self.exclusive
+        )?;
+        if !self.scripts.is_empty() {
+            let scripts: Vec<&str> = self.scripts.iter().map(|script| script.name()).collect();
+            write!(f, ", scripts={}", scripts.join("+"))?;
+        }
+        if let Some((lang, overlap)) = &self.most_overlapping {
+            write!(f, ", most overlapping={} ({})", lang, overlap)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checksum of a single language profile, part of `ModelInfo`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProfileInfo {
+    /// The language identifier.
+    pub lang: Lang,
+    /// Number of n-grams with non-zero probability.
+    pub ngrams: usize,
+    /// FNV-1a hash of the n-grams and their probabilities, as 16 hex digits.
+    ///
+    /// Profiles carry no version number, so the checksum identifies the profile revision:
+    /// it changes whenever any probability of the language changes.
+    pub checksum: String,
+}
+
+/// Serializable summary of the model behind a `DetectorFactory`.
+///
+/// Returned by `DetectorFactory::model_info()`, e.g. to log at startup or to expose on a
+/// health endpoint, so the model serving a result can be identified later.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ModelInfo {
+    /// Version of this crate.
+    pub crate_version: String,
+    /// Enabled cargo features affecting the built-in profiles or the API.
+    pub features: Vec<String>,
+    /// One entry per language, in `langlist` order.
+    pub languages: Vec<ProfileInfo>,
+    /// Algorithm parameters of the created detectors.
+    pub params: DetectorParams,
+    /// Seed of the created detectors.
+    pub seed: Option<u64>,
+    /// Hash of the language order, the profile checksums, the parameters and the seed, as 16 hex digits.
+    ///
+    /// Two factories with the same snapshot hash return the same results.
+    pub snapshot_hash: String,
+}
+
+impl ModelInfo {
+    /// Serializes the model info to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Incremental 64-bit FNV-1a hash, stable across platforms and releases (unlike `DefaultHasher`).
+struct Fnv1a(u64);
+
+impl Fnv1a {
+    fn new() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn hex(&self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
+
+/// Caller-defined label set that detected languages are collapsed onto.
+///
+/// Every language is mapped to a label, languages without an explicit label fall
+/// into the `other` label. Probabilities of languages sharing a label are summed.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::detector_factory::{DetectorFactory, LabelSet};
+///
+/// let labels = LabelSet::new("other")
+///     .with_label("en", &["en"])
+///     .with_label("es", &["es", "ca", "pt"]);
+/// let factory = DetectorFactory::default()
+///     .with_label_set(Some(labels))
+///     .build();
+/// let label = factory.detect("Bonjour le monde, comment allez-vous?", None).unwrap();
+/// assert_eq!(label, "other");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelSet {
+    /// Language identifier to label mapping.
+    pub mapping: HashMap<String, String>,
+    /// Label for languages not present in the mapping.
+    pub other: String,
+}
+
+impl LabelSet {
+    /// Creates an empty label set where every language maps to `other`.
+    ///
+    /// # Arguments
+    /// * `other` - Label for languages without an explicit label.
+    pub fn new(other: &str) -> Self {
+        LabelSet {
+            mapping: HashMap::new(),
+            other: other.to_string(),
+        }
+    }
+
+    /// Maps the given languages onto a label.
+    ///
+    /// # Arguments
+    /// * `label` - The label reported for these languages.
+    /// * `langs` - Language identifiers collapsed into the label.
+    pub fn with_label(mut self, label: &str, langs: &[&str]) -> Self {
+        for lang in langs {
+            self.mapping.insert(lang.to_string(), label.to_string());
+        }
+        self
+    }
+
+    /// Returns the label a language is collapsed onto.
+    pub fn label_for(&self, lang: &str) -> &str {
+        self.mapping.get(lang).map_or(self.other.as_str(), |label| label.as_str())
+    }
+
+    /// Collapses a full probability vector onto the label set.
+    ///
+    /// # Arguments
+    /// * `langlist` - Language identifiers in the order of `langprob`.
+    /// * `langprob` - Probability of every language.
+    /// * `prob_threshold` - Labels must have a higher probability to be reported.
+    ///
+    /// # Returns
+    /// Labels with probability above `prob_threshold`, sorted by probability descending.
+    pub fn collapse(&self, langlist: &[Lang], langprob: &[f64], prob_threshold: f64) -> Vec<Language> {
+        let mut sums: Vec<(&str, f64)> = Vec::new();
+        for (lang, &p) in langlist.iter().zip(langprob.iter()) {
+            let label = self.label_for(lang);
+            match sums.iter_mut().find(|(l, _)| *l == label) {
+                Some((_, sum)) => *sum += p,
+                None => sums.push((label, p)),
+            }
         }
+        let mut result: Vec<Language> = sums
+            .into_iter()
+            .filter(|(_, p)| *p > prob_threshold)
+            .map(|(label, p)| Language::new(Some(Lang::from(label)), p))
+            .collect();
+        result.sort_by(|a, b| b.prob.total_cmp(&a.prob));
+        result
     }
 }
 
@@ -47,15 +309,106 @@ impl std::fmt::Display for DetectorFactoryError {
 /// ```
 #[derive(Clone)]
 pub struct DetectorFactory {
-    /// Word-to-language probability mapping for all loaded languages.
-    pub word_lang_prob_map: HashMap<String, Vec<f64>>,
-    /// List of language identifiers in the same order as probability vectors.
-    pub langlist: Vec<String>,
+    /// Per-language n-gram probabilities for all loaded languages.
+    ///
+    /// Shared with every created detector; modifying the factory copies it first
+    /// if detectors still hold a reference.
+    pub profile_data: Arc<ProfileData>,
+    /// List of language identifiers in the same order as the probability columns.
+    pub langlist: Arc<Vec<Lang>>,
     /// Optional seed for reproducible randomization.
     pub seed: Option<u64>,
+    /// Optional label set the shortcut detection methods collapse their results onto.
+    pub label_set: Option<LabelSet>,
+    /// Label reported when no language is detected (e.g. "und" for BCP-47 schemas).
+    pub unknown_label: String,
+    /// Whether profiles loaded in bulk are ordered by language code.
+    pub sort_profiles: bool,
+    /// Optional result cache shared by the shortcut detection methods.
+    pub cache: Option<Arc<DetectionCache>>,
+    /// Optional restriction of created detectors to a subset of the loaded languages.
+    pub language_filter: Option<LanguageFilter>,
+    /// Normalized prior probabilities by language code, applied to created detectors.
+    pub priors: Option<HashMap<String, f64>>,
+    /// Minimum prior of every language, see `Detector::prior_floor`.
+    pub prior_floor: f64,
+    /// Algorithm parameters of created detectors.
+    pub params: DetectorParams,
+    /// Whether batch methods classify texts that only differ in whitespace once.
+    pub batch_dedup: bool,
+    /// Sentence and word splitter of `detect_sentences` and `detect_words`.
+    pub segmenter: Arc<dyn Segmenter>,
+    /// Removal of URLs, emails or other noise, applied by created detectors.
+    pub scrubbing: TextScrubbing,
+    /// Size limits of loaded profiles and models, see `with_profile_limits`.
+    pub profile_limits: ProfileLimits,
+    /// Languages masked during detection without removing their profiles, see `set_language_enabled`.
+    pub disabled_languages: Vec<Lang>,
+    /// Identifier of the current languages and settings, part of the result cache key.
+    generation: u64,
+}
+
+/// Algorithm parameters a `DetectorFactory` applies to the detectors it creates.
+///
+/// See the fields of `Detector` with the same names.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DetectorParams {
+    /// Alpha smoothing parameter, used unless `create()` is given one.
+    pub alpha: f64,
+    /// Number of EM trials averaged per detection.
+    pub n_trial: usize,
+    /// Maximum number of characters taken from every appended text.
+    pub max_text_length: usize,
+    /// Minimum probability for a language to be reported.
+    pub prob_threshold: f64,
+    /// Minimum probability for `verify` to accept a language.
+    pub verify_threshold: f64,
+    /// Maximum number of EM iterations per trial.
+    pub iteration_limit: usize,
+    /// Probability of the top language at which a trial stops early.
+    pub conv_threshold: f64,
+    /// Smoothing denominator.
+    pub base_freq: f64,
+    /// Whether Arabic-script letter markers are used as additional evidence.
+    pub arabic_markers: bool,
+    /// Whether function words decide between Hindi, Marathi and Nepali.
+    pub devanagari_tiebreak: bool,
+    /// Whether only languages written in the dominant script of a text are candidates.
+    pub script_prefilter: bool,
+    /// Character normalization rules of n-gram extraction.
+    pub normalization: Normalization,
+    /// Whether the EM algorithm is logged, see `Detector::verbose`.
+    ///
+    /// Not part of `model_info`, since logging does not change results.
+    #[serde(skip)]
+    pub verbose: bool,
+}
+
+impl Default for DetectorParams {
+    fn default() -> Self {
+        DetectorParams {
+            alpha: Detector::ALPHA_DEFAULT,
+            n_trial: Detector::N_TRIAL_DEFAULT,
+            max_text_length: Detector::MAX_TEXT_LENGTH_DEFAULT,
+            prob_threshold: Detector::PROB_THRESHOLD,
+            verify_threshold: Detector::VERIFY_THRESHOLD,
+            iteration_limit: Detector::ITERATION_LIMIT,
+            conv_threshold: Detector::CONV_THRESHOLD,
+            base_freq: Detector::BASE_FREQ,
+            arabic_markers: false,
+            devanagari_tiebreak: false,
+            script_prefilter: false,
+            normalization: Normalization::Strict,
+            verbose: false,
+        }
+    }
 }
 
 impl DetectorFactory {
+    /// Number of documents per rayon thread that `detect_iter` processes at once.
+    #[cfg(feature = "rayon")]
+    const ITER_CHUNK_PER_THREAD: usize = 16;
+
     /// Creates a new DetectorFactory builder.
     ///
     /// Use the builder pattern to configure the factory before calling `build()`.
@@ -69,12 +422,27 @@ impl DetectorFactory {
     ///     .with_seed(Some(42))
     ///     .build();
     /// ```
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> DetectorFactoryBuilder {
         DetectorFactoryBuilder {
             factory: DetectorFactory {
-                word_lang_prob_map: HashMap::new(),
-                langlist: Vec::new(),
+                profile_data: Arc::new(ProfileData::default()),
+                langlist: Arc::new(Vec::new()),
                 seed: None,
+                label_set: None,
+                unknown_label: Detector::UNKNOWN_LANG.to_string(),
+                sort_profiles: true,
+                cache: None,
+                language_filter: None,
+                priors: None,
+                prior_floor: 0.0,
+                params: DetectorParams::default(),
+                batch_dedup: false,
+                segmenter: Arc::new(DefaultSegmenter),
+                scrubbing: TextScrubbing::default(),
+                profile_limits: ProfileLimits::default(),
+                disabled_languages: Vec::new(),
+                generation: 0,
             },
         }
     }
@@ -83,7 +451,13 @@ impl DetectorFactory {
     ///
     /// This method loads the 55 built-in language profiles from the crate's
     /// profiles directory and returns a builder that can be further re-configured.
-    /// The profiles are cached for performance.
+    /// The profiles are loaded once and cached (see `default_shared()`).
+    /// The set can be narrowed down at compile time with `lang-*` features
+    /// (e.g. `lang-en`, `cyrillic-script`).
+    ///
+    /// If the profiles cannot be loaded the factory has no languages, and every detection
+    /// fails (with the `log` feature a warning is logged); use `try_default()` to get the
+    /// error instead. Selecting a single language with `lang-*` features fails the build.
     ///
     /// # Example
     ///
@@ -94,27 +468,90 @@ impl DetectorFactory {
     ///     .with_seed(Some(42))
     ///     .build();
     /// ```
+    #[allow(clippy::should_implement_trait)]
     pub fn default() -> DetectorFactoryBuilder {
-        use std::sync::Mutex;
-        use lazy_static::lazy_static;
-        lazy_static! {
-            static ref DEFAULT_FACTORY: Mutex<Option<DetectorFactory>> = Mutex::new(None);
+        DetectorFactoryBuilder { factory: Self::default_shared().as_ref().clone() }
+    }
+
+    /// Creates a DetectorFactoryBuilder with all built-in language profiles loaded, see `default()`.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::BuiltInProfiles` if the profiles directory cannot be
+    /// read, and the errors of `load_json_profile` for invalid profiles.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::try_default()?
+    ///     .with_seed(Some(42))
+    ///     .build();
+    /// # Ok::<(), langdetect_rs::detector_factory::DetectorFactoryError>(())
+    /// ```
+    pub fn try_default() -> Result<DetectorFactoryBuilder, DetectorFactoryError> {
+        Ok(DetectorFactoryBuilder { factory: Self::try_default_shared()?.as_ref().clone() })
+    }
+
+    /// Returns a shared handle to the cached factory with all built-in language profiles.
+    ///
+    /// The profiles are loaded on first use only. Subsequent calls do not lock and
+    /// only clone the `Arc`, so the handle can be requested from many threads cheaply.
+    /// Use `default()` instead when the factory needs to be re-configured.
+    ///
+    /// With the `embedded-profiles` feature the profiles are compiled into the binary and
+    /// no filesystem access is needed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default_shared();
+    /// let result = factory.detect("Hello world!", None);
+    /// ```
+    pub fn default_shared() -> Arc<DetectorFactory> {
+        Self::try_default_shared().unwrap_or_else(|_e| {
+            #[cfg(feature = "log")]
+            log::warn!("Built-in profiles could not be loaded, the default factory has no languages: {}", _e);
+            Arc::new(DetectorFactory::new().build())
+        })
+    }
+
+    /// Returns a shared handle to the cached factory with all built-in language profiles, see `default_shared()`.
+    ///
+    /// Loading is attempted once, so a failure is cached and returned on every call.
+    ///
+    /// # Errors
+    /// Returns the errors of `try_default()`.
+    pub fn try_default_shared() -> Result<Arc<DetectorFactory>, DetectorFactoryError> {
+        static DEFAULT_FACTORY: OnceLock<Result<Arc<DetectorFactory>, DetectorFactoryError>> = OnceLock::new();
+        DEFAULT_FACTORY.get_or_init(|| Self::load_built_in().map(Arc::new)).clone()
+    }
+
+    /// Loads the built-in profiles into a new factory.
+    fn load_built_in() -> Result<DetectorFactory, DetectorFactoryError> {
+        let mut factory = DetectorFactory::new().build();
+        #[cfg(feature = "embedded-profiles")]
+        {
+            #[cfg(feature = "log")]
+            log::debug!("Loading {} embedded profiles", embedded::EMBEDDED_PROFILES.len());
+            factory.load_named_json_profiles(embedded::EMBEDDED_PROFILES)?;
         }
+        #[cfg(not(feature = "embedded-profiles"))]
         {
-            let factory_guard = DEFAULT_FACTORY.lock().unwrap();
-            if let Some(factory) = &*factory_guard {
-                return DetectorFactoryBuilder { factory: factory.clone() };
-            }
+            // Load profiles from crate-level "profiles" (or "profiles_small") folder
+            let json_profiles = Self::read_profile_directory(Self::get_default_profiles_path(), usize::MAX, |name| {
+                built_in_languages::SELECTED_LANGUAGES.is_none_or(|languages| languages.contains(&name))
+            }).map_err(DetectorFactoryError::BuiltInProfiles)?;
+            let json_refs: Vec<(&str, &str)> = json_profiles.iter().map(|(name, json)| (name.as_str(), json.as_str())).collect();
+            #[cfg(feature = "log")]
+            log::debug!("Loading {} profiles from {:?}", json_refs.len(), Self::get_default_profiles_path());
+            factory.load_named_json_profiles(&json_refs)?;
         }
-        let mut factory = DetectorFactory::new().build();
-        // Try to load profiles from crate-level "profiles" folder
-        let crate_profiles = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("profiles");
-
-        let _ = factory.load_profile(&crate_profiles);
-        // Cache the factory for future use
-        let mut factory_guard = DEFAULT_FACTORY.lock().unwrap();
-        *factory_guard = Some(factory.clone());
-        DetectorFactoryBuilder { factory }
+        #[cfg(feature = "log")]
+        log::debug!("Loaded {} built-in languages", factory.langlist.len());
+        Ok(factory)
     }
 
     /// Returns the path to the default language profiles directory.
@@ -127,6 +564,9 @@ impl DetectorFactory {
     /// or when running from the crate's directory. When used as a published dependency,
     /// the profiles may not be available as filesystem files.
     ///
+    /// With the `small-profiles` feature enabled this points to the compact profile set
+    /// (`profiles_small`), which keeps only the top 2000 n-grams of each language.
+    ///
     /// # Returns
     /// A PathBuf pointing to the default profiles directory.
     ///
@@ -149,13 +589,26 @@ impl DetectorFactory {
     /// factory.add_profile(profile, 0, 1).unwrap();
     /// ```
     pub fn get_default_profiles_path() -> std::path::PathBuf {
-        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("profiles")
+        let dir = if cfg!(feature = "small-profiles") { "profiles_small" } else { "profiles" };
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(dir)
     }
 
     /// Clears all loaded language profiles and mappings.
     pub fn clear(&mut self) {
-        self.langlist.clear();
-        self.word_lang_prob_map.clear();
+        self.invalidate_cache();
+        self.langlist = Arc::new(Vec::new());
+        self.profile_data = Arc::new(ProfileData::default());
+        self.disabled_languages.clear();
+    }
+
+    /// Stops the result cache from answering with results computed before this call.
+    ///
+    /// Called by every method changing the languages or settings. Call it after modifying
+    /// public fields like `params` or `language_filter` directly. Clones of the factory
+    /// sharing the cache keep using their own entries.
+    pub fn invalidate_cache(&mut self) {
+        static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+        self.generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Sets the randomization seed for reproducible results.
@@ -163,282 +616,2401 @@ impl DetectorFactory {
     /// # Arguments
     /// * `seed` - The seed value to use for randomization.
     pub fn set_seed(&mut self, seed: u64) {
+        self.invalidate_cache();
         self.seed = Some(seed);
     }
 
-    /// Returns a list of all loaded language identifiers.
+    /// Sets the smoothing denominator for all created detectors (default: 10000.0).
     ///
-    /// # Returns
-    /// A vector of language codes (ISO 639-1) in the order they were loaded.
-    pub fn get_lang_list(&self) -> Vec<String> {
-        self.langlist.clone()
+    /// # Arguments
+    /// * `base_freq` - The base frequency, e.g. from `derived_base_freq()`.
+    pub fn set_base_freq(&mut self, base_freq: f64) {
+        self.invalidate_cache();
+        self.params.base_freq = base_freq;
     }
 
-    /// Creates a new Detector instance with the current profiles.
+    /// Derives a base frequency suited to the loaded profiles.
     ///
-    /// # Arguments
-    /// * `alpha` - Optional alpha smoothing parameter (default: 0.5).
+    /// The default `Detector::BASE_FREQ` is tuned for the built-in profiles of about
+    /// 2000 n-grams per language. Smaller profiles have larger n-gram probabilities,
+    /// which the default smoothing hardly affects, so a few matching n-grams already
+    /// push one language to certainty. The derived value scales the base frequency with
+    /// the average number of n-grams per language instead.
     ///
     /// # Returns
-    /// A configured Detector ready for language detection.
-    pub fn create(&self, alpha: Option<f64>) -> Detector {
-        let mut detector = Detector::new(
-            self.word_lang_prob_map.clone(),
-            self.langlist.clone(),
-            self.seed,
-        );
-        if let Some(a) = alpha {
-            detector.alpha = a;
-        }
-        detector
-    }
-
-    /// Overrides an existing language profile at the specified index.
+    /// `Detector::BASE_FREQ_PER_NGRAM` times the average number of n-grams per language
+    /// (at least 1.0), or `Detector::BASE_FREQ` if no profile is loaded.
     ///
-    /// This is an internal method used during profile loading.
+    /// # Example
     ///
-    /// # Arguments
-    /// * `profile` - The language profile to add.
-    /// * `index` - The index in the language list.
-    /// * `langsize` - Total number of languages.
-    pub fn override_profile(&mut self, profile: LangProfile, index: usize, langsize: usize) -> Result<(), DetectorFactoryError> {
-        let lang = profile.name.clone().unwrap();
-        self.langlist.push(lang.clone());
-        for (word, &count) in profile.freq.iter() {
-            if !self.word_lang_prob_map.contains_key(word) {
-                self.word_lang_prob_map.insert(word.clone(), vec![0.0; langsize]);
-            }
-            let length = word.chars().count();
-            if length >= 1 && length <= 3 {
-                let prob = count as f64 / profile.n_words[length - 1] as f64;
-                if let Some(vec) = self.word_lang_prob_map.get_mut(word) {
-                    vec[index] = prob;
-                }
-            }
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::utils::lang_profile::LangProfile;
+    ///
+    /// let mut factory = DetectorFactory::new().build();
+    /// for (name, words) in [("en", ["the", "and"]), ("de", ["der", "und"])] {
+    ///     let mut profile = LangProfile::new().with_name(name).build();
+    ///     words.iter().for_each(|word| profile.add(word));
+    ///     factory.push_profile(profile).unwrap();
+    /// }
+    /// factory.set_base_freq(factory.derived_base_freq());
+    /// ```
+    pub fn derived_base_freq(&self) -> f64 {
+        let langsize = self.langlist.len();
+        if langsize == 0 {
+            return Detector::BASE_FREQ;
         }
-        Ok(())
+        let ngrams: usize = (0..langsize).filter_map(|i| self.profile_data.column(i)).map(|column| column.len()).sum();
+        (Detector::BASE_FREQ_PER_NGRAM * ngrams as f64 / langsize as f64).max(1.0)
     }
 
-    /// Adds a new language profile to the factory.
+    /// Sets prior probabilities of languages for all created detectors.
+    ///
+    /// See `Detector::set_priors`. Languages without a prior (including languages
+    /// added later) get a prior of zero.
     ///
     /// # Arguments
-    /// * `profile` - The language profile to add.
-    /// * `index` - The index position for this language.
-    /// * `langsize` - Total number of languages in the profile set.
+    /// * `priors` - Language code to (relative) prior probability mapping.
     ///
     /// # Errors
-    /// Returns `DetectorFactoryError::DuplicatedLanguage` if the language already exists.
-    pub fn add_profile(&mut self, profile: LangProfile, index: usize, langsize: usize) -> Result<(), DetectorFactoryError> {
-        let lang = profile.name.clone().unwrap();
-        if self.langlist.contains(&lang) {
-            return Err(DetectorFactoryError::DuplicatedLanguage(lang));
-        }
-        self.override_profile(profile, index, langsize)
+    /// Returns `DetectorError::UnknownLanguage` for a language which is not loaded, and
+    /// `DetectorError::InvalidPrior` for invalid probabilities. The previous priors are kept on error.
+    pub fn set_priors(&mut self, priors: HashMap<&str, f64>) -> Result<(), DetectorError> {
+        let prior = Detector::prior_vector(&self.langlist, &priors)?;
+        self.priors = Some(self.langlist.iter().map(Lang::to_string).zip(prior).filter(|(_, p)| *p > 0.0).collect());
+        self.invalidate_cache();
+        Ok(())
     }
 
-    /// Removes a language profile from the factory.
+    /// Enables or disables a loaded language for all detectors created afterwards.
+    ///
+    /// A disabled language is masked like a language excluded by the language filter, but
+    /// its profile stays loaded, so it can be re-enabled at any time, e.g. to quickly suppress
+    /// a language which is often wrongly detected. Cached results of the previous settings are not used anymore.
     ///
     /// # Arguments
-    /// * `lang` - The language code to remove.
+    /// * `lang` - The language code.
+    /// * `enabled` - False to mask the language, true to unmask it.
     ///
     /// # Errors
-    /// Returns `DetectorFactoryError::DuplicatedLanguage` if the language doesn't exist.
-    pub fn delete_profile(&mut self, lang: &str) -> Result<(), DetectorFactoryError> {
-        let pos = self.langlist.iter().position(|l| l == lang);
-        if let Some(index) = pos {
-            self.langlist.remove(index);
-            // Remove the language's probabilities from word_lang_prob_map
-            for vec in self.word_lang_prob_map.values_mut() {
-                if vec.len() > index {
-                    vec.remove(index);
-                }
-            }
-            Ok(())
-        } else {
-            Err(DetectorFactoryError::DuplicatedLanguage(lang.to_string()))
+    /// Returns `DetectorError::UnknownLanguage` if the language is not loaded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let mut factory = DetectorFactory::default().build();
+    /// factory.set_language_enabled("fr", false).unwrap();
+    /// assert_ne!(factory.detect("Bonjour tout le monde, comment allez-vous ?", None).unwrap(), "fr");
+    /// factory.set_language_enabled("fr", true).unwrap();
+    /// assert_eq!(factory.detect("Bonjour tout le monde, comment allez-vous ?", None).unwrap(), "fr");
+    /// ```
+    pub fn set_language_enabled(&mut self, lang: &str, enabled: bool) -> Result<(), DetectorError> {
+        if !self.langlist.iter().any(|l| l == lang) {
+            return Err(DetectorError::UnknownLanguage(lang.to_string()));
         }
+        self.disabled_languages.retain(|l| l != lang);
+        if !enabled {
+            self.disabled_languages.push(Lang::from(lang));
+        }
+        self.invalidate_cache();
+        Ok(())
     }
 
-    /// Loads language profiles from JSON strings.
+    /// Returns true if a language is loaded and not disabled with `set_language_enabled`.
     ///
     /// # Arguments
-    /// * `json_profiles` - Array of JSON strings representing language profiles.
+    /// * `lang` - The language code.
+    pub fn is_language_enabled(&self, lang: &str) -> bool {
+        self.langlist.iter().any(|l| l == lang) && !self.disabled_languages.iter().any(|l| l == lang)
+    }
+
+    /// Returns a list of all loaded language identifiers.
     ///
-    /// # Errors
-    /// Returns `DetectorFactoryError::NotEnoughProfiles` if fewer than 2 profiles provided.
-    pub fn load_json_profile(&mut self, json_profiles: &[&str]) -> Result<(), DetectorFactoryError> {
-        let langsize = json_profiles.len();
-        if langsize < 2 {
-            return Err(DetectorFactoryError::NotEnoughProfiles);
-        }
-        let mut index = 0;
-        for json_profile in json_profiles {
-            let json_data: LangProfileJson = serde_json::from_str(json_profile)
-                .map_err(|_| DetectorFactoryError::NotEnoughProfiles)?;
-            let profile = LangProfile {
-                name: Some(json_data.name),
-                freq: json_data.freq,
-                n_words: {
-                    let mut arr = [0; 3];
-                    for (i, v) in json_data.n_words.iter().enumerate().take(3) {
-                        arr[i] = *v;
-                    }
-                    arr
-                },
-            };
-            self.add_profile(profile, index, langsize)?;
-            index += 1;
-        }
-        Ok(())
+    /// # Returns
+    /// A vector of language codes (ISO 639-1) in the order they were loaded.
+    pub fn get_lang_list(&self) -> Vec<Lang> {
+        self.langlist.as_ref().clone()
     }
 
-    /// Shortcut method to detect language from text in one call.
+    /// Returns the scripts a loaded language is written in.
+    ///
+    /// Declared by the `scripts` field of the profile, or derived from its unigrams
+    /// (see `ProfileData::language_scripts`).
     ///
     /// # Arguments
-    /// * `text` - The text to analyze.
-    /// * `alpha` - Optional alpha smoothing parameter.
+    /// * `lang` - The language code.
     ///
     /// # Returns
-    /// The detected language code or an error.
+    /// The scripts, or None if the language is not loaded.
     ///
     /// # Example
     ///
     /// ```rust
     /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::utils::script::Script;
     ///
     /// let factory = DetectorFactory::default().build();
-    /// let result = factory.detect("Hello world!", None);
+    /// assert_eq!(factory.language_scripts("ru"), Some(vec![Script::Cyrillic]));
     /// ```
-    pub fn detect(&self, text: &str, alpha: Option<f64>) -> Result<String, DetectorError> {
-        let mut detector = self.create(alpha);
-        detector.append(text);
-        detector.detect()
+    pub fn language_scripts(&self, lang: &str) -> Option<Vec<Script>> {
+        let index = self.langlist.iter().position(|l| l == lang)?;
+        Some(self.profile_data.language_scripts(index).to_vec())
     }
 
-    /// Shortcut method to get language probabilities from text in one call.
+    /// Returns the loaded languages written in a script.
     ///
     /// # Arguments
-    /// * `text` - The text to analyze.
-    /// * `alpha` - Optional alpha smoothing parameter.
+    /// * `script` - The script.
     ///
     /// # Returns
-    /// A vector of languages with their probabilities, sorted by probability descending.
+    /// The language codes, in `langlist` order.
     ///
     /// # Example
     ///
     /// ```rust
     /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::language::Lang;
+    /// use langdetect_rs::utils::script::Script;
     ///
     /// let factory = DetectorFactory::default().build();
-    /// let result = factory.get_probabilities("Hello world!", None);
+    /// assert!(factory.languages_for_script(Script::Cyrillic).contains(&Lang::Uk));
     /// ```
-    pub fn get_probabilities(&self, text: &str, alpha: Option<f64>) -> Result<Vec<Language>, DetectorError> {
-        let mut detector = self.create(alpha);
-        detector.append(text);
-        detector.get_probabilities()
+    pub fn languages_for_script(&self, script: Script) -> Vec<Lang> {
+        self.langlist.iter().enumerate()
+            .filter(|(index, _)| self.profile_data.language_scripts(*index).contains(&script))
+            .map(|(_, lang)| lang.clone())
+            .collect()
     }
 
-    /// Loads all language profiles from a directory of JSON files.
+    /// Builds a per-language summary of n-gram coverage and overlap.
     ///
-    /// # Arguments
-    /// * `profile_directory` - Path to directory containing JSON profile files.
+    /// For each loaded language the report contains how many 1/2/3-grams it contributes,
+    /// how many of them are shared with other languages and which language it overlaps with most.
     ///
     /// # Returns
-    /// Ok(()) on success, or an error string on failure.
+    /// One `LanguageCoverage` entry per language, in `langlist` order.
     ///
     /// # Example
     ///
     /// ```rust
     /// use langdetect_rs::detector_factory::DetectorFactory;
     ///
-    /// let mut factory = DetectorFactory::new().build();
-    /// factory.load_profile("profiles/").unwrap();
+    /// let factory = DetectorFactory::default().build();
+    /// for coverage in factory.coverage_report() {
+    ///     println!("{}", coverage);
+    /// }
     /// ```
-    pub fn load_profile<P: AsRef<Path>>(&mut self, profile_directory: P) -> Result<(), String> {
-        let dir = profile_directory.as_ref();
-        let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read profile directory: {}", e))?;
-        let mut json_profiles = Vec::new();
-        for entry in entries {
-            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-            let path = entry.path();
-            if path.is_file() {
-                let content = fs::read_to_string(&path)
-                    .map_err(|e| format!("Failed to read file {:?}: {}", path, e))?;
-                json_profiles.push(content);
+    pub fn coverage_report(&self) -> Vec<LanguageCoverage> {
+        let langsize = self.langlist.len();
+        let mut ngram_counts = vec![[0usize; 3]; langsize];
+        let mut shared = vec![0usize; langsize];
+        let mut overlap = vec![vec![0usize; langsize]; langsize];
+        let mut present = Vec::with_capacity(langsize);
+        for (word, probs) in self.profile_data.rows() {
+            let length = word.chars().count();
+            if !(1..=3).contains(&length) {
+                continue;
+            }
+            present.clear();
+            present.extend(probs.iter().take(langsize).enumerate().filter(|(_, p)| **p > 0.0).map(|(i, _)| i));
+            for &i in present.iter() {
+                ngram_counts[i][length - 1] += 1;
+                if present.len() > 1 {
+                    shared[i] += 1;
+                }
+                for &j in present.iter() {
+                    if i != j {
+                        overlap[i][j] += 1;
+                    }
+                }
             }
         }
-        let json_refs: Vec<&str> = json_profiles.iter().map(|s| s.as_str()).collect();
-        self.load_json_profile(&json_refs)
-            .map_err(|e| format!("Failed to parse JSON profiles: {:?}", e))?;
-        Ok(())
+        self.langlist.iter().enumerate().map(|(i, lang)| {
+            let total: usize = ngram_counts[i].iter().sum();
+            let most_overlapping = overlap[i].iter().enumerate()
+                .filter(|(j, count)| *j != i && **count > 0)
+                .max_by_key(|(_, count)| **count)
+                .map(|(j, count)| (self.langlist[j].clone(), *count));
+            LanguageCoverage {
+                lang: lang.clone(),
+                ngram_counts: ngram_counts[i],
+                shared: shared[i],
+                exclusive: total - shared[i],
+                most_overlapping,
+                scripts: self.profile_data.language_scripts(i).to_vec(),
+            }
+        }).collect()
     }
-}
 
-/// Builder for `DetectorFactory` with fluent setters.
-///
-/// Provides a convenient way to configure a DetectorFactory before building it.
-///
-/// # Examples
-///
-/// ```rust
-/// use langdetect_rs::detector_factory::DetectorFactory;
-/// use std::collections::HashMap;
-///
-/// let factory = DetectorFactory::new()
-///     .with_langlist(vec!["en".to_string(), "fr".to_string()])
-///     .with_seed(Some(42))
-///     .build();
+    /// Summarizes the loaded model: languages with profile checksums, build features,
+    /// parameters and a hash of the whole snapshot.
+    ///
+    /// Hashes all profile probabilities, so call it once (e.g. at startup) rather than per request.
+    ///
+    /// # Returns
+    /// A `ModelInfo`, serializable with serde or `ModelInfo::to_json()`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let info = factory.model_info();
+    /// assert!(info.languages.iter().any(|profile| profile.lang == "en"));
+    /// println!("{}", info.to_json());
+    /// ```
+    pub fn model_info(&self) -> ModelInfo {
+        let features = [
+            ("small-profiles", cfg!(feature = "small-profiles")),
+            ("embedded-profiles", cfg!(feature = "embedded-profiles")),
+            ("rayon", cfg!(feature = "rayon")),
+            ("cli", cfg!(feature = "cli")),
+            ("wasm", cfg!(feature = "wasm")),
+            ("python", cfg!(feature = "python")),
+        ];
+        let languages: Vec<ProfileInfo> = self.langlist.iter().enumerate().map(|(index, lang)| {
+            let mut entries: Vec<(&str, f64)> = self.profile_data.column(index)
+                .map(|column| column.into_iter().filter_map(|(id, prob)| Some((self.profile_data.ngram(id)?, prob))).collect())
+                .unwrap_or_default();
+            entries.sort_unstable_by(|a, b| a.0.cmp(b.0));
+            let mut hasher = Fnv1a::new();
+            for (word, prob) in entries.iter() {
+                hasher.write(word.as_bytes());
+                hasher.write(&[0]);
+                hasher.write(&prob.to_bits().to_le_bytes());
+            }
+            ProfileInfo { lang: lang.clone(), ngrams: entries.len(), checksum: hasher.hex() }
+        }).collect();
+
+        let mut hasher = Fnv1a::new();
+        for profile in languages.iter() {
+            hasher.write(profile.lang.as_bytes());
+            hasher.write(&[0]);
+            hasher.write(profile.checksum.as_bytes());
+        }
+        hasher.write(serde_json::to_string(&self.params).unwrap_or_default().as_bytes());
+        hasher.write(format!("{:?}", self.seed).as_bytes());
+
+        ModelInfo {
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            features: features.iter().filter(|(_, enabled)| *enabled).map(|(name, _)| name.to_string()).collect(),
+            languages,
+            params: self.params,
+            seed: self.seed,
+            snapshot_hash: hasher.hex(),
+        }
+    }
+
+    /// Creates a new Detector instance with the current profiles.
+    ///
+    /// The detector shares the profile data with the factory instead of copying it,
+    /// so creating one detector per request is cheap.
+    ///
+    /// # Arguments
+    /// * `alpha` - Optional alpha smoothing parameter (default: 0.5).
+    ///
+    /// # Returns
+    /// A configured Detector ready for language detection.
+    pub fn create(&self, alpha: Option<f64>) -> Detector {
+        let mut detector = Detector::from_shared(
+            Arc::clone(&self.profile_data),
+            Arc::clone(&self.langlist),
+            self.seed,
+        );
+        detector.alpha = alpha.unwrap_or(self.params.alpha);
+        detector.n_trial = self.params.n_trial;
+        detector.max_text_length = self.params.max_text_length;
+        detector.prob_threshold = self.params.prob_threshold;
+        detector.verify_threshold = self.params.verify_threshold;
+        detector.iteration_limit = self.params.iteration_limit;
+        detector.conv_threshold = self.params.conv_threshold;
+        detector.base_freq = self.params.base_freq;
+        detector.arabic_markers = self.params.arabic_markers;
+        detector.devanagari_tiebreak = self.params.devanagari_tiebreak;
+        detector.script_prefilter = self.params.script_prefilter;
+        detector.verbose = self.params.verbose;
+        detector.normalization = self.params.normalization;
+        detector.unknown_label = self.unknown_label.clone();
+        detector.scrubbing = self.scrubbing.clone();
+        detector.prior_floor = self.prior_floor;
+        detector.set_disabled_languages(&self.disabled_languages);
+        if self.language_filter.is_some() || !self.disabled_languages.is_empty() {
+            detector.set_language_filter(self.language_filter.as_ref());
+        }
+        if let Some(priors) = &self.priors {
+            let prior: Vec<f64> = self.langlist.iter().map(|lang| priors.get(lang.as_str()).copied().unwrap_or(0.0)).collect();
+            // All languages with a prior may have been deleted since
+            if prior.iter().any(|p| *p > 0.0) {
+                detector.prior_map = Some(prior);
+            }
+        }
+        detector
+    }
+
+    /// Estimates the heap memory used by the loaded profiles, in bytes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// println!("profiles use about {} MiB", factory.memory_usage() / (1 << 20));
+    /// ```
+    pub fn memory_usage(&self) -> usize {
+        self.profile_data.memory_usage()
+    }
+
+    /// Drops the lowest-probability n-grams across languages until the profiles fit a memory budget.
+    ///
+    /// Useful for deploying on small containers: rare n-grams carry little evidence, so
+    /// accuracy degrades gradually. Detectors created before keep the full profiles.
+    ///
+    /// # Arguments
+    /// * `bytes` - Maximum estimated heap memory of the profiles, see `memory_usage()`.
+    ///
+    /// # Returns
+    /// The number of removed (n-gram, language) entries.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let mut factory = DetectorFactory::default().build();
+    /// let budget = factory.memory_usage() / 2;
+    /// factory.shrink_to_budget(budget);
+    /// assert!(factory.memory_usage() <= budget);
+    /// assert_eq!(factory.detect("Bonjour tout le monde, comment allez-vous ?", None).unwrap(), "fr");
+    /// ```
+    pub fn shrink_to_budget(&mut self, bytes: usize) -> usize {
+        if self.profile_data.memory_usage() <= bytes {
+            return 0;
+        }
+        self.invalidate_cache();
+        Arc::make_mut(&mut self.profile_data).shrink_to_budget(bytes)
+    }
+
+    /// Creates a new Detector with per-call options applied on top of the factory settings.
+    ///
+    /// # Arguments
+    /// * `options` - Alpha, script hint, boosts and history, see `DetectOptions`.
+    pub fn create_with_options(&self, options: &DetectOptions) -> Detector {
+        let mut detector = self.create(options.alpha);
+        if let Some(script) = options.script_hint {
+            detector.restrict_to_script(script);
+        }
+        if !options.boosts.is_empty() {
+            let boost = self.langlist.iter().map(|lang| {
+                options.boosts.iter().find(|(l, _)| l == lang).map_or(1.0, |(_, factor)| *factor)
+            }).collect();
+            detector.boost_map = Some(boost);
+        }
+        if !options.history.is_empty() {
+            let mut prior: Vec<f64> = self.langlist.iter().map(|lang| {
+                options.history.iter().filter(|(l, _)| l == lang).map(|(_, share)| *share).sum()
+            }).collect();
+            let total: f64 = prior.iter().sum();
+            // Invalid shares are left for the prior validation to report
+            if total > 0.0 && total.is_finite() && prior.iter().all(|p| *p >= 0.0) {
+                prior.iter_mut().for_each(|p| *p /= total);
+            }
+            detector.prior_map = Some(prior);
+            detector.prior_floor = detector.prior_floor.max(DetectOptions::HISTORY_FLOOR);
+        }
+        detector
+    }
+
+    /// Creates a builder for a detector configured like `create(None)` would.
+    ///
+    /// # Returns
+    /// A `DetectorBuilder` to tune the parameters of this detector only.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().with_trials(3).build();
+    /// let detector = factory.detector_builder().with_alpha(0.3).build();
+    /// assert_eq!(detector.n_trial, 3);
+    /// ```
+    pub fn detector_builder(&self) -> DetectorBuilder {
+        DetectorBuilder::from(self.create(None))
+    }
+
+    /// Stores a language profile at the specified index, which must be the end of the language list.
+    ///
+    /// This is an internal method used during profile loading; it does not replace a loaded
+    /// language, only appends one.
+    ///
+    /// # Arguments
+    /// * `profile` - The language profile to add.
+    /// * `index` - The index in the language list.
+    /// * `langsize` - Total number of languages.
+    ///
+    /// The probability storage is grown to `langsize` languages if needed.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::UnnamedProfile` if the profile has no name,
+    /// `DetectorFactoryError::InvalidFormat` if it has n-grams of a length whose `n_words` total is 0,
+    /// `DetectorFactoryError::IndexOutOfRange` if `index` is not below `langsize`,
+    /// `DetectorFactoryError::LangsizeMismatch` if `langsize` is smaller than the number of
+    /// languages/probabilities the factory already holds and
+    /// `DetectorFactoryError::IndexMismatch` if `index` is not the number of languages already
+    /// loaded (profiles are stored in the order of the language list).
+    pub fn override_profile(&mut self, profile: LangProfile, index: usize, langsize: usize) -> Result<(), DetectorFactoryError> {
+        self.invalidate_cache();
+        let lang = profile.name.clone().ok_or(DetectorFactoryError::UnnamedProfile)?;
+        if index >= langsize {
+            return Err(DetectorFactoryError::IndexOutOfRange { index, langsize });
+        }
+        if self.langlist.len() >= langsize {
+            return Err(DetectorFactoryError::LangsizeMismatch { expected: langsize, found: self.langlist.len() });
+        }
+        if index != self.langlist.len() {
+            return Err(DetectorFactoryError::IndexMismatch { index, expected: self.langlist.len() });
+        }
+        let found = self.profile_data.lang_count();
+        if found > langsize {
+            return Err(DetectorFactoryError::LangsizeMismatch { expected: langsize, found });
+        }
+        // Probabilities are relative to the total of each n-gram length
+        for word in profile.freq.keys() {
+            let length = word.chars().count();
+            if (1..=3).contains(&length) && profile.n_words[length - 1] == 0 {
+                return Err(DetectorFactoryError::InvalidFormat(format!("Profile {} has {}-grams but a total of 0 in n_words", lang, length)));
+            }
+        }
+        let profile_data = Arc::make_mut(&mut self.profile_data);
+        profile_data.resize_languages(langsize);
+        Arc::make_mut(&mut self.langlist).push(Lang::from(lang));
+        for (word, &count) in profile.freq.iter() {
+            let length = word.chars().count();
+            if (1..=3).contains(&length) {
+                let prob = count as f64 / profile.n_words[length - 1] as f64;
+                profile_data.set(word, index, prob);
+            }
+        }
+        profile_data.set_language_scripts(index, profile.scripts);
+        Ok(())
+    }
+
+    /// Adds a new language profile to the factory.
+    ///
+    /// # Arguments
+    /// * `profile` - The language profile to add.
+    /// * `index` - The index position for this language.
+    /// * `langsize` - Total number of languages in the profile set.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::DuplicatedLanguage` if the language already exists, or
+    /// the errors of `override_profile` for an unnamed profile or an inconsistent `index`/`langsize`.
+    pub fn add_profile(&mut self, profile: LangProfile, index: usize, langsize: usize) -> Result<(), DetectorFactoryError> {
+        let lang = profile.name.clone().ok_or(DetectorFactoryError::UnnamedProfile)?;
+        if self.langlist.iter().any(|l| *l == lang) {
+            return Err(DetectorFactoryError::DuplicatedLanguage(lang));
+        }
+        self.override_profile(profile, index, langsize)
+    }
+
+    /// Appends a language profile, growing the probability storage by one language.
+    ///
+    /// Unlike `add_profile` the index and the final number of languages are derived
+    /// from the profiles already loaded, so profiles can be added to any factory
+    /// (e.g. the default one) at any time.
+    ///
+    /// # Arguments
+    /// * `profile` - The language profile to add.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::DuplicatedLanguage` if the language already exists
+    /// and `DetectorFactoryError::UnnamedProfile` if the profile has no name.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::language::Lang;
+    /// use langdetect_rs::utils::lang_profile::LangProfile;
+    ///
+    /// let mut factory = DetectorFactory::default().build();
+    /// let mut profile = LangProfile::new().with_name("xx").build();
+    /// profile.update("some text of the new language");
+    /// factory.push_profile(profile).unwrap();
+    /// assert_eq!(factory.get_lang_list().last().map(Lang::as_str), Some("xx"));
+    /// ```
+    pub fn push_profile(&mut self, profile: LangProfile) -> Result<(), DetectorFactoryError> {
+        let index = self.langlist.len();
+        // Columns reserved by an earlier add_profile call are filled first
+        let langsize = self.profile_data.lang_count().max(index + 1);
+        self.add_profile(profile, index, langsize)
+    }
+
+    /// Appends several language profiles, see `push_profile`.
+    ///
+    /// All profiles are checked for duplicates before any of them is added, so
+    /// the factory is left unchanged on error.
+    ///
+    /// # Arguments
+    /// * `profiles` - The language profiles to add, in the order they are appended.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::DuplicatedLanguage` if a language already exists
+    /// or appears twice in `profiles`, and `DetectorFactoryError::UnnamedProfile` if a
+    /// profile has no name.
+    pub fn add_profiles(&mut self, profiles: Vec<LangProfile>) -> Result<(), DetectorFactoryError> {
+        self.invalidate_cache();
+        let mut names: Vec<&str> = Vec::with_capacity(profiles.len());
+        for profile in profiles.iter() {
+            let name = profile.name.as_deref().ok_or(DetectorFactoryError::UnnamedProfile)?;
+            if names.contains(&name) || self.langlist.iter().any(|lang| lang == name) {
+                return Err(DetectorFactoryError::DuplicatedLanguage(name.to_string()));
+            }
+            names.push(name);
+        }
+        for profile in profiles {
+            self.push_profile(profile)?;
+        }
+        Ok(())
+    }
+
+    /// Removes a language profile from the factory.
+    ///
+    /// # Arguments
+    /// * `lang` - The language code to remove.
+    ///
+    /// # Returns
+    /// The removed probabilities, which `restore_profile` can add again later.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::LanguageNotFound` if the language doesn't exist.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::language::Lang;
+    ///
+    /// let mut factory = DetectorFactory::default().build();
+    /// let column = factory.delete_profile("af").unwrap();
+    /// assert!(!factory.get_lang_list().contains(&Lang::Af));
+    /// factory.restore_profile(column).unwrap();
+    /// assert_eq!(factory.get_lang_list().last(), Some(&Lang::Af));
+    /// ```
+    pub fn delete_profile(&mut self, lang: &str) -> Result<ProfileColumn, DetectorFactoryError> {
+        self.invalidate_cache();
+        let index = self.langlist.iter().position(|l| l == lang)
+            .ok_or_else(|| DetectorFactoryError::LanguageNotFound(lang.to_string()))?;
+        let lang = Arc::make_mut(&mut self.langlist).remove(index);
+        // Dropping the language's column is enough, other languages are not touched
+        let scripts = self.profile_data.declared_language_scripts(index).map(|scripts| scripts.to_vec());
+        let probabilities = Arc::make_mut(&mut self.profile_data).remove_language(index).unwrap_or_default();
+        self.disabled_languages.retain(|l| *l != lang);
+        Ok(ProfileColumn { lang, probabilities, scripts })
+    }
+
+    /// Appends a language removed with `delete_profile`, see `push_profile`.
+    ///
+    /// The probabilities are added as they are, so detection results match those before
+    /// the removal (up to the language order).
+    ///
+    /// # Arguments
+    /// * `column` - The probabilities of the language.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::DuplicatedLanguage` if the language already exists.
+    pub fn restore_profile(&mut self, column: ProfileColumn) -> Result<(), DetectorFactoryError> {
+        self.invalidate_cache();
+        if self.langlist.contains(&column.lang) {
+            return Err(DetectorFactoryError::DuplicatedLanguage(column.lang.to_string()));
+        }
+        let index = self.langlist.len();
+        // Columns reserved by an earlier add_profile call are filled first
+        let langsize = self.profile_data.lang_count().max(index + 1);
+        let profile_data = Arc::make_mut(&mut self.profile_data);
+        profile_data.resize_languages(langsize);
+        for (word, &prob) in column.probabilities.iter() {
+            profile_data.set(word, index, prob);
+        }
+        profile_data.set_language_scripts(index, column.scripts);
+        Arc::make_mut(&mut self.langlist).push(column.lang);
+        Ok(())
+    }
+
+    /// Adds the languages of another factory, e.g. custom languages kept apart from a base factory.
+    ///
+    /// The probabilities of `other` are copied as they are and its languages are appended in
+    /// their order. Replaced languages keep their position. All other settings (parameters,
+    /// priors, disabled languages, ...) of this factory are kept.
+    ///
+    /// # Arguments
+    /// * `other` - The factory to take the languages from.
+    /// * `replace` - Whether languages loaded in both factories take the probabilities of `other`.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::DuplicatedLanguage` if `replace` is false and a language
+    /// is loaded in both factories. The factory is left unchanged in that case.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::language::Lang;
+    /// use langdetect_rs::utils::lang_profile::LangProfile;
+    ///
+    /// let mut factory = DetectorFactory::default().build();
+    /// let mut tenant = DetectorFactory::new().build();
+    /// let mut profile = LangProfile::new().with_name("xx").build();
+    /// profile.update("some text of the new language");
+    /// tenant.push_profile(profile).unwrap();
+    ///
+    /// factory.merge(&tenant, false).unwrap();
+    /// assert_eq!(factory.get_lang_list().last().map(Lang::as_str), Some("xx"));
+    /// assert!(factory.merge(&tenant, false).is_err());
+    /// factory.merge(&tenant, true).unwrap();
+    /// ```
+    pub fn merge(&mut self, other: &DetectorFactory, replace: bool) -> Result<(), DetectorFactoryError> {
+        self.invalidate_cache();
+        if !replace && let Some(lang) = other.langlist.iter().find(|lang| self.langlist.contains(lang)) {
+            return Err(DetectorFactoryError::DuplicatedLanguage(lang.to_string()));
+        }
+        for (other_index, lang) in other.langlist.iter().enumerate() {
+            let probabilities = other.profile_data.column(other_index).map(|column| {
+                column.into_iter().filter_map(|(id, prob)| other.profile_data.ngram(id).map(|word| (word.to_string(), prob))).collect()
+            }).unwrap_or_default();
+            let scripts = other.profile_data.declared_language_scripts(other_index).map(|scripts| scripts.to_vec());
+            let column = ProfileColumn { lang: lang.clone(), probabilities, scripts };
+            match self.langlist.iter().position(|l| *l == column.lang) {
+                Some(index) => {
+                    let profile_data = Arc::make_mut(&mut self.profile_data);
+                    profile_data.clear_language(index);
+                    for (word, &prob) in column.probabilities.iter() {
+                        profile_data.set(word, index, prob);
+                    }
+                    profile_data.set_language_scripts(index, column.scripts);
+                }
+                None => self.restore_profile(column)?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Keeps only the given languages and removes all others from the factory.
+    ///
+    /// Unlike calling `delete_profile` for every unwanted language, the probability table is
+    /// filtered in a single pass, and n-grams only the removed languages knew are released.
+    /// Codes which are not loaded are ignored. Columns reserved by `add_profile` for languages
+    /// not loaded yet are dropped along with the removed languages.
+    ///
+    /// # Arguments
+    /// * `langs` - Language codes or `Lang` values to keep.
+    ///
+    /// # Returns
+    /// The number of removed languages.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::language::Lang;
+    ///
+    /// let mut factory = DetectorFactory::default().build();
+    /// let before = factory.memory_usage();
+    /// factory.retain_languages(&[Lang::En, Lang::De, Lang::Fr]);
+    /// assert_eq!(factory.get_lang_list(), vec![Lang::De, Lang::En, Lang::Fr]);
+    /// assert!(factory.memory_usage() < before);
+    /// ```
+    pub fn retain_languages<L: Into<Lang> + Clone>(&mut self, langs: &[L]) -> usize {
+        let langs: Vec<Lang> = langs.iter().cloned().map(Into::into).collect();
+        let kept: Vec<bool> = self.langlist.iter().map(|lang| langs.contains(lang)).collect();
+        let removed = kept.iter().filter(|keep| !**keep).count();
+        if removed == 0 {
+            return 0;
+        }
+        self.invalidate_cache();
+        Arc::make_mut(&mut self.langlist).retain(|lang| langs.contains(lang));
+        Arc::make_mut(&mut self.profile_data).retain_languages(|index| kept.get(index).copied().unwrap_or(false));
+        self.disabled_languages.retain(|lang| langs.contains(lang));
+        removed
+    }
+
+    /// Loads language profiles from JSON strings.
+    ///
+    /// The profiles are appended after the languages already loaded. Unless disabled with
+    /// `with_sorted_profiles(false)`, they are added in order of their language code, so the
+    /// language order (and the layout of probability vectors) does not depend on the order of
+    /// the input, e.g. the filesystem order in `load_profile`.
+    ///
+    /// # Arguments
+    /// * `json_profiles` - Array of JSON strings representing language profiles.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::NotEnoughProfiles` if fewer than 2 profiles provided,
+    /// `DetectorFactoryError::InvalidFormat` if a profile is not valid JSON (naming its position
+    /// in `json_profiles`) or `n_words` does not hold one total per n-gram length,
+    /// the errors of `add_profile` for duplicated languages or inconsistent `n_words`,
+    /// or `DetectorFactoryError::LimitExceeded` if a profile exceeds `profile_limits` or the
+    /// probability matrix of all profiles could exceed `max_model_size`.
+    pub fn load_json_profile(&mut self, json_profiles: &[&str]) -> Result<(), DetectorFactoryError> {
+        let named: Vec<(String, &str)> = json_profiles.iter().enumerate().map(|(i, json)| (format!("#{}", i), *json)).collect();
+        self.load_named_json_profiles(&named)
+    }
+
+    /// Loads language profiles from JSON strings, see `load_json_profile`.
+    ///
+    /// Each profile comes with the name of its source (e.g. the file name), which is
+    /// reported if the profile cannot be parsed.
+    fn load_named_json_profiles<N: AsRef<str>>(&mut self, json_profiles: &[(N, &str)]) -> Result<(), DetectorFactoryError> {
+        self.invalidate_cache();
+        let langsize = json_profiles.len();
+        if langsize < 2 {
+            return Err(DetectorFactoryError::NotEnoughProfiles);
+        }
+        let mut profiles = Vec::with_capacity(langsize);
+        // Columns reserved by an earlier add_profile call are filled first, as in push_profile
+        let offset = self.langlist.len();
+        let langsize = self.profile_data.lang_count().max(offset + langsize);
+        let mut ngram_count = self.profile_data.ngram_count();
+        for (source, json_profile) in json_profiles.iter() {
+            let json_data = LangProfileJson::from_str_with_limits(json_profile, &self.profile_limits)
+                .map_err(|e| match e {
+                    LangProfileJsonError::LimitExceeded(e) => DetectorFactoryError::LimitExceeded(e),
+                    e => DetectorFactoryError::InvalidFormat(format!("Failed to parse profile {}: {}", source.as_ref(), e)),
+                })?;
+            ngram_count = ngram_count.saturating_add(json_data.freq.len());
+            // The probability matrix gets a row per n-gram and a column per language
+            self.profile_limits.check_model_size(ngram_count.saturating_mul(langsize).saturating_mul(std::mem::size_of::<f64>()))
+                .map_err(model_error)?;
+            let name = json_data.name.clone();
+            profiles.push(LangProfile::from_json(json_data)
+                .map_err(|e| DetectorFactoryError::InvalidFormat(format!("Profile {}: {}", name, e)))?);
+        }
+        if self.sort_profiles {
+            profiles.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        for (index, profile) in profiles.into_iter().enumerate() {
+            self.add_profile(profile, offset + index, langsize)?;
+        }
+        Ok(())
+    }
+
+    /// Shortcut method to detect language from text in one call.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The detected language code or an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let result = factory.detect("Hello world!", None);
+    /// ```
+    pub fn detect(&self, text: &str, alpha: Option<f64>) -> Result<String, DetectorError> {
+        if self.label_set.is_some() || self.cache.is_some() {
+            let language = self.detect_lang(text, alpha)?;
+            return Ok(language.lang.map(String::from).unwrap_or_else(|| self.unknown_label.clone()));
+        }
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        detector.detect()
+    }
+
+    /// Shortcut method to detect the most probable language together with its probability.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The detected `Language` (code and probability) or an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let result = factory.detect_lang("Hello world!", None);
+    /// ```
+    pub fn detect_lang(&self, text: &str, alpha: Option<f64>) -> Result<Language, DetectorError> {
+        if self.label_set.is_some() || self.cache.is_some() {
+            let probabilities = self.get_probabilities(text, alpha)?;
+            return Ok(probabilities.into_iter().next().unwrap_or_else(|| Language::new(None, 0.0)));
+        }
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        detector.detect_lang()
+    }
+
+    /// Detects the most probable language of every paragraph of a text.
+    ///
+    /// Paragraphs are separated by blank (whitespace-only) lines. This is a middle
+    /// ground between whole-document and per-sentence detection for articles and emails.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The byte range of each paragraph in `text` (without surrounding whitespace) together
+    /// with its detected `Language`. Paragraphs without detectable features get a
+    /// `Language` with `lang: None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let text = "Hello, how are you doing today?\n\nBonjour, comment allez-vous aujourd'hui ?";
+    /// for (range, language) in factory.detect_paragraphs(text, None) {
+    ///     println!("{:?} {}: {}", range, &text[range.clone()], language);
+    /// }
+    /// ```
+    pub fn detect_paragraphs(&self, text: &str, alpha: Option<f64>) -> Vec<(Range<usize>, Language)> {
+        self.detect_ranges(text, split_paragraphs(text), alpha)
+    }
+
+    /// Detects the most probable language of every sentence of a text.
+    ///
+    /// Sentences are split by the factory's segmenter (`split_sentences` unless set with
+    /// `DetectorFactoryBuilder::with_segmenter`); use `detect_sentences_with` for a one-off
+    /// splitter. Useful for subtitles and chat logs mixing languages line by line.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The byte range of each sentence in `text` together with its detected `Language`
+    /// (code and probability). Sentences without detectable features get a `Language`
+    /// with `lang: None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let text = "How are you doing today? Ich bin heute sehr müde und möchte schlafen.";
+    /// for (range, language) in factory.detect_sentences(text, None) {
+    ///     println!("{:?} {}: {}", range, &text[range.clone()], language);
+    /// }
+    /// ```
+    pub fn detect_sentences(&self, text: &str, alpha: Option<f64>) -> Vec<(Range<usize>, Language)> {
+        self.detect_ranges(text, self.segmenter.sentences(text), alpha)
+    }
+
+    /// Detects the most probable language of every word of a text.
+    ///
+    /// Words are split by the factory's segmenter (`segmenter::split_words` unless set with
+    /// `DetectorFactoryBuilder::with_segmenter`). Single words carry few n-grams, so expect
+    /// lower probabilities than for sentences; useful to find code-switched words.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The byte range of each word in `text` together with its detected `Language`
+    /// (code and probability). Words without detectable features get a `Language`
+    /// with `lang: None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let text = "Das ist ein wonderful Tag";
+    /// for (range, language) in factory.detect_words(text, None) {
+    ///     println!("{:?} {}: {}", range, &text[range.clone()], language);
+    /// }
+    /// ```
+    pub fn detect_words(&self, text: &str, alpha: Option<f64>) -> Vec<(Range<usize>, Language)> {
+        self.detect_ranges(text, self.segmenter.words(text), alpha)
+    }
+
+    /// Detects the most probable language of every segment returned by a custom splitter.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `splitter` - Returns the byte ranges of the segments of a text.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// Every segment's byte range together with its detected `Language`, see `detect_sentences`.
+    /// Ranges out of bounds of the text or not on character boundaries are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let log = "alice: see you tomorrow|bob: à demain";
+    /// let by_message = |text: &str| {
+    ///     let mut start = 0;
+    ///     text.split('|').map(|part| {
+    ///         let range = start..start + part.len();
+    ///         start += part.len() + 1;
+    ///         range
+    ///     }).collect()
+    /// };
+    /// let messages = factory.detect_sentences_with(log, by_message, None);
+    /// assert_eq!(messages.len(), 2);
+    /// ```
+    pub fn detect_sentences_with<F>(&self, text: &str, splitter: F, alpha: Option<f64>) -> Vec<(Range<usize>, Language)>
+    where
+        F: Fn(&str) -> Vec<Range<usize>>,
+    {
+        self.detect_ranges(text, splitter(text), alpha)
+    }
+
+    /// Detects the language of every given byte range of a text, skipping invalid ranges.
+    fn detect_ranges(&self, text: &str, ranges: Vec<Range<usize>>, alpha: Option<f64>) -> Vec<(Range<usize>, Language)> {
+        ranges
+            .into_iter()
+            .filter_map(|range| {
+                let segment = text.get(range.clone())?;
+                let language = self.detect_lang(segment, alpha)
+                    .unwrap_or_else(|_| Language::new(None, 0.0));
+                Some((range, language))
+            })
+            .collect()
+    }
+
+    /// Detects the language of the newly written content of an email message.
+    ///
+    /// Headers, quoted reply blocks (`>` lines) and signatures are stripped before
+    /// detection (see `utils::email::strip_email`), so replies are not detected as
+    /// the language of the quoted message.
+    ///
+    /// # Arguments
+    /// * `raw` - The raw email message.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The detected `Language` (code and probability) or an error.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let raw = "Subject: Re: meeting\nFrom: bob@example.com\n\nMerci, à demain !\n\nOn Monday Alice wrote:\n> See you tomorrow at the office";
+    /// let result = factory.detect_email(raw, None);
+    /// ```
+    pub fn detect_email(&self, raw: &str, alpha: Option<f64>) -> Result<Language, DetectorError> {
+        self.detect_lang(&strip_email(raw), alpha)
+    }
+
+    /// Shortcut method to detect language with per-call options.
+    ///
+    /// The result cache is not used, since its entries do not depend on the options.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `options` - Alpha, script hint, boosts and history, see `DetectOptions`.
+    ///
+    /// # Returns
+    /// The detected language code, or `unknown_label` if no language is detected.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector::DetectOptions;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::utils::script::Script;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let options = DetectOptions::new().with_script_hint(Script::Arabic);
+    /// let result = factory.detect_with_options("سلام دنیا", &options);
+    /// ```
+    pub fn detect_with_options(&self, text: &str, options: &DetectOptions) -> Result<String, DetectorError> {
+        let language = self.detect_lang_with_options(text, options)?;
+        Ok(language.lang.map(String::from).unwrap_or_else(|| self.unknown_label.clone()))
+    }
+
+    /// Shortcut method to detect language and probability with per-call options.
+    ///
+    /// See `detect_with_options`.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `options` - Alpha, script hint, boosts and history, see `DetectOptions`.
+    ///
+    /// # Returns
+    /// The detected `Language` (code and probability) or an error.
+    pub fn detect_lang_with_options(&self, text: &str, options: &DetectOptions) -> Result<Language, DetectorError> {
+        let mut detector = self.create_with_options(options);
+        detector.append(text);
+        match &self.label_set {
+            Some(label_set) => {
+                detector.detect_lang()?;
+                let labels = label_set.collapse(&detector.langlist, detector.langprob.as_deref().unwrap_or_default(), self.params.prob_threshold);
+                Ok(labels.into_iter().next().unwrap_or_else(|| Language::new(None, 0.0)))
+            }
+            None => detector.detect_lang(),
+        }
+    }
+
+    /// Shortcut method to get language probabilities from text in one call.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// A vector of languages with their probabilities, sorted by probability descending.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let result = factory.get_probabilities("Hello world!", None);
+    /// ```
+    pub fn get_probabilities(&self, text: &str, alpha: Option<f64>) -> Result<Vec<Language>, DetectorError> {
+        let Some(cache) = &self.cache else {
+            return self.compute_probabilities(text, alpha);
+        };
+        let key = DetectionCache::key(text, alpha, self.generation);
+        if let Some(probabilities) = cache.get(key) {
+            return Ok(probabilities);
+        }
+        let probabilities = self.compute_probabilities(text, alpha)?;
+        cache.insert(key, probabilities.clone());
+        Ok(probabilities)
+    }
+
+    /// Shortcut method to detect the most probable language with reliability signals.
+    ///
+    /// See `Detector::detect_with_confidence`. The result cache and the label set are not used.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The detected language with its margin, entropy and n-gram coverage.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().with_seed(Some(0)).build();
+    /// let result = factory.detect_with_confidence("ok", None).unwrap();
+    /// if !result.is_reliable() {
+    ///     println!("not sure: {} (margin {:.2})", result.language, result.margin);
+    /// }
+    /// ```
+    pub fn detect_with_confidence(&self, text: &str, alpha: Option<f64>) -> Result<DetectionResult, DetectorError> {
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        detector.detect_with_confidence()
+    }
+
+    /// Shortcut method to detect a language, describing the text if it has no n-grams.
+    ///
+    /// See `Detector::detect_with_fallback`. The result cache and the label set are not used.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The detected language, or the reason, the script and a script-based guess.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector::Detection;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// match factory.detect_with_fallback("🎉🎉", None).unwrap() {
+    ///     Detection::Language(language) => println!("{}", language),
+    ///     Detection::Fallback(fallback) => println!("undetectable: {:?}", fallback.reason),
+    /// }
+    /// ```
+    pub fn detect_with_fallback(&self, text: &str, alpha: Option<f64>) -> Result<Detection, DetectorError> {
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        detector.detect_with_fallback()
+    }
+
+    /// Shortcut method to get the probabilities of all loaded languages in one call.
+    ///
+    /// See `Detector::get_probabilities_full`. The result cache and the label set are not
+    /// used, so the distribution is always complete and per language.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// One `Language` per loaded language, sorted by probability descending.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let result = factory.get_probabilities_full("Hello world!", None).unwrap();
+    /// assert_eq!(result.len(), factory.get_lang_list().len());
+    /// ```
+    pub fn get_probabilities_full(&self, text: &str, alpha: Option<f64>) -> Result<Vec<Language>, DetectorError> {
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        detector.get_probabilities_full()
+    }
+
+    /// Shortcut method to get the probabilities together with the statistics of the detection.
+    ///
+    /// Stateless counterpart of `Detector::get_probabilities` followed by `Detector::last_stats`,
+    /// e.g. to route texts with low n-gram coverage to a fallback detector. The result cache
+    /// and the label set are not used.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The languages above the probability threshold, sorted by probability descending,
+    /// and the statistics of the detection (n-gram coverage, transliteration).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let (probabilities, stats) = factory.get_probabilities_with_stats("Hello world!", None).unwrap();
+    /// if stats.coverage() < 0.3 {
+    ///     println!("{} of {} n-grams known, trying another detector", stats.matched_ngrams, stats.total_ngrams);
+    /// }
+    /// ```
+    pub fn get_probabilities_with_stats(&self, text: &str, alpha: Option<f64>) -> Result<(Vec<Language>, DetectionStats), DetectorError> {
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        let probabilities = detector.get_probabilities()?;
+        let stats = detector.last_stats().cloned().unwrap_or_default();
+        Ok((probabilities, stats))
+    }
+
+    /// Shortcut method to explain a detection by the n-grams which favored each candidate.
+    ///
+    /// See `Detector::explain`; the cache and the label set are not used.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `top_k` - Number of most probable languages to explain.
+    /// * `top_n` - Maximum number of n-grams reported per language.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// One explanation per language, most probable first.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().with_seed(Some(0)).build();
+    /// let explanations = factory.explain("Добрий день, як справи?", 2, 5, None).unwrap();
+    /// for contribution in &explanations[0].ngrams {
+    ///     println!("{:?} x{}: {:.2}", contribution.ngram, contribution.count, contribution.score);
+    /// }
+    /// ```
+    pub fn explain(&self, text: &str, top_k: usize, top_n: usize, alpha: Option<f64>) -> Result<Vec<LanguageExplanation>, DetectorError> {
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        detector.explain(top_k, top_n)
+    }
+
+    /// Shortcut method to decide whether a text is written in a language.
+    ///
+    /// See `Detector::verify`; the cache and the label set are not used.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `lang` - The language, as a `Lang` value or code.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The probability of the language, whether it matches and its margin over the most
+    /// probable other language.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().with_seed(Some(0)).build();
+    /// let documents = ["Guten Morgen, wie geht es dir?", "Good morning, how are you?"];
+    /// let german: Vec<&str> = documents.into_iter()
+    ///     .filter(|text| factory.verify(text, "de", None).is_ok_and(|result| result.is_match))
+    ///     .collect();
+    /// assert_eq!(german, ["Guten Morgen, wie geht es dir?"]);
+    /// ```
+    pub fn verify(&self, text: &str, lang: impl Into<Lang>, alpha: Option<f64>) -> Result<VerificationResult, DetectorError> {
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        detector.verify(lang)
+    }
+
+    /// Shortcut method to score a text against a few languages only.
+    ///
+    /// See `Detector::score_languages`; the cache and the label set are not used.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `langs` - The languages to score, as `Lang` values or codes.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The requested languages with their probability among them, sorted by probability descending.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let scores = factory.score_languages("Das ist ein kurzer Satz", &["en", "de"], None).unwrap();
+    /// let is_english = scores[0].0 == "en";
+    /// assert!(!is_english);
+    /// ```
+    pub fn score_languages<L: Into<Lang> + Clone>(&self, text: &str, langs: &[L], alpha: Option<f64>) -> Result<Vec<(Lang, f64)>, DetectorError> {
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        detector.score_languages(langs)
+    }
+
+    /// Shortcut method to get the `k` most probable languages in one call.
+    ///
+    /// See `Detector::top_k`; like `get_probabilities_full` it bypasses the cache and the label set.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `k` - Maximum number of languages to return.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// Up to `k` languages, sorted by probability descending.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let result = factory.top_k("Hello world!", 3, None).unwrap();
+    /// assert_eq!(result.len(), 3);
+    /// ```
+    pub fn top_k(&self, text: &str, k: usize, alpha: Option<f64>) -> Result<Vec<Language>, DetectorError> {
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        detector.top_k(k)
+    }
+
+    /// Shortcut method to get the languages almost as probable as the detected one.
+    ///
+    /// See `Detector::alternatives`; the cache and the label set are not used.
+    ///
+    /// # Arguments
+    /// * `text` - The text to analyze.
+    /// * `max_gap` - Maximum probability difference to the winner.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The runner-up languages within `max_gap` of the winner, sorted by probability descending.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let suggestions = factory.alternatives("Dit is een kort zinnetje", 0.5, None).unwrap();
+    /// ```
+    pub fn alternatives(&self, text: &str, max_gap: f64, alpha: Option<f64>) -> Result<Vec<Language>, DetectorError> {
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        detector.alternatives(max_gap)
+    }
+
+    /// Detects the language of many documents at once.
+    ///
+    /// With the `rayon` feature the documents are processed in parallel on the global
+    /// rayon thread pool; otherwise they are processed sequentially. With a seed set,
+    /// every document gets the same result as a single `detect` call. With `batch_dedup`
+    /// enabled, repeated documents are only classified once.
+    ///
+    /// # Arguments
+    /// * `texts` - The documents to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The detection result of every document, in input order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().with_seed(Some(42)).build();
+    /// let results = factory.detect_batch(&["Hello world!", "Bonjour le monde !"], None);
+    /// assert_eq!(results.len(), 2);
+    /// ```
+    pub fn detect_batch(&self, texts: &[&str], alpha: Option<f64>) -> Vec<Result<String, DetectorError>> {
+        self.map_batch(texts, |text| self.detect(text, alpha))
+    }
+
+    /// Computes language probabilities of many documents at once.
+    ///
+    /// Parallel with the `rayon` feature, see `detect_batch`.
+    ///
+    /// # Arguments
+    /// * `texts` - The documents to analyze.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// The probabilities of every document (sorted by probability descending), in input order.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let results = factory.get_probabilities_batch(&["Hello world!", "Hallo Welt!"], None);
+    /// ```
+    pub fn get_probabilities_batch(&self, texts: &[&str], alpha: Option<f64>) -> Vec<Result<Vec<Language>, DetectorError>> {
+        self.map_batch(texts, |text| self.get_probabilities(text, alpha))
+    }
+
+    /// Lazily detects the language of a stream of documents.
+    ///
+    /// Unlike `detect_batch` the documents are pulled from `texts` only as results are
+    /// consumed, so corpora of any size can be streamed with bounded memory. With the
+    /// `rayon` feature, chunks of a few documents per thread are processed in parallel;
+    /// results are always returned in input order. Uses the cache and the label set like
+    /// `detect_lang`.
+    ///
+    /// # Arguments
+    /// * `texts` - The documents to analyze, e.g. the lines of a file.
+    /// * `alpha` - Optional alpha smoothing parameter.
+    ///
+    /// # Returns
+    /// An iterator over the detected `Language` of every document.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use std::io::BufRead;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::language::Lang;
+    ///
+    /// let factory = DetectorFactory::default().with_seed(Some(42)).build();
+    /// let input = std::io::Cursor::new("Hello world, how are you?\nBonjour tout le monde !\n");
+    /// let lines = input.lines().map_while(Result::ok);
+    /// let langs: Vec<Lang> = factory.detect_iter(lines, None)
+    ///     .filter_map(|result| result.ok()?.lang)
+    ///     .collect();
+    /// assert_eq!(langs, vec!["en", "fr"]);
+    /// ```
+    pub fn detect_iter<'a, I>(&'a self, texts: I, alpha: Option<f64>) -> impl Iterator<Item = Result<Language, DetectorError>> + 'a
+    where
+        I: IntoIterator<Item = String>,
+        I::IntoIter: 'a,
+    {
+        #[cfg(feature = "rayon")]
+        {
+            use rayon::prelude::*;
+            let chunk_size = rayon::current_num_threads() * Self::ITER_CHUNK_PER_THREAD;
+            let mut texts = texts.into_iter();
+            std::iter::from_fn(move || {
+                let chunk: Vec<String> = texts.by_ref().take(chunk_size).collect();
+                if chunk.is_empty() {
+                    return None;
+                }
+                Some(chunk.par_iter().map(|text| self.detect_lang(text, alpha)).collect::<Vec<_>>())
+            }).flatten()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            texts.into_iter().map(move |text| self.detect_lang(&text, alpha))
+        }
+    }
+
+    /// Applies `f` to every text of a batch, in parallel with the `rayon` feature.
+    ///
+    /// With `batch_dedup` enabled, `f` is only applied to the first of the texts sharing
+    /// the same whitespace-normalized form and its result is copied to the others.
+    fn map_batch<T, F>(&self, texts: &[&str], f: F) -> Vec<T>
+    where
+        T: Clone + Send,
+        F: Fn(&str) -> T + Sync + Send,
+    {
+        let (unique, slots): (Vec<&str>, Vec<usize>) = if self.batch_dedup {
+            let mut first: HashMap<String, usize> = HashMap::new();
+            let mut unique = Vec::new();
+            let slots = texts.iter().map(|text| {
+                let key = text.split_whitespace().collect::<Vec<&str>>().join(" ");
+                *first.entry(key).or_insert_with(|| {
+                    unique.push(*text);
+                    unique.len() - 1
+                })
+            }).collect();
+            (unique, slots)
+        } else {
+            (texts.to_vec(), (0..texts.len()).collect())
+        };
+        #[cfg(feature = "rayon")]
+        let results: Vec<T> = {
+            use rayon::prelude::*;
+            unique.par_iter().map(|text| f(text)).collect()
+        };
+        #[cfg(not(feature = "rayon"))]
+        let results: Vec<T> = unique.iter().map(|text| f(text)).collect();
+        if !self.batch_dedup {
+            return results;
+        }
+        slots.into_iter().map(|slot| results[slot].clone()).collect()
+    }
+
+    /// Runs detection for `get_probabilities`, bypassing the cache.
+    fn compute_probabilities(&self, text: &str, alpha: Option<f64>) -> Result<Vec<Language>, DetectorError> {
+        let mut detector = self.create(alpha);
+        detector.append(text);
+        match &self.label_set {
+            Some(label_set) => {
+                detector.detect_lang()?;
+                Ok(label_set.collapse(&detector.langlist, detector.langprob.as_deref().unwrap_or_default(), self.params.prob_threshold))
+            }
+            None => detector.get_probabilities(),
+        }
+    }
+
+    /// Loads all language profiles from a directory of JSON files.
+    ///
+    /// Files ending in `.gz` or `.zst` (e.g. `en.json.gz`) are decompressed transparently
+    /// with the `gzip` or `zstd` feature, and rejected with an error without it.
+    ///
+    /// # Arguments
+    /// * `profile_directory` - Path to directory containing JSON profile files.
+    ///
+    /// # Returns
+    /// Ok(()) on success, or an error string on failure.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let mut factory = DetectorFactory::new().build();
+    /// factory.load_profile("profiles/").unwrap();
+    /// ```
+    pub fn load_profile<P: AsRef<Path>>(&mut self, profile_directory: P) -> Result<(), String> {
+        let json_profiles = Self::read_profile_directory(&profile_directory, self.profile_limits.max_file_size, |_| true)?;
+        #[cfg(feature = "log")]
+        log::debug!("Loading {} profiles from {:?}", json_profiles.len(), profile_directory.as_ref());
+        let json_refs: Vec<(&str, &str)> = json_profiles.iter().map(|(name, json)| (name.as_str(), json.as_str())).collect();
+        self.load_named_json_profiles(&json_refs)
+            .map_err(|e| format!("Failed to parse JSON profiles: {:?}", e))?;
+        Ok(())
+    }
+
+    /// Saves the loaded languages and probabilities in the compact binary format.
+    ///
+    /// Loading the binary file with `load_binary` is much faster than parsing the
+    /// JSON profiles. See `ProfileData::to_bytes` for the layout.
+    ///
+    /// # Arguments
+    /// * `path` - Output file path.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::IoError` if the file cannot be written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let path = std::env::temp_dir().join("langdetect-doc-profiles.bin");
+    /// factory.save_binary(&path).unwrap();
+    ///
+    /// let mut loaded = DetectorFactory::new().build();
+    /// loaded.load_binary(&path).unwrap();
+    /// assert_eq!(loaded.get_lang_list(), factory.get_lang_list());
+    /// ```
+    pub fn save_binary<P: AsRef<Path>>(&self, path: P) -> Result<(), DetectorFactoryError> {
+        fs::write(path, self.profile_data.to_bytes(&self.langlist))
+            .map_err(|e| DetectorFactoryError::IoError(e.to_string()))
+    }
+
+    /// Replaces the loaded languages with the ones from a binary file written by `save_binary`.
+    ///
+    /// The file is checked against `profile_limits`, see `ProfileData::from_bytes_with_limits`.
+    ///
+    /// # Arguments
+    /// * `path` - Binary profile file path.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::IoError` if the file cannot be read,
+    /// `DetectorFactoryError::InvalidFormat` if it is malformed and
+    /// `DetectorFactoryError::LimitExceeded` if it exceeds `profile_limits`.
+    pub fn load_binary<P: AsRef<Path>>(&mut self, path: P) -> Result<(), DetectorFactoryError> {
+        self.invalidate_cache();
+        let file = fs::File::open(path).map_err(|e| DetectorFactoryError::IoError(e.to_string()))?;
+        let bytes = read_model(file, &self.profile_limits)?;
+        let (langlist, profile_data) = ProfileData::from_bytes_with_limits(&bytes, &self.profile_limits).map_err(model_error)?;
+        self.langlist = Arc::new(langlist);
+        self.profile_data = Arc::new(profile_data);
+        Ok(())
+    }
+
+    /// Saves the loaded languages and probabilities in the mapped format.
+    ///
+    /// A file in this format is used without decoding by `load_mapped`, see
+    /// `ProfileData::to_mapped_bytes` for the layout. It is larger than the binary format
+    /// of `save_binary`, since it stores a dense probability matrix.
+    ///
+    /// # Arguments
+    /// * `path` - Output file path.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::IoError` if the file cannot be written.
+    pub fn save_mapped<P: AsRef<Path>>(&self, path: P) -> Result<(), DetectorFactoryError> {
+        fs::write(path, self.profile_data.to_mapped_bytes(&self.langlist))
+            .map_err(|e| DetectorFactoryError::IoError(e.to_string()))
+    }
+
+    /// Replaces the loaded languages with a read-only table from a file written by `save_mapped`.
+    ///
+    /// With the `mmap` feature the file is memory-mapped: startup costs no parsing, only the
+    /// pages detection touches are read, and processes mapping the same file share them.
+    /// Without it the file is read into memory once, which still skips building the n-gram
+    /// maps. Adding or removing languages afterwards copies the table into regular storage.
+    ///
+    /// The file must not be modified while the factory or its detectors use it. It is checked
+    /// against `profile_limits`, see `ProfileData::from_mapped_bytes_with_limits`.
+    ///
+    /// # Arguments
+    /// * `path` - Mapped profile file path.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::IoError` if the file cannot be read,
+    /// `DetectorFactoryError::InvalidFormat` if it is malformed and
+    /// `DetectorFactoryError::LimitExceeded` if it exceeds `profile_limits`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let path = std::env::temp_dir().join("langdetect-doc-profiles.mapped");
+    /// factory.save_mapped(&path).unwrap();
+    ///
+    /// let mut loaded = DetectorFactory::new().with_seed(Some(42)).build();
+    /// loaded.load_mapped(&path).unwrap();
+    /// assert_eq!(loaded.get_lang_list(), factory.get_lang_list());
+    /// assert_eq!(loaded.detect("Bonjour tout le monde !", None).unwrap(), "fr");
+    /// ```
+    pub fn load_mapped<P: AsRef<Path>>(&mut self, path: P) -> Result<(), DetectorFactoryError> {
+        self.invalidate_cache();
+        let file = fs::File::open(path).map_err(|e| DetectorFactoryError::IoError(e.to_string()))?;
+        // SAFETY: the mapping is read-only; modifying the file while it is mapped is documented as unsupported
+        #[cfg(feature = "mmap")]
+        let bytes = unsafe { memmap2::Mmap::map(&file) }.map_err(|e| DetectorFactoryError::IoError(e.to_string()))?;
+        #[cfg(not(feature = "mmap"))]
+        let bytes = read_model(file, &self.profile_limits)?;
+        let (langlist, profile_data) = ProfileData::from_mapped_bytes_with_limits(bytes, &self.profile_limits).map_err(model_error)?;
+        self.langlist = Arc::new(langlist);
+        self.profile_data = Arc::new(profile_data);
+        Ok(())
+    }
+
+    /// Saves the loaded languages and probabilities as a gzip-compressed snapshot.
+    ///
+    /// The snapshot is the binary format of `save_binary` compressed with gzip, so a service
+    /// can ship one prebuilt model file and load it with `import` instead of parsing profiles
+    /// at startup. Settings like parameters or priors are not included.
+    ///
+    /// # Arguments
+    /// * `path` - Output file path.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::IoError` if the file cannot be written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let path = std::env::temp_dir().join("langdetect-doc-snapshot.bin.gz");
+    /// factory.export(&path).unwrap();
+    ///
+    /// let mut loaded = DetectorFactory::new().build();
+    /// loaded.import(&path).unwrap();
+    /// assert_eq!(loaded.get_lang_list(), factory.get_lang_list());
+    /// ```
+    #[cfg(feature = "gzip")]
+    pub fn export<P: AsRef<Path>>(&self, path: P) -> Result<(), DetectorFactoryError> {
+        use std::io::Write;
+        let file = fs::File::create(path).map_err(|e| DetectorFactoryError::IoError(e.to_string()))?;
+        let mut encoder = flate2::write::GzEncoder::new(std::io::BufWriter::new(file), flate2::Compression::default());
+        encoder.write_all(&self.profile_data.to_bytes(&self.langlist))
+            .and_then(|_| encoder.finish()?.flush())
+            .map_err(|e| DetectorFactoryError::IoError(e.to_string()))
+    }
+
+    /// Replaces the loaded languages with the ones from a snapshot written by `export`.
+    ///
+    /// Decompression stops after `max_model_size` bytes of `profile_limits`, and the data is
+    /// checked against the limits like by `load_binary`.
+    ///
+    /// # Arguments
+    /// * `path` - Snapshot file path.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::IoError` if the file cannot be read or decompressed,
+    /// `DetectorFactoryError::InvalidFormat` if the decompressed data is malformed and
+    /// `DetectorFactoryError::LimitExceeded` if it exceeds `profile_limits`.
+    #[cfg(feature = "gzip")]
+    pub fn import<P: AsRef<Path>>(&mut self, path: P) -> Result<(), DetectorFactoryError> {
+        self.invalidate_cache();
+        let file = fs::File::open(path).map_err(|e| DetectorFactoryError::IoError(e.to_string()))?;
+        let bytes = read_model(flate2::read::GzDecoder::new(std::io::BufReader::new(file)), &self.profile_limits)?;
+        let (langlist, profile_data) = ProfileData::from_bytes_with_limits(&bytes, &self.profile_limits).map_err(model_error)?;
+        self.langlist = Arc::new(langlist);
+        self.profile_data = Arc::new(profile_data);
+        Ok(())
+    }
+
+    /// Converts a directory of JSON profiles into a single binary profile file.
+    ///
+    /// # Arguments
+    /// * `profile_directory` - Directory containing JSON profile files.
+    /// * `output` - Output file path.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::IoError` if the profiles cannot be loaded or the output cannot be written.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    ///
+    /// let output = std::env::temp_dir().join("langdetect-doc-converted.bin");
+    /// DetectorFactory::convert_json_to_binary(DetectorFactory::get_default_profiles_path(), &output).unwrap();
+    /// ```
+    pub fn convert_json_to_binary<P: AsRef<Path>, Q: AsRef<Path>>(profile_directory: P, output: Q) -> Result<(), DetectorFactoryError> {
+        let mut factory = DetectorFactory::new().build();
+        factory.load_profile(profile_directory).map_err(DetectorFactoryError::IoError)?;
+        factory.save_binary(output)
+    }
+
+    /// Reads the file name and contents of every profile file in a directory whose language passes the filter.
+    ///
+    /// The filter gets the file name without `.json`, `.gz` and `.zst` extensions. Compressed
+    /// files are decompressed, see `read_limited`. Files larger than `max_file_size` bytes
+    /// (after decompression) are rejected without reading them completely.
+    fn read_profile_directory<P: AsRef<Path>>(profile_directory: P, max_file_size: usize, filter: impl Fn(&str) -> bool) -> Result<Vec<(String, String)>, String> {
+        let dir = profile_directory.as_ref();
+        let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read profile directory: {}", e))?;
+        let mut json_profiles = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            if path.is_file() && filter(profile_file_stem(&file_name)) {
+                let content = read_limited(&path, max_file_size)
+                    .map_err(|e| format!("Failed to read file {:?}: {}", path, e))?;
+                json_profiles.push((file_name, content));
+            }
+        }
+        Ok(json_profiles)
+    }
+}
+
+/// Reads a model file (binary, mapped or decompressed snapshot) of at most `max_model_size` bytes.
+///
+/// # Errors
+/// Returns `DetectorFactoryError::IoError` if reading fails and
+/// `DetectorFactoryError::LimitExceeded` if there are more bytes.
+fn read_model(reader: impl std::io::Read, limits: &ProfileLimits) -> Result<Vec<u8>, DetectorFactoryError> {
+    use std::io::Read;
+    let mut bytes = Vec::new();
+    reader.take((limits.max_model_size as u64).saturating_add(1)).read_to_end(&mut bytes)
+        .map_err(|e| DetectorFactoryError::IoError(e.to_string()))?;
+    limits.check_model_size(bytes.len()).map_err(model_error)?;
+    Ok(bytes)
+}
+
+/// Converts an error of decoding a model into the factory error.
+fn model_error(error: LangProfileJsonError) -> DetectorFactoryError {
+    match error {
+        LangProfileJsonError::LimitExceeded(e) => DetectorFactoryError::LimitExceeded(e),
+        e => DetectorFactoryError::InvalidFormat(e.to_string()),
+    }
+}
+
+/// Splits text on blank lines into byte ranges of trimmed paragraphs.
+fn split_paragraphs(text: &str) -> Vec<Range<usize>> {
+    let mut paragraphs = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            if let Some(s) = start.take() {
+                paragraphs.push(s..end);
+            }
+        } else {
+            let leading = line.len() - line.trim_start().len();
+            start.get_or_insert(offset + leading);
+            end = offset + leading + trimmed.len();
+        }
+        offset += line.len();
+    }
+    if let Some(s) = start {
+        paragraphs.push(s..end);
+    }
+    paragraphs
+}
+
+/// Splits text into byte ranges of trimmed sentences.
+///
+/// A sentence ends at a line break, after a sentence terminator (`.`, `!`, `?`, `…`, `؟`, `।`, ...)
+/// followed by whitespace, or right after a full-width terminator (`。`, `！`, `？`). Closing
+/// quotes and brackets after a terminator stay with the sentence, while terminators inside
+/// words (e.g. "3.14") do not end it. This is the default splitter of `DetectorFactory::detect_sentences`.
+///
+/// # Arguments
+/// * `text` - The text to split.
+///
+/// # Returns
+/// The byte ranges of the non-empty sentences, in text order.
+///
+/// # Example
+///
+/// ```rust
+/// use langdetect_rs::detector_factory::split_sentences;
+///
+/// let text = "It costs 3.14 euros. \"Really?\" Yes!\n你好。再见";
+/// let sentences: Vec<&str> = split_sentences(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(sentences, vec!["It costs 3.14 euros.", "\"Really?\"", "Yes!", "你好。", "再见"]);
+/// ```
+pub fn split_sentences(text: &str) -> Vec<Range<usize>> {
+    let mut sentences = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut end = 0;
+    let mut terminated = false;
+    for (i, ch) in text.char_indices() {
+        if ch == '\n' || (ch.is_whitespace() && terminated) {
+            if let Some(s) = start.take() {
+                sentences.push(s..end);
+            }
+            terminated = false;
+            continue;
+        }
+        if ch.is_whitespace() {
+            continue;
+        }
+        start.get_or_insert(i);
+        end = i + ch.len_utf8();
+        match ch {
+            '。' | '！' | '？' | '｡' => {
+                sentences.push(start.take().unwrap_or(i)..end);
+                terminated = false;
+            }
+            '.' | '!' | '?' | '…' | '؟' | '।' | '॥' | '።' => terminated = true,
+            '"' | '\'' | ')' | ']' | '»' | '”' | '’' | '」' | '』' => {}
+            _ => terminated = false,
+        }
+    }
+    if let Some(s) = start {
+        sentences.push(s..end);
+    }
+    sentences
+}
+
+/// Builder for `DetectorFactory` with fluent setters.
+///
+/// Provides a convenient way to configure a DetectorFactory before building it.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::detector_factory::DetectorFactory;
+/// use langdetect_rs::language::Lang;
+///
+/// let factory = DetectorFactory::new()
+///     .with_langlist(vec![Lang::En, Lang::Fr])
+///     .with_seed(Some(42))
+///     .build();
 /// ```
 pub struct DetectorFactoryBuilder {
     factory: DetectorFactory,
 }
 
-impl DetectorFactoryBuilder {
-    /// Set the word language probability map.
+impl DetectorFactoryBuilder {
+    /// Set the word language probability map.
+    ///
+    /// # Arguments
+    /// * `word_lang_prob_map` - A HashMap of word to language probabilities.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let mut word_lang_prob_map = HashMap::new();
+    /// word_lang_prob_map.insert("hello".to_string(), vec![0.5, 0.3]);
+    /// let builder = DetectorFactory::new().with_word_lang_prob_map(word_lang_prob_map);
+    /// ```
+    pub fn with_word_lang_prob_map(mut self, word_lang_prob_map: HashMap<String, Vec<f64>>) -> Self {
+        self.factory.profile_data = Arc::new(ProfileData::from_word_lang_prob_map(&word_lang_prob_map));
+        self
+    }
+
+    /// Set the per-language n-gram probability storage.
+    ///
+    /// # Arguments
+    /// * `profile_data` - N-gram probabilities of the languages.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::profile_data::ProfileData;
+    /// let mut profile_data = ProfileData::default();
+    /// profile_data.resize_languages(2);
+    /// profile_data.set("hello", 0, 0.5);
+    /// let builder = DetectorFactory::new().with_profile_data(profile_data);
+    /// ```
+    pub fn with_profile_data(mut self, profile_data: ProfileData) -> Self {
+        self.factory.profile_data = Arc::new(profile_data);
+        self
+    }
+
+    /// Set the language list.
+    ///
+    /// # Arguments
+    /// * `langlist` - A vector of language names.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::language::Lang;
+    /// let builder = DetectorFactory::new().with_langlist(vec![Lang::En, Lang::Fr]);
+    /// ```
+    pub fn with_langlist(mut self, langlist: Vec<Lang>) -> Self {
+        self.factory.langlist = Arc::new(langlist);
+        self
+    }
+
+    /// Set the seed for randomization.
+    ///
+    /// # Arguments
+    /// * `seed` - An optional u64 seed value.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let builder = DetectorFactory::new().with_seed(Some(42));
+    /// ```
+    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
+        self.factory.seed = seed;
+        self
+    }
+
+    /// Set the label set detected languages are collapsed onto.
+    ///
+    /// When set, `detect`, `detect_lang` and `get_probabilities` of the factory report
+    /// labels instead of language identifiers, with the probabilities of all languages
+    /// sharing a label summed up.
     ///
     /// # Arguments
-    /// * `word_lang_prob_map` - A HashMap of word to language probabilities.
+    /// * `label_set` - The label set, or None to report languages as-is.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::{DetectorFactory, LabelSet};
+    /// let builder = DetectorFactory::new().with_label_set(Some(LabelSet::new("other").with_label("en", &["en"])));
+    /// ```
+    pub fn with_label_set(mut self, label_set: Option<LabelSet>) -> Self {
+        self.factory.label_set = label_set;
+        self
+    }
+
+    /// Set the label reported when no language is detected.
+    ///
+    /// Defaults to `Detector::UNKNOWN_LANG` ("unknown").
+    ///
+    /// # Arguments
+    /// * `label` - The unknown-language label, e.g. "und" (BCP-47).
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let builder = DetectorFactory::new().with_unknown_label("und");
+    /// ```
+    pub fn with_unknown_label(mut self, label: &str) -> Self {
+        self.factory.unknown_label = label.to_string();
+        self
+    }
+
+    /// Set whether profiles loaded by `load_profile`/`load_json_profile` are ordered by language code.
+    ///
+    /// Enabled by default for stable, reproducible factories. Disable it to keep the
+    /// order in which the profiles are given.
+    ///
+    /// # Arguments
+    /// * `sort` - Whether to sort profiles by language code.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let builder = DetectorFactory::new().with_sorted_profiles(false);
+    /// ```
+    pub fn with_sorted_profiles(mut self, sort: bool) -> Self {
+        self.factory.sort_profiles = sort;
+        self
+    }
+
+    /// Set a result cache for the shortcut detection methods.
+    ///
+    /// `detect`, `detect_lang` and `get_probabilities` of the factory answer repeated
+    /// texts from the cache. The cache can be shared between threads and factory clones.
+    ///
+    /// # Arguments
+    /// * `cache` - The shared cache, or None to disable caching.
+    ///
+    /// # Example
+    /// ```
+    /// use std::sync::Arc;
+    /// use langdetect_rs::cache::DetectionCache;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let builder = DetectorFactory::new().with_cache(Some(Arc::new(DetectionCache::new().build())));
+    /// ```
+    pub fn with_cache(mut self, cache: Option<Arc<DetectionCache>>) -> Self {
+        self.factory.cache = cache;
+        self
+    }
+
+    /// Restrict detection to a subset of the loaded languages.
+    ///
+    /// The profiles of the other languages stay loaded, so the restriction can be
+    /// changed later through `language_filter` without reloading the profiles.
+    ///
+    /// # Arguments
+    /// * `langs` - The languages which can be detected, as `Lang` values or codes.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::language::Lang;
+    /// let factory = DetectorFactory::default().with_allowed_languages(&[Lang::En, Lang::De, Lang::Fr]).build();
+    /// ```
+    pub fn with_allowed_languages<L: Into<Lang> + Clone>(mut self, langs: &[L]) -> Self {
+        let langs = langs.iter().cloned().map(Into::into).collect();
+        self.factory.language_filter = Some(LanguageFilter::Allow(langs));
+        self
+    }
+
+    /// Set prior probabilities of the loaded languages, see `DetectorFactory::set_priors`.
+    ///
+    /// # Arguments
+    /// * `priors` - Language code to (relative) prior probability mapping.
+    ///
+    /// # Errors
+    /// Returns `DetectorError::UnknownLanguage` or `DetectorError::InvalidPrior` for invalid priors.
     ///
     /// # Example
     /// ```
     /// use std::collections::HashMap;
     /// use langdetect_rs::detector_factory::DetectorFactory;
-    /// let mut word_lang_prob_map = HashMap::new();
-    /// word_lang_prob_map.insert("hello".to_string(), vec![0.5, 0.3]);
-    /// let builder = DetectorFactory::new().with_word_lang_prob_map(word_lang_prob_map);
+    /// let factory = DetectorFactory::default()
+    ///     .with_priors(HashMap::from([("en", 0.7), ("es", 0.3)]))
+    ///     .unwrap()
+    ///     .build();
     /// ```
-    pub fn with_word_lang_prob_map(mut self, word_lang_prob_map: HashMap<String, Vec<f64>>) -> Self {
-        self.factory.word_lang_prob_map = word_lang_prob_map;
+    pub fn with_priors(mut self, priors: HashMap<&str, f64>) -> Result<Self, DetectorError> {
+        self.factory.set_priors(priors)?;
+        Ok(self)
+    }
+
+    /// Set the minimum prior of every language.
+    ///
+    /// Languages with a zero (or missing) prior can never be detected; with a floor they
+    /// start with a small probability that strong evidence can still raise.
+    ///
+    /// # Arguments
+    /// * `floor` - Minimum prior within [0, 1), applied before renormalization.
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let factory = DetectorFactory::default()
+    ///     .with_priors(HashMap::from([("en", 1.0)]))
+    ///     .unwrap()
+    ///     .with_prior_floor(0.01)
+    ///     .build();
+    /// assert_eq!(factory.detect("Bonjour tout le monde, comment allez-vous ?", None).unwrap(), "fr");
+    /// ```
+    pub fn with_prior_floor(mut self, floor: f64) -> Self {
+        self.factory.prior_floor = floor;
         self
     }
 
-    /// Set the language list.
+    /// Set the default alpha smoothing parameter of created detectors (default: 0.5).
     ///
     /// # Arguments
-    /// * `langlist` - A vector of language names.
+    /// * `alpha` - The smoothing parameter; `create(Some(alpha))` still overrides it.
     ///
     /// # Example
     /// ```
     /// use langdetect_rs::detector_factory::DetectorFactory;
-    /// let builder = DetectorFactory::new().with_langlist(vec!["en".to_string(), "fr".to_string()]);
+    /// let factory = DetectorFactory::new().with_alpha(0.3).build();
+    /// assert_eq!(factory.create(None).alpha, 0.3);
     /// ```
-    pub fn with_langlist(mut self, langlist: Vec<String>) -> Self {
-        self.factory.langlist = langlist;
+    pub fn with_alpha(mut self, alpha: f64) -> Self {
+        self.factory.params.alpha = alpha;
         self
     }
 
-    /// Set the seed for randomization.
+    /// Set the number of EM trials averaged per detection (default: 7).
+    ///
+    /// Fewer trials are faster but less stable on short texts.
     ///
     /// # Arguments
-    /// * `seed` - An optional u64 seed value.
+    /// * `n_trial` - Number of trials; values below 1 are raised to 1.
     ///
     /// # Example
     /// ```
     /// use langdetect_rs::detector_factory::DetectorFactory;
-    /// let builder = DetectorFactory::new().with_seed(Some(42));
+    /// let builder = DetectorFactory::new().with_trials(3);
     /// ```
-    pub fn with_seed(mut self, seed: Option<u64>) -> Self {
-        self.factory.seed = seed;
+    pub fn with_trials(mut self, n_trial: usize) -> Self {
+        self.factory.params.n_trial = n_trial.max(1);
+        self
+    }
+
+    /// Set the maximum number of characters taken from every appended text (default: 10000).
+    ///
+    /// # Arguments
+    /// * `max_text_length` - Maximum number of characters.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let builder = DetectorFactory::new().with_max_text_length(2000);
+    /// ```
+    pub fn with_max_text_length(mut self, max_text_length: usize) -> Self {
+        self.factory.params.max_text_length = max_text_length;
+        self
+    }
+
+    /// Set the minimum probability for a language to be reported (default: 0.1).
+    ///
+    /// # Arguments
+    /// * `prob_threshold` - The threshold; `detect()` returns the unknown label if no language exceeds it.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let builder = DetectorFactory::new().with_prob_threshold(0.5);
+    /// ```
+    pub fn with_prob_threshold(mut self, prob_threshold: f64) -> Self {
+        self.factory.params.prob_threshold = prob_threshold;
+        self
+    }
+
+    /// Set the minimum probability for `verify` to accept a language (default: 0.5).
+    ///
+    /// Raise it to trade recall for precision when filtering text down to one language.
+    ///
+    /// # Arguments
+    /// * `verify_threshold` - The threshold, see `Detector::verify_threshold`.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let builder = DetectorFactory::new().with_verify_threshold(0.9);
+    /// ```
+    pub fn with_verify_threshold(mut self, verify_threshold: f64) -> Self {
+        self.factory.params.verify_threshold = verify_threshold;
+        self
+    }
+
+    /// Set the maximum number of EM iterations per trial (default: 1000).
+    ///
+    /// # Arguments
+    /// * `iteration_limit` - Maximum number of iterations.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let builder = DetectorFactory::new().with_iteration_limit(200);
+    /// ```
+    pub fn with_iteration_limit(mut self, iteration_limit: usize) -> Self {
+        self.factory.params.iteration_limit = iteration_limit;
+        self
+    }
+
+    /// Set the probability of the top language at which a trial stops early (default: 0.99999).
+    ///
+    /// # Arguments
+    /// * `conv_threshold` - The convergence threshold.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let builder = DetectorFactory::new().with_conv_threshold(0.999);
+    /// ```
+    pub fn with_conv_threshold(mut self, conv_threshold: f64) -> Self {
+        self.factory.params.conv_threshold = conv_threshold;
+        self
+    }
+
+    /// Set the smoothing denominator (default: 10000.0), see `DetectorFactory::derived_base_freq`.
+    ///
+    /// # Arguments
+    /// * `base_freq` - The base frequency; lower values smooth more.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let builder = DetectorFactory::new().with_base_freq(500.0);
+    /// ```
+    pub fn with_base_freq(mut self, base_freq: f64) -> Self {
+        self.factory.params.base_freq = base_freq;
+        self
+    }
+
+    /// Use letters lost by n-gram normalization as additional evidence for Arabic-script languages.
+    ///
+    /// The built-in profiles share one character for Arabic yeh (ي) and Farsi yeh (ی) and
+    /// treat ZWNJ as a word boundary, so short Arabic, Persian and Urdu texts are often
+    /// confused. With markers enabled, every such letter of the text counts as an observation
+    /// favoring the languages writing it, see `utils::arabic`.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether markers are used (default: false).
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let factory = DetectorFactory::default().with_arabic_markers(true).with_seed(Some(42)).build();
+    /// // "Between": the Arabic yeh rules out Persian and Urdu
+    /// assert_eq!(factory.detect("\u{0628}\u{064A}\u{0646}", None).unwrap(), "ar");
+    /// ```
+    pub fn with_arabic_markers(mut self, enabled: bool) -> Self {
+        self.factory.params.arabic_markers = enabled;
+        self
+    }
+
+    /// Let function words decide between Hindi, Marathi and Nepali on short texts.
+    ///
+    /// The three languages share Devanagari and many n-grams, so short texts are often
+    /// confused. When two of them are the most probable languages, the one with the most
+    /// function words of its own (e.g. "है", "आहे", "छ") in the text is reported first,
+    /// see `utils::devanagari`.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether the tie-breaker is used (default: false).
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let factory = DetectorFactory::default().with_devanagari_tiebreak(true).with_seed(Some(0)).build();
+    /// assert_eq!(factory.detect("मुझे पानी चाहिए", None).unwrap(), "hi");
+    /// ```
+    pub fn with_devanagari_tiebreak(mut self, enabled: bool) -> Self {
+        self.factory.params.devanagari_tiebreak = enabled;
+        self
+    }
+
+    /// Only consider languages written in the dominant script of each text.
+    ///
+    /// The EM loop then only updates the languages of that script, e.g. Bulgarian,
+    /// Macedonian, Russian and Ukrainian for Cyrillic text, which cuts detection time for
+    /// non-Latin text. Languages of other scripts can no longer win on mixed-script text.
+    /// The filter is skipped for texts whose script no loaded language uses.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether the pre-filter is used (default: false).
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let factory = DetectorFactory::default().with_script_prefilter(true).with_seed(Some(0)).build();
+    /// assert_eq!(factory.detect("Добрий день", None).unwrap(), "uk");
+    /// ```
+    pub fn with_script_prefilter(mut self, enabled: bool) -> Self {
+        self.factory.params.script_prefilter = enabled;
+        self
+    }
+
+    /// Log the EM algorithm of every detection (default: false).
+    ///
+    /// Trials, their iteration counts and the resulting probabilities are emitted through
+    /// the `log` facade, see `Detector::verbose`; without the `log` feature nothing is logged.
+    ///
+    /// # Arguments
+    /// * `enabled` - Whether the EM algorithm is logged.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let factory = DetectorFactory::default().with_verbose(true).build();
+    /// ```
+    pub fn with_verbose(mut self, enabled: bool) -> Self {
+        self.factory.params.verbose = enabled;
+        self
+    }
+
+    /// Set the character normalization rules of n-gram extraction.
+    ///
+    /// The default strict rules are those of the Java langdetect, which the built-in profiles
+    /// were trained with: every digit, apostrophe or hyphen ends a word. The lenient rules keep
+    /// them inside words ("mp3", "aujourd'hui", "e-mail"), which avoids made-up word boundaries
+    /// on modern web text at the cost of results differing from the Java library.
+    ///
+    /// # Arguments
+    /// * `normalization` - The rules, see `utils::ngram::Normalization`.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::utils::ngram::Normalization;
+    /// let factory = DetectorFactory::default().with_normalization(Normalization::Lenient).with_seed(Some(0)).build();
+    /// assert_eq!(factory.detect("Aujourd'hui, c'est l'e-mail du week-end", None).unwrap(), "fr");
+    /// ```
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.factory.params.normalization = normalization;
+        self
+    }
+
+    /// Classify repeated documents of a batch only once.
+    ///
+    /// `detect_batch` and `get_probabilities_batch` then group documents which only
+    /// differ in whitespace, classify every group once and copy the result to all its
+    /// documents. This pays off for data with heavy repetition (product titles, UI strings).
+    ///
+    /// # Arguments
+    /// * `dedup` - Whether to deduplicate batches.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let factory = DetectorFactory::default().with_batch_dedup(true).build();
+    /// let results = factory.detect_batch(&["Add to cart", "Add to  cart", "Ajouter au panier"], None);
+    /// assert_eq!(results.len(), 3);
+    /// ```
+    pub fn with_batch_dedup(mut self, dedup: bool) -> Self {
+        self.factory.batch_dedup = dedup;
+        self
+    }
+
+    /// Set the sentence and word splitter of `detect_sentences` and `detect_words`.
+    ///
+    /// The default splits on whitespace and punctuation, which fails for scripts written
+    /// without spaces (Thai, Japanese, ...); plug in a dictionary or ICU based segmenter there.
+    ///
+    /// # Arguments
+    /// * `segmenter` - The segmenter, see `segmenter::Segmenter`.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::segmenter::DefaultSegmenter;
+    /// let factory = DetectorFactory::default().with_segmenter(DefaultSegmenter).build();
+    /// ```
+    pub fn with_segmenter(mut self, segmenter: impl Segmenter + 'static) -> Self {
+        self.factory.segmenter = Arc::new(segmenter);
+        self
+    }
+
+    /// Set the removal of URLs, emails or other noise from text before detection.
+    ///
+    /// By default URLs and email addresses are removed; `TextScrubbing::Disabled` skips the
+    /// step for text known to be clean.
+    ///
+    /// # Arguments
+    /// * `scrubbing` - The scrubbing step, see `detector::TextScrubbing`.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector::TextScrubbing;
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let factory = DetectorFactory::default().with_scrubbing(TextScrubbing::Disabled).build();
+    /// ```
+    pub fn with_scrubbing(mut self, scrubbing: TextScrubbing) -> Self {
+        self.factory.scrubbing = scrubbing;
+        self
+    }
+
+    /// Set the size limits of profiles loaded afterwards with `load_profile` or `load_json_profile`,
+    /// and of models loaded with `load_binary`, `load_mapped` or `import`.
+    ///
+    /// Services loading user-supplied profiles should lower them to what they expect.
+    ///
+    /// # Arguments
+    /// * `limits` - The limits, see `ProfileLimits`.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::{DetectorFactory, DetectorFactoryError};
+    /// use langdetect_rs::utils::lang_profile::ProfileLimits;
+    /// let mut factory = DetectorFactory::new()
+    ///     .with_profile_limits(ProfileLimits::default().with_max_entries(10))
+    ///     .build();
+    /// let profiles = [
+    ///     r#"{"freq":{"a":1},"n_words":[1,0,0],"name":"xx"}"#,
+    ///     r#"{"freq":{"a":1,"b":1,"c":1,"d":1,"e":1,"f":1,"g":1,"h":1,"i":1,"j":1,"k":1},"n_words":[11,0,0],"name":"yy"}"#,
+    /// ];
+    /// assert!(matches!(factory.load_json_profile(&profiles), Err(DetectorFactoryError::LimitExceeded(_))));
+    /// ```
+    pub fn with_profile_limits(mut self, limits: ProfileLimits) -> Self {
+        self.factory.profile_limits = limits;
+        self
+    }
+
+    /// Exclude some of the loaded languages from detection.
+    ///
+    /// # Arguments
+    /// * `langs` - The languages which can not be detected, as `Lang` values or codes.
+    ///
+    /// # Example
+    /// ```
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// let factory = DetectorFactory::default().with_excluded_languages(&["af", "nl"]).build();
+    /// ```
+    pub fn with_excluded_languages<L: Into<Lang> + Clone>(mut self, langs: &[L]) -> Self {
+        let langs = langs.iter().cloned().map(Into::into).collect();
+        self.factory.language_filter = Some(LanguageFilter::Deny(langs));
         self
     }
 
@@ -452,7 +3024,8 @@ impl DetectorFactoryBuilder {
     /// use langdetect_rs::detector_factory::DetectorFactory;
     /// let factory = DetectorFactory::new().with_seed(Some(123)).build();
     /// ```
-    pub fn build(self) -> DetectorFactory {
+    pub fn build(mut self) -> DetectorFactory {
+        self.factory.invalidate_cache();
         self.factory
     }
 }
\ No newline at end of file
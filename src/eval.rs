@@ -0,0 +1,288 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::detector_factory::DetectorFactory;
+use crate::utils::script::dominant_script;
+
+/// A labeled document of an evaluation dataset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalSample {
+    /// Expected language code.
+    pub lang: String,
+    /// The document text.
+    pub text: String,
+}
+
+impl EvalSample {
+    /// Creates a new sample.
+    ///
+    /// # Arguments
+    /// * `lang` - Expected language code.
+    /// * `text` - The document text.
+    pub fn new(lang: &str, text: &str) -> Self {
+        EvalSample { lang: lang.to_string(), text: text.to_string() }
+    }
+
+    /// Parses a dataset with one `lang<TAB>text` sample per line.
+    ///
+    /// Empty lines and lines without a tab are skipped.
+    ///
+    /// # Arguments
+    /// * `content` - The dataset content.
+    ///
+    /// # Returns
+    /// The parsed samples, in file order.
+    pub fn parse_tsv(content: &str) -> Vec<EvalSample> {
+        content.lines()
+            .filter_map(|line| line.split_once('\t'))
+            .filter(|(lang, text)| !lang.trim().is_empty() && !text.trim().is_empty())
+            .map(|(lang, text)| EvalSample::new(lang.trim(), text))
+            .collect()
+    }
+}
+
+/// Latency distribution and throughput of a group of detections.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LatencyStats {
+    /// Number of timed detections.
+    pub count: usize,
+    /// Sum of all detection times.
+    pub total: Duration,
+    /// Median detection time.
+    pub p50: Duration,
+    /// 90th percentile of the detection time.
+    pub p90: Duration,
+    /// 99th percentile of the detection time.
+    pub p99: Duration,
+    /// Slowest detection.
+    pub max: Duration,
+}
+
+impl LatencyStats {
+    /// Computes the statistics of a set of detection times.
+    ///
+    /// Percentiles use the nearest-rank method.
+    ///
+    /// # Arguments
+    /// * `durations` - Detection times, in any order.
+    pub fn from_durations(mut durations: Vec<Duration>) -> Self {
+        if durations.is_empty() {
+            return LatencyStats::default();
+        }
+        durations.sort();
+        let percentile = |p: f64| {
+            let rank = ((p / 100.0) * durations.len() as f64).ceil() as usize;
+            durations[rank.clamp(1, durations.len()) - 1]
+        };
+        LatencyStats {
+            count: durations.len(),
+            total: durations.iter().sum(),
+            p50: percentile(50.0),
+            p90: percentile(90.0),
+            p99: percentile(99.0),
+            max: durations[durations.len() - 1],
+        }
+    }
+
+    /// Detections per second, computed from the total detection time.
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.total.as_secs_f64();
+        if seconds > 0.0 { self.count as f64 / seconds } else { 0.0 }
+    }
+}
+
+/// Accuracy and performance of one group of samples (a language, a script or all samples).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupReport {
+    /// Name of the group: the language code, the script name or "all".
+    pub name: String,
+    /// Number of samples in the group.
+    pub samples: usize,
+    /// Number of samples detected as their expected language.
+    pub correct: usize,
+    /// Number of characters in the group's samples.
+    pub chars: usize,
+    /// Detection latency of the group's samples.
+    pub latency: LatencyStats,
+}
+
+impl GroupReport {
+    /// Share of correctly detected samples, 0.0 for an empty group.
+    pub fn accuracy(&self) -> f64 {
+        if self.samples > 0 { self.correct as f64 / self.samples as f64 } else { 0.0 }
+    }
+
+    /// Characters processed per second of detection time.
+    pub fn chars_per_second(&self) -> f64 {
+        let seconds = self.latency.total.as_secs_f64();
+        if seconds > 0.0 { self.chars as f64 / seconds } else { 0.0 }
+    }
+}
+
+impl std::fmt::Display for GroupReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: accuracy={:.4} ({}/{}), docs/s={:.1}, chars/s={:.0}, p50={:?}, p90={:?}, p99={:?}, max={:?}",
+            self.name, self.accuracy(), self.correct, self.samples, self.latency.throughput(), self.chars_per_second(),
+            self.latency.p50, self.latency.p90, self.latency.p99, self.latency.max
+        )
+    }
+}
+
+/// Result of `evaluate`: accuracy together with latency percentiles and throughput.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EvalReport {
+    /// Figures over all samples.
+    pub overall: GroupReport,
+    /// Figures per expected language, ordered by language code.
+    pub per_language: Vec<GroupReport>,
+    /// Figures per dominant script of the samples, ordered by script name.
+    /// Samples without letters of a known script are grouped as "unknown".
+    pub per_script: Vec<GroupReport>,
+}
+
+impl std::fmt::Display for EvalReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{}", self.overall)?;
+        writeln!(f, "Per language:")?;
+        for group in self.per_language.iter() {
+            writeln!(f, "  {}", group)?;
+        }
+        writeln!(f, "Per script:")?;
+        for group in self.per_script.iter() {
+            writeln!(f, "  {}", group)?;
+        }
+        Ok(())
+    }
+}
+
+/// Measurements of a single sample.
+struct Measurement {
+    correct: bool,
+    chars: usize,
+    elapsed: Duration,
+}
+
+/// Evaluates a factory on a labeled dataset.
+///
+/// Every sample is detected with `DetectorFactory::detect` and timed individually,
+/// so the figures include detector creation and text preprocessing. Set a seed on the
+/// factory to get reproducible accuracy figures.
+///
+/// # Arguments
+/// * `factory` - The factory to evaluate.
+/// * `samples` - The labeled documents.
+///
+/// # Returns
+/// Accuracy, latency percentiles and throughput overall, per language and per script.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::detector_factory::DetectorFactory;
+/// use langdetect_rs::eval::{evaluate, EvalSample};
+///
+/// let factory = DetectorFactory::default().with_seed(Some(42)).build();
+/// let samples = EvalSample::parse_tsv("en\tThe weather is nice today.\nru\tСегодня хорошая погода.\n");
+/// let report = evaluate(&factory, &samples);
+/// println!("{}", report);
+/// assert_eq!(report.per_script.len(), 2);
+/// ```
+pub fn evaluate(factory: &DetectorFactory, samples: &[EvalSample]) -> EvalReport {
+    let mut by_language: BTreeMap<&str, Vec<Measurement>> = BTreeMap::new();
+    let mut by_script: BTreeMap<String, Vec<Measurement>> = BTreeMap::new();
+    let mut all = Vec::with_capacity(samples.len());
+    for sample in samples {
+        let start = Instant::now();
+        let detected = factory.detect(&sample.text, None);
+        let elapsed = start.elapsed();
+        let correct = detected.is_ok_and(|lang| lang == sample.lang);
+        let chars = sample.text.chars().count();
+        let script = dominant_script(&sample.text).map_or_else(|| "unknown".to_string(), |script| script.to_string());
+        by_language.entry(sample.lang.as_str()).or_default().push(Measurement { correct, chars, elapsed });
+        by_script.entry(script).or_default().push(Measurement { correct, chars, elapsed });
+        all.push(Measurement { correct, chars, elapsed });
+    }
+    EvalReport {
+        overall: group_report("all", &all),
+        per_language: by_language.iter().map(|(name, group)| group_report(name, group)).collect(),
+        per_script: by_script.iter().map(|(name, group)| group_report(name, group)).collect(),
+    }
+}
+
+/// Aggregates the measurements of a group.
+fn group_report(name: &str, measurements: &[Measurement]) -> GroupReport {
+    GroupReport {
+        name: name.to_string(),
+        samples: measurements.len(),
+        correct: measurements.iter().filter(|m| m.correct).count(),
+        chars: measurements.iter().map(|m| m.chars).sum(),
+        latency: LatencyStats::from_durations(measurements.iter().map(|m| m.elapsed).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_percentiles() {
+        let durations: Vec<Duration> = (1..=100).rev().map(Duration::from_millis).collect();
+        let stats = LatencyStats::from_durations(durations);
+        assert_eq!(stats.count, 100);
+        assert_eq!(stats.p50, Duration::from_millis(50));
+        assert_eq!(stats.p90, Duration::from_millis(90));
+        assert_eq!(stats.p99, Duration::from_millis(99));
+        assert_eq!(stats.max, Duration::from_millis(100));
+        assert_eq!(stats.total, Duration::from_millis(5050));
+        assert_eq!(LatencyStats::from_durations(Vec::new()), LatencyStats::default());
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let factory = DetectorFactory::default().with_seed(Some(0)).build();
+        let samples = EvalSample::parse_tsv(
+            "en\tThe quick brown fox jumps over the lazy dog.\nde\tDer schnelle braune Fuchs springt über den faulen Hund.\nbroken line\nru\tСъешь же ещё этих мягких французских булок.\nen\t12345\n",
+        );
+        assert_eq!(samples.len(), 4);
+        let report = evaluate(&factory, &samples);
+        assert_eq!(report.overall.samples, 4);
+        assert_eq!(report.overall.correct, 3);
+        let names: Vec<&str> = report.per_language.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, vec!["de", "en", "ru"]);
+        assert_eq!(report.per_language[1].accuracy(), 0.5);
+        let scripts: Vec<&str> = report.per_script.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(scripts, vec!["Cyrillic", "Latin", "unknown"]);
+        assert_eq!(report.per_script[1].latency.count, 2);
+    }
+
+    #[test]
+    fn test_arabic_markers() {
+        // Short texts whose yeh is the only hint: Arabic \u{064A} or Farsi \u{06CC}
+        let samples = EvalSample::parse_tsv(
+            "ar\t\u{0628}\u{064A}\u{0646}\nar\t\u{062F}\u{064A}\u{0646}\nar\t\u{0643}\u{064A}\u{0641} \u{062D}\u{0627}\u{0644}\u{0643}\n\
+             fa\t\u{0645}\u{06CC}\u{200C}\u{0631}\u{0648}\u{0645}\nfa\t\u{067E}\u{062F}\u{0631} \u{0648} \u{0645}\u{0627}\u{062F}\u{0631}\n\
+             ur\t\u{0628}\u{06C1}\u{062A}\nur\t\u{0679}\u{06BE}\u{06CC}\u{06A9}\n",
+        );
+        assert_eq!(samples.len(), 7);
+        let plain = evaluate(&DetectorFactory::default().with_seed(Some(0)).build(), &samples);
+        let marked = evaluate(&DetectorFactory::default().with_seed(Some(0)).with_arabic_markers(true).build(), &samples);
+        assert_eq!(plain.per_language[0].correct, 1);
+        assert_eq!(marked.per_language[0].correct, 3);
+        assert_eq!(marked.overall.correct, plain.overall.correct + 2);
+        assert_eq!(marked.overall.accuracy(), 1.0);
+    }
+
+    #[test]
+    fn test_devanagari_tiebreak() {
+        let samples = EvalSample::parse_tsv(
+            "hi\tमुझे पानी चाहिए\nhi\tयह किताब बहुत अच्छी है\nmr\tखूप छान\nmr\tमला पाणी हवे आहे\n\
+             ne\tके भयो\nne\tमलाई पानी चाहिन्छ\n",
+        );
+        let plain = evaluate(&DetectorFactory::default().with_seed(Some(0)).build(), &samples);
+        let tiebreak = evaluate(&DetectorFactory::default().with_seed(Some(0)).with_devanagari_tiebreak(true).build(), &samples);
+        assert_eq!(plain.overall.correct, 3);
+        assert_eq!(tiebreak.overall.correct, 6);
+    }
+}
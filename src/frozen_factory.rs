@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use crate::detector::{DetectOptions, DetectorError};
+use crate::detector_factory::DetectorFactory;
+use crate::language::{Lang, Language};
+
+/// Read-only language profiles and settings for `detect_with_profiles`.
+///
+/// A frozen factory cannot be modified and holds no result cache, so detection only
+/// depends on its arguments. Clones share the profile data.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::detector_factory::DetectorFactory;
+/// use langdetect_rs::frozen_factory::FrozenFactory;
+/// use langdetect_rs::language::Lang;
+///
+/// let frozen = FrozenFactory::new(DetectorFactory::default().with_seed(Some(42)).build());
+/// assert!(frozen.lang_list().contains(&Lang::En));
+/// ```
+#[derive(Clone)]
+pub struct FrozenFactory {
+    factory: Arc<DetectorFactory>,
+}
+
+impl FrozenFactory {
+    /// Freezes a factory; its result cache is dropped.
+    ///
+    /// # Arguments
+    /// * `factory` - The configured factory.
+    pub fn new(mut factory: DetectorFactory) -> Self {
+        factory.cache = None;
+        FrozenFactory { factory: Arc::new(factory) }
+    }
+
+    /// Returns the codes of the loaded languages.
+    pub fn lang_list(&self) -> &[Lang] {
+        &self.factory.langlist
+    }
+
+    /// Returns the frozen factory, e.g. for its probability or batch methods.
+    pub fn factory(&self) -> &DetectorFactory {
+        &self.factory
+    }
+}
+
+impl From<DetectorFactory> for FrozenFactory {
+    fn from(factory: DetectorFactory) -> Self {
+        FrozenFactory::new(factory)
+    }
+}
+
+/// Detects the language of a text without any detector or factory state.
+///
+/// The result depends only on the arguments: with a seed set on the frozen factory, the same
+/// text and options always give the same result. Convenient for FFI and WASM bindings and
+/// unit tests, where creating and mutating `Detector` objects is awkward.
+///
+/// # Arguments
+/// * `text` - The text to analyze.
+/// * `profiles` - Language profiles and settings.
+/// * `options` - Per-call options, see `DetectOptions`.
+///
+/// # Returns
+/// The detected `Language` (code and probability), with a `None` code if no language is
+/// detected. A label set of the factory is applied, see `DetectorFactory::detect_lang_with_options`.
+///
+/// # Errors
+/// Returns `DetectorError::NoFeatures` if the text has no n-grams of the loaded languages.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::detector::DetectOptions;
+/// use langdetect_rs::detector_factory::DetectorFactory;
+/// use langdetect_rs::frozen_factory::{detect_with_profiles, FrozenFactory};
+///
+/// let frozen = FrozenFactory::new(DetectorFactory::default().with_seed(Some(42)).build());
+/// let language = detect_with_profiles("Bonjour tout le monde !", &frozen, &DetectOptions::new()).unwrap();
+/// assert_eq!(language.lang.as_deref(), Some("fr"));
+/// ```
+pub fn detect_with_profiles(text: &str, profiles: &FrozenFactory, options: &DetectOptions) -> Result<Language, DetectorError> {
+    profiles.factory.detect_lang_with_options(text, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::DetectionCache;
+    use crate::utils::lang_profile::LangProfile;
+
+    #[test]
+    fn test_detect_with_profiles() {
+        let mut factory = DetectorFactory::new()
+            .with_seed(Some(0))
+            .with_cache(Some(Arc::new(DetectionCache::new().build())))
+            .build();
+        for (name, words) in [("en", ["a", "a", "b"]), ("ja", ["\u{3042}", "\u{3044}", "\u{3044}"])] {
+            let mut profile = LangProfile::new().with_name(name).build();
+            words.iter().for_each(|word| profile.add(word));
+            factory.push_profile(profile).unwrap();
+        }
+        let frozen = FrozenFactory::from(factory);
+        assert!(frozen.factory().cache.is_none());
+        assert_eq!(frozen.lang_list(), ["en", "ja"]);
+
+        let options = DetectOptions::new();
+        let first = detect_with_profiles("a b a", &frozen, &options).unwrap();
+        assert_eq!(first.lang.as_deref(), Some("en"));
+        assert_eq!(detect_with_profiles("a b a", &frozen.clone(), &options).unwrap(), first);
+        assert_eq!(detect_with_profiles("\u{3044}", &frozen, &options).unwrap().lang.as_deref(), Some("ja"));
+        assert!(matches!(detect_with_profiles("123", &frozen, &options), Err(DetectorError::NoFeatures)));
+    }
+}
@@ -1,13 +1,291 @@
+use std::borrow::Borrow;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::str::FromStr;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Declares `Lang` with one variant per built-in language, plus the lookup tables.
+macro_rules! built_in_langs {
+    ($($variant:ident => $code:literal, $name:literal;)*) => {
+        /// Language identifier: one of the built-in languages or a custom profile's code.
+        ///
+        /// Prefer the variants over string codes, so typos like `"rus"` are caught by the
+        /// compiler. Compares, hashes and serializes as its code, so `Lang::Other("en")` equals
+        /// `Lang::En`, and a `Lang` can be compared with `&str` directly.
+        ///
+        /// Parsing with `str::parse` only accepts built-in codes; `Lang::from` accepts any code
+        /// and maps unknown ones to `Other`.
+        ///
+        /// # Examples
+        ///
+        /// ```rust
+        /// use langdetect_rs::language::Lang;
+        ///
+        /// assert_eq!(Lang::ZhCn.as_str(), "zh-cn");
+        /// assert_eq!("ru".parse::<Lang>().unwrap(), Lang::Ru);
+        /// assert!("rus".parse::<Lang>().is_err());
+        /// assert_eq!(Lang::from("kk"), Lang::Other("kk".to_string()));
+        /// assert_eq!(Lang::Fr, "fr");
+        /// ```
+        #[derive(Debug, Clone)]
+        pub enum Lang {
+            $(
+                #[doc = $name]
+                $variant,
+            )*
+            /// A language without a built-in profile, by its code.
+            Other(String),
+        }
+
+        impl Lang {
+            /// All built-in languages, in alphabetical order of their codes.
+            pub const BUILT_IN: &'static [Lang] = &[$(Lang::$variant),*];
+
+            /// Returns the language code, e.g. `"en"` or `"zh-tw"`.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Lang::$variant => $code,)*
+                    Lang::Other(code) => code,
+                }
+            }
+
+            /// Returns the English name of a built-in language, None for `Other`.
+            pub fn name(&self) -> Option<&'static str> {
+                match self {
+                    $(Lang::$variant => Some($name),)*
+                    Lang::Other(_) => None,
+                }
+            }
+
+            /// Returns the built-in language with a code, or None.
+            fn built_in(code: &str) -> Option<Lang> {
+                match code {
+                    $($code => Some(Lang::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+built_in_langs! {
+    Af => "af", "Afrikaans";
+    Ar => "ar", "Arabic";
+    Bg => "bg", "Bulgarian";
+    Bn => "bn", "Bengali";
+    Ca => "ca", "Catalan";
+    Cs => "cs", "Czech";
+    Cy => "cy", "Welsh";
+    Da => "da", "Danish";
+    De => "de", "German";
+    El => "el", "Greek";
+    En => "en", "English";
+    Es => "es", "Spanish";
+    Et => "et", "Estonian";
+    Fa => "fa", "Persian";
+    Fi => "fi", "Finnish";
+    Fr => "fr", "French";
+    Gu => "gu", "Gujarati";
+    He => "he", "Hebrew";
+    Hi => "hi", "Hindi";
+    Hr => "hr", "Croatian";
+    Hu => "hu", "Hungarian";
+    Id => "id", "Indonesian";
+    It => "it", "Italian";
+    Ja => "ja", "Japanese";
+    Kn => "kn", "Kannada";
+    Ko => "ko", "Korean";
+    Lt => "lt", "Lithuanian";
+    Lv => "lv", "Latvian";
+    Mk => "mk", "Macedonian";
+    Ml => "ml", "Malayalam";
+    Mr => "mr", "Marathi";
+    Ne => "ne", "Nepali";
+    Nl => "nl", "Dutch";
+    No => "no", "Norwegian";
+    Pa => "pa", "Punjabi";
+    Pl => "pl", "Polish";
+    Pt => "pt", "Portuguese";
+    Ro => "ro", "Romanian";
+    Ru => "ru", "Russian";
+    Sk => "sk", "Slovak";
+    Sl => "sl", "Slovenian";
+    So => "so", "Somali";
+    Sq => "sq", "Albanian";
+    Sv => "sv", "Swedish";
+    Sw => "sw", "Swahili";
+    Ta => "ta", "Tamil";
+    Te => "te", "Telugu";
+    Th => "th", "Thai";
+    Tl => "tl", "Tagalog";
+    Tr => "tr", "Turkish";
+    Uk => "uk", "Ukrainian";
+    Ur => "ur", "Urdu";
+    Vi => "vi", "Vietnamese";
+    ZhCn => "zh-cn", "Simplified Chinese";
+    ZhTw => "zh-tw", "Traditional Chinese";
+}
+
+impl Lang {
+    /// Returns true for the variants of built-in languages, also if written as `Other`.
+    pub fn is_built_in(&self) -> bool {
+        Lang::built_in(self.as_str()).is_some()
+    }
+}
+
+/// Error of parsing a code which is not one of the built-in languages.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownLangError(pub String);
+
+impl fmt::Display for UnknownLangError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown built-in language code: {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownLangError {}
+
+impl FromStr for Lang {
+    type Err = UnknownLangError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        Lang::built_in(code).ok_or_else(|| UnknownLangError(code.to_string()))
+    }
+}
+
+impl From<&str> for Lang {
+    fn from(code: &str) -> Self {
+        Lang::built_in(code).unwrap_or_else(|| Lang::Other(code.to_string()))
+    }
+}
+
+impl From<String> for Lang {
+    fn from(code: String) -> Self {
+        Lang::built_in(&code).unwrap_or(Lang::Other(code))
+    }
+}
+
+impl From<&String> for Lang {
+    fn from(code: &String) -> Self {
+        Lang::from(code.as_str())
+    }
+}
+
+impl From<Lang> for String {
+    fn from(lang: Lang) -> Self {
+        match lang {
+            Lang::Other(code) => code,
+            lang => lang.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Deref for Lang {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for Lang {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for Lang {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for Lang {
+    fn eq(&self, other: &Lang) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for Lang {}
+
+impl PartialEq<str> for Lang {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Lang {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Lang {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<Lang> for &str {
+    fn eq(&self, other: &Lang) -> bool {
+        *self == other.as_str()
+    }
+}
+
+impl PartialEq<Lang> for String {
+    fn eq(&self, other: &Lang) -> bool {
+        self == other.as_str()
+    }
+}
+
+impl Hash for Lang {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+
+impl PartialOrd for Lang {
+    fn partial_cmp(&self, other: &Lang) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Lang {
+    fn cmp(&self, other: &Lang) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl Serialize for Lang {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Lang {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Lang::from(String::deserialize(deserializer)?))
+    }
+}
+
 
 /// Represents a detected language with its probability score.
 ///
 /// This struct is returned by detection methods to provide both the
 /// language identifier and the confidence score for that detection.
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Serialized to JSON as `{"lang": "en", "prob": 0.99}`, with `"lang": null` for unknown.
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Language {
     /// The language identifier (ISO 639-1 code) or None for unknown.
-    pub lang: Option<String>,
+    pub lang: Option<Lang>,
     /// The probability score between 0.0 and 1.0.
     pub prob: f64,
 }
@@ -16,20 +294,107 @@ impl Language {
     /// Creates a new Language instance.
     ///
     /// # Arguments
-    /// * `lang` - Optional language code (e.g., "ru", "en").
+    /// * `lang` - Optional language (e.g. `Lang::Ru`, or `"xx".into()` for a custom profile).
     /// * `prob` - Probability score between 0.0 and 1.0.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use langdetect_rs::language::Language;
+    /// use langdetect_rs::language::{Lang, Language};
     ///
-    /// let english = Language::new(Some("en".to_string()), 0.95);
+    /// let english = Language::new(Some(Lang::En), 0.95);
     /// let unknown = Language::new(None, 0.0);
     /// ```
-    pub fn new(lang: Option<String>, prob: f64) -> Self {
+    pub fn new(lang: Option<Lang>, prob: f64) -> Self {
         Language { lang, prob }
     }
+
+    /// Serializes the language to JSON.
+    ///
+    /// # Returns
+    /// A JSON object `{"lang": <code or null>, "prob": <probability>}`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::language::{Lang, Language};
+    ///
+    /// let english = Language::new(Some(Lang::En), 0.5);
+    /// assert_eq!(english.to_json(), r#"{"lang":"en","prob":0.5}"#);
+    /// ```
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Serializes a ranked list of languages (e.g. from `get_probabilities()`) to JSON.
+    ///
+    /// # Arguments
+    /// * `languages` - Languages sorted by probability descending.
+    ///
+    /// # Returns
+    /// A JSON array of `{"rank": <1-based rank>, "lang": <code or null>, "prob": <probability>}` objects.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::language::{Lang, Language};
+    ///
+    /// let probs = vec![
+    ///     Language::new(Some(Lang::De), 0.75),
+    ///     Language::new(Some(Lang::Nl), 0.25),
+    /// ];
+    /// assert_eq!(
+    ///     Language::list_to_json(&probs),
+    ///     r#"[{"rank":1,"lang":"de","prob":0.75},{"rank":2,"lang":"nl","prob":0.25}]"#
+    /// );
+    /// ```
+    pub fn list_to_json(languages: &[Language]) -> String {
+        #[derive(Serialize)]
+        struct Ranked<'a> {
+            rank: usize,
+            #[serde(flatten)]
+            language: &'a Language,
+        }
+        let ranked: Vec<Ranked> = languages.iter().enumerate()
+            .map(|(i, language)| Ranked { rank: i + 1, language })
+            .collect();
+        serde_json::to_string(&ranked).unwrap_or_default()
+    }
+
+    /// Computes the Shannon entropy (in bits) of a detection distribution.
+    ///
+    /// Probabilities are renormalized to sum to 1 first, since detection results
+    /// omit languages below the reporting threshold. A value of 0.0 means the
+    /// detector is fully certain, higher values mean more uncertainty.
+    ///
+    /// # Arguments
+    /// * `probabilities` - Languages with their probabilities, e.g. from `get_probabilities()`.
+    ///
+    /// # Returns
+    /// The entropy in bits, or 0.0 for an empty distribution.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::language::{Lang, Language};
+    ///
+    /// let probs = vec![
+    ///     Language::new(Some(Lang::En), 0.5),
+    ///     Language::new(Some(Lang::Fr), 0.5),
+    /// ];
+    /// assert!((Language::entropy(&probs) - 1.0).abs() < 1e-9);
+    /// ```
+    pub fn entropy(probabilities: &[Language]) -> f64 {
+        let total: f64 = probabilities.iter().map(|l| l.prob).filter(|p| *p > 0.0).sum();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        probabilities.iter()
+            .map(|l| l.prob / total)
+            .filter(|p| *p > 0.0)
+            .map(|p| -p * p.log2())
+            .sum()
+    }
 }
 
 impl fmt::Display for Language {
@@ -58,19 +423,65 @@ mod tests {
         assert!((lang.prob - 0.0).abs() < 0.0001);
         assert_eq!(lang.to_string(), "");
 
-        let lang2 = Language::new(Some("en".to_string()), 1.0);
+        let lang2 = Language::new(Some(Lang::En), 1.0);
         assert_eq!(lang2.lang.as_deref(), Some("en"));
         assert!((lang2.prob - 1.0).abs() < 0.0001);
         assert_eq!(lang2.to_string(), "en:1.0");
     }
 
     #[test]
+    #[allow(clippy::neg_cmp_op_on_partial_ord)]
     fn test_cmp() {
-        let lang1 = Language::new(Some("a".to_string()), 0.1);
-        let lang2 = Language::new(Some("b".to_string()), 0.5);
+        let lang1 = Language::new(Some("a".into()), 0.1);
+        let lang2 = Language::new(Some("b".into()), 0.5);
 
         assert!(lang1 < lang2);
         assert!(lang1 != lang2);
         assert!(!(lang1 > lang1));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_entropy() {
+        assert_eq!(Language::entropy(&[]), 0.0);
+        let certain = vec![Language::new(Some(Lang::En), 0.99999)];
+        assert!(Language::entropy(&certain).abs() < 0.0001);
+        let uniform: Vec<Language> = ["a", "b", "c", "d"].iter()
+            .map(|l| Language::new(Some(Lang::from(*l)), 0.25))
+            .collect();
+        assert!((Language::entropy(&uniform) - 2.0).abs() < 0.0001);
+        // Not normalized input is renormalized
+        let partial = vec![
+            Language::new(Some("a".into()), 0.4),
+            Language::new(Some("b".into()), 0.4),
+        ];
+        assert!((Language::entropy(&partial) - 1.0).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_lang() {
+        assert_eq!(Lang::BUILT_IN.len(), 55);
+        for lang in Lang::BUILT_IN {
+            assert_eq!(lang.as_str().parse::<Lang>().as_ref(), Ok(lang));
+            assert!(lang.name().is_some());
+        }
+        assert_eq!(Lang::ZhTw.to_string(), "zh-tw");
+        assert_eq!("rus".parse::<Lang>(), Err(UnknownLangError("rus".to_string())));
+        let custom = Lang::from("kk".to_string());
+        assert_eq!(custom, Lang::Other("kk".to_string()));
+        assert!(!custom.is_built_in() && custom.name().is_none());
+        // Other variants of built-in codes are equal to the built-in variant
+        assert_eq!(Lang::Other("en".to_string()), Lang::En);
+        assert!(Lang::Other("en".to_string()).is_built_in());
+        let set: std::collections::HashSet<Lang> = [Lang::En].into_iter().collect();
+        assert!(set.contains("en"));
+        assert_eq!(serde_json::to_string(&Lang::ZhCn).unwrap(), r#""zh-cn""#);
+        assert_eq!(serde_json::from_str::<Lang>(r#""fr""#).unwrap(), Lang::Fr);
+        assert_eq!(String::from(custom), "kk");
+    }
+
+    #[test]
+    fn test_to_json() {
+        assert_eq!(Language::new(None, 0.0).to_json(), r#"{"lang":null,"prob":0.0}"#);
+        assert_eq!(Language::list_to_json(&[]), "[]");
+    }
+}
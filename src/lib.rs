@@ -25,6 +25,13 @@
 //! }
 //! ```
 //!
+//! For one-off calls the crate-level `detect` and `detect_langs` functions use a shared
+//! factory with the built-in profiles, like the module-level functions of the Python package:
+//!
+//! ```rust
+//! let lang = langdetect_rs::detect("Hello world! My name is Dima and I am a developer");
+//! ```
+//!
 //! ## Algorithm Overview
 //!
 //! The library uses a Bayesian approach with n-gram (1-3 character sequences) frequency analysis.
@@ -32,11 +39,106 @@
 //!
 //! ## Modules
 //!
+//! - [`cache`] - Thread-safe cache of detection results
+//! - [`corpus`] - Partitioning mixed-language corpora by detected language
 //! - [`detector_factory`] - Factory with languages profiles for creating detectors
 //! - [`detector`] - Core language detection logic
-//! - [`language`] - Language probability data structure
+//! - [`eval`] - Accuracy, latency and throughput evaluation on labeled datasets
+//! - [`frozen_factory`] - Immutable profiles and a stateless detection function
+//! - [`language`] - Language identifiers and the language probability data structure
+//! - [`profile_data`] - Column-oriented storage of per-language n-gram probabilities
+//! - `python` - Python extension module mirroring the `langdetect` package (`python` feature)
+//! - [`segmenter`] - Sentence and word splitting for per-segment detection
+//! - [`shared_factory`] - Thread-safe factory with a language set that can change at runtime
+//! - [`trainer`] - Building language profiles from text corpora
 //! - [`utils`] - Utility modules for profiles, n-grams, and Unicode handling
+//! - `wasm` - JavaScript API for browsers (`wasm` feature)
+pub mod cache;
+pub mod corpus;
 pub mod detector;
 pub mod detector_factory;
+pub mod eval;
+pub mod frozen_factory;
 pub mod language;
+pub mod profile_data;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod segmenter;
+pub mod shared_factory;
+pub mod trainer;
 pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+use crate::detector::DetectorError;
+use crate::detector_factory::DetectorFactory;
+use crate::language::Language;
+
+/// Detects the language of a text with the built-in profiles.
+///
+/// The profiles are loaded into a process-wide factory on the first call (see
+/// `DetectorFactory::default_shared`; with the `embedded-profiles` feature no filesystem
+/// access is needed), so this can be called from many threads at once. Like `detect()` of
+/// the Python package, results are not seeded; configure a `DetectorFactory` for seeds,
+/// language subsets or other settings.
+///
+/// # Arguments
+/// * `text` - The text to analyze.
+///
+/// # Returns
+/// The most probable language code, or "unknown" if no language is probable enough.
+///
+/// # Errors
+/// Returns `DetectorError::NoFeatures` if the text has no n-grams of the built-in languages.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(langdetect_rs::detect("Ich bin heute sehr müde und möchte schlafen.").unwrap(), "de");
+/// ```
+pub fn detect(text: &str) -> Result<String, DetectorError> {
+    DetectorFactory::default_shared().detect(text, None)
+}
+
+/// Computes the probable languages of a text with the built-in profiles.
+///
+/// Uses the same process-wide factory as `detect`.
+///
+/// # Arguments
+/// * `text` - The text to analyze.
+///
+/// # Returns
+/// The languages above the probability threshold, most probable first.
+///
+/// # Errors
+/// Returns `DetectorError::NoFeatures` if the text has no n-grams of the built-in languages.
+///
+/// # Examples
+///
+/// ```rust
+/// let languages = langdetect_rs::detect_langs("Bonjour tout le monde, comment allez-vous ?").unwrap();
+/// assert_eq!(languages[0].lang.as_deref(), Some("fr"));
+/// ```
+pub fn detect_langs(text: &str) -> Result<Vec<Language>, DetectorError> {
+    DetectorFactory::default_shared().get_probabilities(text, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global_detect() {
+        let texts = ["The weather is nice today and we go for a walk.", "Сегодня хорошая погода, и мы идём гулять."];
+        let handles: Vec<_> = (0..8).map(|i| {
+            let text = texts[i % 2];
+            std::thread::spawn(move || detect(text).unwrap())
+        }).collect();
+        let results: Vec<String> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+        for (i, lang) in results.iter().enumerate() {
+            assert_eq!(lang, ["en", "ru"][i % 2]);
+        }
+        assert_eq!(detect_langs(texts[1]).unwrap()[0].lang.as_deref(), Some("ru"));
+        assert!(matches!(detect("12345"), Err(DetectorError::NoFeatures)));
+    }
+}
@@ -0,0 +1,1266 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use rustc_hash::FxHashMap;
+
+use crate::language::Lang;
+use crate::utils::lang_profile::{LangProfileJsonError, ProfileLimits};
+use crate::utils::ngram::NGram;
+use crate::utils::script::{scripts_by_share, Script};
+
+/// Storage of n-gram probabilities in one contiguous matrix.
+///
+/// Every n-gram gets a numeric id, and its probabilities in all languages are the row
+/// with that id in a `ProfileMatrix` (one column per language). Detection reads the
+/// rows of a text's n-grams as slices of the matrix (see `row_slice`) instead of
+/// separately allocated vectors. Adding or removing a language rebuilds the matrix
+/// with one column more or less.
+///
+/// Alternatively the data can be a read-only table in the mapped format (see
+/// `from_mapped_bytes`), which is used as it is, without building any map. The first
+/// modification copies such a table into the regular matrix.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::profile_data::ProfileData;
+///
+/// let mut data = ProfileData::default();
+/// data.resize_languages(2);
+/// data.set("a", 0, 0.5);
+/// data.set("a", 1, 0.25);
+/// assert_eq!(data.probabilities("a"), Some(vec![0.5, 0.25]));
+/// assert_eq!(data.probabilities("b"), None);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProfileData {
+    /// Packed n-gram (see `NGram::pack`) to id mapping.
+    ngram_ids: FxHashMap<u64, usize>,
+    /// Id mapping of the n-grams too long to be packed.
+    long_ngram_ids: HashMap<String, usize>,
+    /// All n-grams concatenated in id order, so no string is allocated per n-gram.
+    ngram_text: String,
+    /// End offset of every n-gram in `ngram_text`.
+    ngram_ends: Vec<u32>,
+    /// Probabilities: one row per n-gram id, one column per language.
+    probs: ProfileMatrix,
+    /// Per-language scripts, derived on first use and discarded on every change.
+    scripts: OnceLock<Vec<Vec<Script>>>,
+    /// Per-language scripts declared by the profiles, overriding the derived ones.
+    declared_scripts: Vec<Option<Vec<Script>>>,
+    /// Read-only table used instead of the maps above until the first modification.
+    mapped: Option<Arc<MappedTable>>,
+}
+
+impl ProfileData {
+    /// Magic bytes at the start of the binary format.
+    pub const BINARY_MAGIC: &'static [u8; 4] = b"LDRS";
+    /// Current version of the binary format.
+    pub const BINARY_VERSION: u32 = 1;
+    /// Magic bytes at the start of the mapped format.
+    pub const MAPPED_MAGIC: &'static [u8; 4] = b"LDRM";
+    /// Current version of the mapped format.
+    pub const MAPPED_VERSION: u32 = 1;
+    /// Minimum share of a language's letter unigram probability a script needs to count as used.
+    pub const SCRIPT_MIN_SHARE: f64 = 0.1;
+
+    /// Builds column storage from per-n-gram probability rows.
+    ///
+    /// # Arguments
+    /// * `word_lang_prob_map` - N-gram to per-language probabilities mapping.
+    ///
+    /// # Returns
+    /// The equivalent profile data. The number of languages is the longest row length.
+    pub fn from_word_lang_prob_map(word_lang_prob_map: &HashMap<String, Vec<f64>>) -> Self {
+        let mut data = ProfileData::default();
+        let langsize = word_lang_prob_map.values().map(|probs| probs.len()).max().unwrap_or(0);
+        data.resize_languages(langsize);
+        for (word, probs) in word_lang_prob_map.iter() {
+            let id = data.intern(word);
+            data.probs.row_mut(id)[..probs.len()].copy_from_slice(probs);
+        }
+        data
+    }
+
+    /// Converts the matrix into per-n-gram probability rows.
+    pub fn to_word_lang_prob_map(&self) -> HashMap<String, Vec<f64>> {
+        self.rows().map(|(word, row)| (word.to_string(), row)).collect()
+    }
+
+    /// Number of language columns.
+    pub fn lang_count(&self) -> usize {
+        match &self.mapped {
+            Some(mapped) => mapped.langsize,
+            None => self.probs.width(),
+        }
+    }
+
+    /// Number of distinct n-grams known to any language.
+    pub fn ngram_count(&self) -> usize {
+        match &self.mapped {
+            Some(mapped) => mapped.ngram_count,
+            None => self.ngram_ends.len(),
+        }
+    }
+
+    /// Returns true if no n-gram is stored.
+    pub fn is_empty(&self) -> bool {
+        self.ngram_count() == 0
+    }
+
+    /// Returns true if the data is a read-only table in the mapped format, see `from_mapped_bytes`.
+    pub fn is_mapped(&self) -> bool {
+        self.mapped.is_some()
+    }
+
+    /// Removes all n-grams and languages.
+    pub fn clear(&mut self) {
+        self.ngram_ids.clear();
+        self.long_ngram_ids.clear();
+        self.ngram_text.clear();
+        self.ngram_ends.clear();
+        self.probs = ProfileMatrix::default();
+        self.scripts.take();
+        self.declared_scripts.clear();
+        self.mapped = None;
+    }
+
+    /// Grows (with empty columns) or shrinks the number of language columns.
+    ///
+    /// # Arguments
+    /// * `langsize` - The new number of languages.
+    pub fn resize_languages(&mut self, langsize: usize) {
+        self.thaw();
+        if langsize != self.probs.width() {
+            let width = self.probs.width();
+            let columns: Vec<Option<usize>> = (0..langsize).map(|index| (index < width).then_some(index)).collect();
+            self.remap_columns(&columns);
+        }
+        self.declared_scripts.truncate(langsize);
+        self.scripts.take();
+    }
+
+    /// Removes the column of a language, shifting the following columns down by one.
+    ///
+    /// # Arguments
+    /// * `index` - The column index of the language.
+    ///
+    /// # Returns
+    /// The removed probabilities by n-gram, or None if the index is out of range.
+    pub fn remove_language(&mut self, index: usize) -> Option<HashMap<String, f64>> {
+        self.thaw();
+        let column = self.column(index)?;
+        let removed = column.into_iter().map(|(id, p)| (self.ngram(id).unwrap_or_default().to_string(), p)).collect();
+        let columns: Vec<Option<usize>> = (0..self.probs.width()).filter(|&i| i != index).map(Some).collect();
+        self.remap_columns(&columns);
+        if index < self.declared_scripts.len() {
+            self.declared_scripts.remove(index);
+        }
+        self.scripts.take();
+        Some(removed)
+    }
+
+    /// Removes all probabilities of a language, keeping its (now empty) column.
+    ///
+    /// # Arguments
+    /// * `index` - The column index of the language. Out of range indices are ignored.
+    pub fn clear_language(&mut self, index: usize) {
+        self.thaw();
+        if index < self.probs.width() {
+            for id in 0..self.ngram_count() {
+                self.probs.row_mut(id)[index] = 0.0;
+            }
+            self.scripts.take();
+        }
+    }
+
+    /// Keeps the columns of the selected languages and forgets n-grams only the others knew.
+    ///
+    /// The matrix is rebuilt and the n-grams renumbered in a single pass, which is much
+    /// cheaper than removing languages one by one.
+    ///
+    /// # Arguments
+    /// * `keep` - Called with the column index of every language; false drops the column.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use langdetect_rs::profile_data::ProfileData;
+    ///
+    /// let mut data = ProfileData::default();
+    /// data.resize_languages(3);
+    /// data.set("a", 0, 0.5);
+    /// data.set("b", 1, 0.5);
+    /// data.set("c", 2, 0.5);
+    /// data.retain_languages(|index| index != 1);
+    /// assert_eq!(data.lang_count(), 2);
+    /// assert_eq!(data.probabilities("c"), Some(vec![0.0, 0.5]));
+    /// assert!(!data.contains("b"));
+    /// ```
+    pub fn retain_languages(&mut self, mut keep: impl FnMut(usize) -> bool) {
+        self.thaw();
+        let kept: Vec<bool> = (0..self.probs.width()).map(&mut keep).collect();
+        let columns: Vec<Option<usize>> = (0..kept.len()).filter(|&index| kept[index]).map(Some).collect();
+        self.remap_columns(&columns);
+        let mut flags = kept.iter().copied();
+        self.declared_scripts.retain(|_| flags.next().unwrap_or(false));
+        self.compact();
+    }
+
+    /// Sets the probability of an n-gram for a language.
+    ///
+    /// # Arguments
+    /// * `word` - The n-gram.
+    /// * `index` - The column index of the language. Out of range indices are ignored.
+    /// * `prob` - The probability; 0.0 removes the entry.
+    pub fn set(&mut self, word: &str, index: usize, prob: f64) {
+        self.thaw();
+        if index >= self.probs.width() {
+            return;
+        }
+        let id = self.intern(word);
+        self.scripts.take();
+        self.probs.row_mut(id)[index] = prob;
+    }
+
+    /// Returns the id of an n-gram, or None if no language knows it.
+    pub fn ngram_id(&self, word: &str) -> Option<usize> {
+        match &self.mapped {
+            Some(mapped) => mapped.ngram_id(word),
+            None => match NGram::pack(word) {
+                Some(key) => self.ngram_ids.get(&key).copied(),
+                None => self.long_ngram_ids.get(word).copied(),
+            },
+        }
+    }
+
+    /// Returns the id of an n-gram packed with `NGram::pack`, or None if no language knows it.
+    ///
+    /// This is the lookup of n-gram extraction, which needs no string for the n-gram.
+    pub fn ngram_id_packed(&self, key: u64) -> Option<usize> {
+        match &self.mapped {
+            Some(mapped) => {
+                let mut buffer = [0u8; 4 * NGram::N_GRAM];
+                let mut len = 0;
+                for ch in NGram::unpack(key) {
+                    len += ch.encode_utf8(&mut buffer[len..]).len();
+                }
+                mapped.ngram_id(std::str::from_utf8(&buffer[..len]).ok()?)
+            }
+            None => self.ngram_ids.get(&key).copied(),
+        }
+    }
+
+    /// Returns the n-gram with the given id.
+    pub fn ngram(&self, id: usize) -> Option<&str> {
+        match &self.mapped {
+            Some(mapped) => (id < mapped.ngram_count).then(|| mapped.ngram(id)),
+            None => {
+                let end = *self.ngram_ends.get(id)? as usize;
+                let start = id.checked_sub(1).map_or(0, |previous| self.ngram_ends[previous] as usize);
+                Some(&self.ngram_text[start..end])
+            }
+        }
+    }
+
+    /// Returns true if the n-gram is known.
+    pub fn contains(&self, word: &str) -> bool {
+        self.ngram_id(word).is_some()
+    }
+
+    /// Returns the probability of an n-gram (by id) for a language, 0.0 if absent.
+    pub fn probability(&self, id: usize, index: usize) -> f64 {
+        match &self.mapped {
+            Some(mapped) => mapped.probability(id, index),
+            None => self.row_slice(id).and_then(|row| row.get(index)).copied().unwrap_or(0.0),
+        }
+    }
+
+    /// Returns the probabilities of an n-gram (by id) for every language.
+    pub fn row(&self, id: usize) -> Vec<f64> {
+        match &self.mapped {
+            Some(mapped) => (0..mapped.langsize).map(|index| mapped.probability(id, index)).collect(),
+            None => self.row_slice(id).map_or_else(|| vec![0.0; self.probs.width()], <[f64]>::to_vec),
+        }
+    }
+
+    /// Returns the probabilities of an n-gram (by id) for every language as a row of the matrix.
+    ///
+    /// # Returns
+    /// The row, or None for unknown ids and for a table in the mapped format, whose
+    /// probabilities are only read through `row` or `probability`.
+    pub fn row_slice(&self, id: usize) -> Option<&[f64]> {
+        (self.mapped.is_none() && id < self.ngram_ends.len()).then(|| self.probs.row(id))
+    }
+
+    /// Returns the probabilities of an n-gram for every language, or None if it is unknown.
+    pub fn probabilities(&self, word: &str) -> Option<Vec<f64>> {
+        self.ngram_id(word).map(|id| self.row(id))
+    }
+
+    /// Appends the probabilities of an n-gram (by id) as a new row of a matrix.
+    ///
+    /// # Arguments
+    /// * `id` - The n-gram id.
+    /// * `columns` - Column indices of the languages to copy, None for all languages.
+    /// * `matrix` - The matrix, as wide as `columns` (or `lang_count()`).
+    ///
+    /// # Returns
+    /// The index of the new row.
+    pub fn push_row(&self, id: usize, columns: Option<&[usize]>, matrix: &mut ProfileMatrix) -> usize {
+        match columns {
+            Some(columns) => matrix.push_row(columns.iter().map(|&index| self.probability(id, index))),
+            None => matrix.push_row((0..self.lang_count()).map(|index| self.probability(id, index))),
+        }
+    }
+
+    /// Iterates over all known n-grams with their per-language probabilities.
+    pub fn rows(&self) -> impl Iterator<Item = (&str, Vec<f64>)> + '_ {
+        (0..self.ngram_count()).filter_map(|id| Some((self.ngram(id)?, self.row(id))))
+    }
+
+    /// Returns the column of a language: (n-gram id, probability) pairs of the non-zero
+    /// probabilities, in ascending order of id.
+    pub fn column(&self, index: usize) -> Option<Vec<(usize, f64)>> {
+        match &self.mapped {
+            Some(mapped) => (index < mapped.langsize).then(|| {
+                (0..mapped.ngram_count).map(|id| (id, mapped.probability(id, index))).filter(|(_, p)| *p != 0.0).collect()
+            }),
+            None => (index < self.probs.width()).then(|| {
+                (0..self.ngram_count()).map(|id| (id, self.probs.row(id)[index])).filter(|(_, p)| *p != 0.0).collect()
+            }),
+        }
+    }
+
+    /// Returns the scripts a language is written in.
+    ///
+    /// Scripts declared with `set_language_scripts` take precedence. Otherwise they are
+    /// derived from the letter unigrams of the language: a script counts if its letters hold
+    /// at least `SCRIPT_MIN_SHARE` of their probability, so stray foreign letters (e.g. Latin
+    /// in Russian profiles) are ignored. Computed for all languages on first use.
+    ///
+    /// # Arguments
+    /// * `index` - The column index of the language.
+    ///
+    /// # Returns
+    /// The scripts in declaration order of `Script`, empty for unknown indices.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::profile_data::ProfileData;
+    /// use langdetect_rs::utils::script::Script;
+    ///
+    /// let mut data = ProfileData::default();
+    /// data.resize_languages(1);
+    /// data.set("ж", 0, 0.6);
+    /// data.set("я", 0, 0.35);
+    /// data.set("x", 0, 0.05);
+    /// assert_eq!(data.language_scripts(0), &[Script::Cyrillic]);
+    /// ```
+    pub fn language_scripts(&self, index: usize) -> &[Script] {
+        let scripts = self.scripts.get_or_init(|| (0..self.lang_count()).map(|index| self.derive_scripts(index)).collect());
+        scripts.get(index).map_or(&[], |scripts| scripts.as_slice())
+    }
+
+    /// Declares the scripts of a language, overriding the derived ones.
+    ///
+    /// # Arguments
+    /// * `index` - The column index of the language. Out of range indices are ignored.
+    /// * `scripts` - The scripts, or None to derive them from the probabilities again.
+    pub fn set_language_scripts(&mut self, index: usize, scripts: Option<Vec<Script>>) {
+        if index >= self.lang_count() {
+            return;
+        }
+        if self.declared_scripts.len() <= index {
+            self.declared_scripts.resize(index + 1, None);
+        }
+        self.declared_scripts[index] = scripts;
+        self.scripts.take();
+    }
+
+    /// Returns the scripts declared with `set_language_scripts`, None if they are derived.
+    pub fn declared_language_scripts(&self, index: usize) -> Option<&[Script]> {
+        self.declared_scripts.get(index).and_then(|scripts| scripts.as_deref())
+    }
+
+    /// Computes the scripts of a language column, see `language_scripts`.
+    fn derive_scripts(&self, index: usize) -> Vec<Script> {
+        if let Some(Some(declared)) = self.declared_scripts.get(index) {
+            return declared.clone();
+        }
+        let unigrams = self.column(index).unwrap_or_default().into_iter().filter_map(|(id, p)| {
+            let mut chars = self.ngram(id)?.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Some((ch, p)),
+                _ => None,
+            }
+        });
+        scripts_by_share(unigrams, Self::SCRIPT_MIN_SHARE)
+    }
+
+    /// Estimates the heap memory used by the column of a language, in bytes.
+    ///
+    /// A table in the mapped format is not counted.
+    pub fn language_memory(&self, index: usize) -> usize {
+        match self.mapped {
+            Some(_) => 0,
+            None if index < self.probs.width() => self.ngram_count() * std::mem::size_of::<f64>(),
+            None => 0,
+        }
+    }
+
+    /// Estimates the total heap memory used by the storage, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        let ngrams: usize = match self.mapped {
+            Some(_) => 0,
+            None => (0..self.ngram_count()).filter_map(|id| self.ngram(id)).map(ngram_memory).sum(),
+        };
+        let columns: usize = (0..self.lang_count()).map(|index| self.language_memory(index)).sum();
+        ngrams + columns
+    }
+
+    /// Drops the lowest probabilities across all languages until `memory_usage()` fits a budget.
+    ///
+    /// Entries are removed in ascending order of probability, regardless of the language,
+    /// so rare n-grams go first. Memory is only released by n-grams left without any
+    /// language, whose rows are dropped when the storage is compacted (n-gram ids change).
+    ///
+    /// # Arguments
+    /// * `budget` - Maximum estimated heap memory, in bytes.
+    ///
+    /// # Returns
+    /// The number of removed (n-gram, language) entries.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::profile_data::ProfileData;
+    ///
+    /// let mut data = ProfileData::default();
+    /// data.resize_languages(1);
+    /// for (i, word) in ["a", "b", "c", "d"].iter().enumerate() {
+    ///     data.set(word, 0, (i + 1) as f64 / 10.0);
+    /// }
+    /// let budget = data.memory_usage() / 2;
+    /// assert!(data.shrink_to_budget(budget) > 0);
+    /// assert!(data.memory_usage() <= budget);
+    /// // The most probable n-gram is kept
+    /// assert!(data.contains("d"));
+    /// ```
+    pub fn shrink_to_budget(&mut self, budget: usize) -> usize {
+        self.thaw();
+        let row_cost = self.probs.width() * std::mem::size_of::<f64>();
+        let mut removed = 0;
+        let mut usage = self.memory_usage();
+        if usage > budget {
+            // N-grams no language knows anymore go first
+            self.compact();
+            usage = self.memory_usage();
+        }
+        while usage > budget {
+            let probs = &self.probs;
+            let mut entries: Vec<(f64, usize, usize)> = (0..self.ngram_ends.len())
+                .flat_map(|id| probs.row(id).iter().enumerate().filter(|(_, p)| **p != 0.0).map(move |(index, &p)| (p, index, id)))
+                .collect();
+            if entries.is_empty() {
+                break;
+            }
+            entries.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut languages = vec![0usize; self.ngram_count()];
+            for &(_, _, id) in entries.iter() {
+                languages[id] += 1;
+            }
+            // Remove at least the excess, counting the estimated cost of every n-gram
+            // losing its last language
+            let excess = usage - budget;
+            let mut freed = 0;
+            for (_, index, id) in entries {
+                if freed >= excess {
+                    break;
+                }
+                self.probs.row_mut(id)[index] = 0.0;
+                languages[id] -= 1;
+                if languages[id] == 0 {
+                    freed += row_cost + self.ngram(id).map_or(0, ngram_memory);
+                }
+                removed += 1;
+            }
+            self.compact();
+            usage = self.memory_usage();
+        }
+        removed
+    }
+
+    /// Serializes the languages and their probabilities into the compact binary format.
+    ///
+    /// Layout (little endian): magic `LDRS`, format version (u32), number of languages (u32)
+    /// followed by each language code, number of n-grams (u32) followed by each n-gram, and
+    /// for every language column the number of entries (u32) followed by (n-gram id u32,
+    /// probability f64) pairs. Strings are stored as a length (u32) and UTF-8 bytes.
+    /// Declared scripts are not stored; they are derived again after loading.
+    ///
+    /// # Arguments
+    /// * `langlist` - Language identifiers in column order.
+    ///
+    /// # Returns
+    /// The encoded bytes.
+    pub fn to_bytes(&self, langlist: &[Lang]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(Self::BINARY_MAGIC);
+        bytes.extend_from_slice(&Self::BINARY_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(langlist.len() as u32).to_le_bytes());
+        for lang in langlist {
+            write_str(&mut bytes, lang);
+        }
+        bytes.extend_from_slice(&(self.ngram_count() as u32).to_le_bytes());
+        for id in 0..self.ngram_count() {
+            write_str(&mut bytes, self.ngram(id).unwrap_or_default());
+        }
+        for index in 0..langlist.len() {
+            let mut entries = self.column(index).unwrap_or_default();
+            entries.sort_by_key(|(id, _)| *id);
+            bytes.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+            for (id, p) in entries {
+                bytes.extend_from_slice(&(id as u32).to_le_bytes());
+                bytes.extend_from_slice(&p.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Deserializes languages and probabilities written by `to_bytes`, within the default `ProfileLimits`.
+    ///
+    /// # Arguments
+    /// * `bytes` - The encoded bytes.
+    ///
+    /// # Returns
+    /// The language identifiers in column order and the profile data.
+    ///
+    /// # Errors
+    /// Returns a description of the problem if the data is truncated, not in the expected
+    /// format or exceeds the limits.
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Vec<Lang>, ProfileData), String> {
+        Self::from_bytes_with_limits(bytes, &ProfileLimits::default()).map_err(|e| e.to_string())
+    }
+
+    /// Deserializes languages and probabilities written by `to_bytes`, rejecting data which
+    /// exceeds the given limits.
+    ///
+    /// The data must fit into `max_model_size`, and so must the probability matrix of all
+    /// languages, which is checked before it is allocated. Every language may have at most
+    /// `max_entries` n-grams of at most `max_ngram_length` bytes.
+    ///
+    /// # Arguments
+    /// * `bytes` - The encoded bytes.
+    /// * `limits` - The size limits to enforce.
+    ///
+    /// # Returns
+    /// The language identifiers in column order and the profile data.
+    ///
+    /// # Errors
+    /// Returns `LangProfileJsonError::ParseError` if the data is truncated or not in the
+    /// expected format and `LangProfileJsonError::LimitExceeded` if it exceeds the limits.
+    pub fn from_bytes_with_limits(bytes: &[u8], limits: &ProfileLimits) -> Result<(Vec<Lang>, ProfileData), LangProfileJsonError> {
+        limits.check_model_size(bytes.len())?;
+        let mut reader = ByteReader { bytes, pos: 0 };
+        if reader.take(4)? != Self::BINARY_MAGIC {
+            return Err(invalid("Not a langdetect binary profile".to_string()));
+        }
+        let version = reader.read_u32()?;
+        if version != Self::BINARY_VERSION {
+            return Err(invalid(format!("Unsupported binary profile version: {}", version)));
+        }
+        // Every count is checked against the remaining bytes before anything is allocated for it
+        let langsize = reader.read_count(4)?;
+        let mut langlist = Vec::new();
+        for _ in 0..langsize {
+            langlist.push(Lang::from(reader.read_str()?));
+        }
+        let mut data = ProfileData::default();
+        data.resize_languages(langsize);
+        let ngram_count = reader.read_count(4)?;
+        limits.check_model_size(matrix_size(langsize, ngram_count))?;
+        for _ in 0..ngram_count {
+            let word = reader.read_str()?;
+            limits.check_ngram_length(&word)?;
+            if data.contains(&word) {
+                return Err(invalid(format!("Duplicated n-gram: {:?}", word)));
+            }
+            data.intern(&word);
+        }
+        for index in 0..langsize {
+            let entries = reader.read_count(12)?;
+            limits.check_entries(entries)?;
+            for _ in 0..entries {
+                let id = reader.read_u32()? as usize;
+                if id >= data.ngram_count() {
+                    return Err(invalid(format!("Invalid n-gram id: {}", id)));
+                }
+                let p = f64::from_le_bytes(reader.take(8)?.try_into().unwrap());
+                data.probs.row_mut(id)[index] = p;
+            }
+        }
+        Ok((langlist, data))
+    }
+
+    /// Serializes the languages and their probabilities into the mapped format.
+    ///
+    /// Unlike the binary format of `to_bytes`, the mapped format can be used without decoding
+    /// (see `from_mapped_bytes`), at the cost of a dense probability matrix. Layout (little
+    /// endian): magic `LDRM`, format version (u32), number of languages (u32), number of
+    /// n-grams (u32), each language code as a length (u32) and UTF-8 bytes, the byte offsets
+    /// (u32) of the n-grams in the string table plus its total length, the string table with
+    /// the n-grams in ascending byte order, zero padding to a multiple of 8 bytes and the
+    /// probability matrix (f64) with one row of all languages per n-gram.
+    /// Declared scripts are not stored; they are derived again after loading.
+    ///
+    /// # Arguments
+    /// * `langlist` - Language identifiers in column order.
+    ///
+    /// # Returns
+    /// The encoded bytes.
+    pub fn to_mapped_bytes(&self, langlist: &[Lang]) -> Vec<u8> {
+        let mut ids: Vec<usize> = (0..self.ngram_count()).collect();
+        ids.sort_by(|&a, &b| self.ngram(a).cmp(&self.ngram(b)));
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(Self::MAPPED_MAGIC);
+        bytes.extend_from_slice(&Self::MAPPED_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(langlist.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(ids.len() as u32).to_le_bytes());
+        for lang in langlist {
+            write_str(&mut bytes, lang);
+        }
+        let mut offset = 0;
+        for &id in ids.iter() {
+            bytes.extend_from_slice(&(offset as u32).to_le_bytes());
+            offset += self.ngram(id).unwrap_or_default().len();
+        }
+        bytes.extend_from_slice(&(offset as u32).to_le_bytes());
+        for &id in ids.iter() {
+            bytes.extend_from_slice(self.ngram(id).unwrap_or_default().as_bytes());
+        }
+        bytes.resize(bytes.len().next_multiple_of(8), 0);
+        for &id in ids.iter() {
+            for index in 0..langlist.len() {
+                bytes.extend_from_slice(&self.probability(id, index).to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    /// Uses languages and probabilities written by `to_mapped_bytes` without decoding them.
+    ///
+    /// Only the header and the n-gram table are checked. Lookups read the n-grams and
+    /// probabilities directly from `bytes`, e.g. a memory-mapped file: no per n-gram memory
+    /// is allocated and pages the detection never touches are never loaded.
+    ///
+    /// # Arguments
+    /// * `bytes` - The encoded bytes, kept for the lifetime of the data.
+    ///
+    /// # Returns
+    /// The language identifiers in column order and the profile data.
+    ///
+    /// # Errors
+    /// Returns a description of the problem if the data is truncated, not in the expected
+    /// format or exceeds the default `ProfileLimits`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::language::Lang;
+    /// use langdetect_rs::profile_data::ProfileData;
+    ///
+    /// let mut data = ProfileData::default();
+    /// data.resize_languages(2);
+    /// data.set("a", 0, 0.5);
+    /// data.set("b", 1, 0.25);
+    /// let bytes = data.to_mapped_bytes(&[Lang::En, Lang::De]);
+    ///
+    /// let (langlist, mapped) = ProfileData::from_mapped_bytes(bytes).unwrap();
+    /// assert_eq!(langlist, vec![Lang::En, Lang::De]);
+    /// assert!(mapped.is_mapped());
+    /// assert_eq!(mapped.probabilities("b"), Some(vec![0.0, 0.25]));
+    /// ```
+    pub fn from_mapped_bytes<B: AsRef<[u8]> + Send + Sync + 'static>(bytes: B) -> Result<(Vec<Lang>, ProfileData), String> {
+        Self::from_mapped_bytes_with_limits(bytes, &ProfileLimits::default()).map_err(|e| e.to_string())
+    }
+
+    /// Uses languages and probabilities written by `to_mapped_bytes`, rejecting data which
+    /// exceeds the given limits.
+    ///
+    /// The data, matrix included, must fit into `max_model_size` and every n-gram into
+    /// `max_ngram_length`. `max_entries` is not checked, as that would read the whole matrix.
+    ///
+    /// # Arguments
+    /// * `bytes` - The encoded bytes, kept for the lifetime of the data.
+    /// * `limits` - The size limits to enforce.
+    ///
+    /// # Returns
+    /// The language identifiers in column order and the profile data.
+    ///
+    /// # Errors
+    /// Returns `LangProfileJsonError::ParseError` if the data is truncated or not in the
+    /// expected format and `LangProfileJsonError::LimitExceeded` if it exceeds the limits.
+    pub fn from_mapped_bytes_with_limits<B: AsRef<[u8]> + Send + Sync + 'static>(bytes: B, limits: &ProfileLimits) -> Result<(Vec<Lang>, ProfileData), LangProfileJsonError> {
+        let (langlist, table) = MappedTable::new(Box::new(bytes), limits)?;
+        let data = ProfileData { mapped: Some(Arc::new(table)), ..ProfileData::default() };
+        Ok((langlist, data))
+    }
+
+    /// Copies a table in the mapped format into the regular matrix, keeping the n-gram ids.
+    fn thaw(&mut self) {
+        let Some(mapped) = self.mapped.take() else {
+            return;
+        };
+        self.scripts.take();
+        self.probs = ProfileMatrix::new(mapped.langsize);
+        for id in 0..mapped.ngram_count {
+            let id = self.push_ngram(mapped.ngram(id));
+            for (index, p) in self.probs.row_mut(id).iter_mut().enumerate() {
+                *p = mapped.probability(id, index);
+            }
+        }
+    }
+
+    /// Rebuilds the matrix with the given old column, or zeros for None, at every position.
+    fn remap_columns(&mut self, columns: &[Option<usize>]) {
+        let mut probs = ProfileMatrix::new(columns.len());
+        probs.values.reserve(columns.len() * self.ngram_ends.len());
+        for id in 0..self.ngram_ends.len() {
+            let row = self.probs.row(id);
+            probs.push_row(columns.iter().map(|column| column.map_or(0.0, |index| row[index])));
+        }
+        self.probs = probs;
+    }
+
+    /// Forgets n-grams no language knows anymore, renumbers the others and releases unused capacity.
+    fn compact(&mut self) {
+        self.scripts.take();
+        let used: Vec<bool> = (0..self.ngram_ends.len()).map(|id| self.probs.row(id).iter().any(|p| *p != 0.0)).collect();
+        let count = used.iter().filter(|u| **u).count();
+        let text = std::mem::take(&mut self.ngram_text);
+        let ends = std::mem::take(&mut self.ngram_ends);
+        self.ngram_text = String::with_capacity(text.len());
+        self.ngram_ends = Vec::with_capacity(count);
+        self.ngram_ids = FxHashMap::with_capacity_and_hasher(count, Default::default());
+        self.long_ngram_ids = HashMap::new();
+        let width = self.probs.width();
+        let probs = std::mem::replace(&mut self.probs, ProfileMatrix::new(width));
+        self.probs.values.reserve_exact(count * probs.width());
+        let mut start = 0;
+        for (id, &end) in ends.iter().enumerate() {
+            if used[id] {
+                let new_id = self.push_ngram(&text[start..end as usize]);
+                self.probs.row_mut(new_id).copy_from_slice(probs.row(id));
+            }
+            start = end as usize;
+        }
+        self.ngram_text.shrink_to_fit();
+    }
+
+    /// Returns the id of an n-gram, registering it first if needed.
+    fn intern(&mut self, word: &str) -> usize {
+        match self.ngram_id(word) {
+            Some(id) => id,
+            None => self.push_ngram(word),
+        }
+    }
+
+    /// Registers a new n-gram with a row of zeros and returns its id.
+    fn push_ngram(&mut self, word: &str) -> usize {
+        let id = self.ngram_ends.len();
+        self.probs.push_row(std::iter::empty());
+        self.ngram_text.push_str(word);
+        self.ngram_ends.push(self.ngram_text.len() as u32);
+        match NGram::pack(word) {
+            Some(key) => self.ngram_ids.insert(key, id),
+            None => self.long_ngram_ids.insert(word.to_string(), id),
+        };
+        id
+    }
+}
+
+/// Estimates the heap memory of an n-gram: its text, end offset and id mapping entry.
+fn ngram_memory(word: &str) -> usize {
+    let key = match NGram::pack(word) {
+        Some(_) => std::mem::size_of::<u64>(),
+        None => word.len() + std::mem::size_of::<String>(),
+    };
+    word.len() + std::mem::size_of::<u32>() + key + std::mem::size_of::<usize>() + 1
+}
+
+/// Appends a length-prefixed UTF-8 string.
+fn write_str(bytes: &mut Vec<u8>, value: &str) {
+    bytes.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(value.as_bytes());
+}
+
+/// Probability rows of several n-grams stored in one contiguous buffer.
+///
+/// `ProfileData` keeps all its probabilities in one matrix, so every EM update of the
+/// detection multiplies by a slice of it instead of a separately allocated vector.
+/// Detection restricted to some languages gathers the needed columns of the rows of a text
+/// into a smaller matrix once (see `ProfileData::push_row`) and then reads them many times.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::profile_data::{ProfileData, ProfileMatrix};
+///
+/// let mut data = ProfileData::default();
+/// data.resize_languages(2);
+/// data.set("a", 0, 0.5);
+/// data.set("b", 1, 0.25);
+///
+/// let mut matrix = ProfileMatrix::new(data.lang_count());
+/// let row = data.push_row(data.ngram_id("b").unwrap(), None, &mut matrix);
+/// assert_eq!(matrix.row(row), &[0.0, 0.25]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProfileMatrix {
+    /// Number of languages per row.
+    width: usize,
+    /// Rows one after another.
+    values: Vec<f64>,
+}
+
+impl ProfileMatrix {
+    /// Creates an empty matrix.
+    ///
+    /// # Arguments
+    /// * `width` - Number of languages per row.
+    pub fn new(width: usize) -> Self {
+        ProfileMatrix { width, values: Vec::new() }
+    }
+
+    /// Number of languages per row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.values.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    /// Returns true if the matrix has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Appends a row, truncated or padded with zeros to the width of the matrix.
+    ///
+    /// # Returns
+    /// The index of the new row.
+    pub fn push_row(&mut self, row: impl IntoIterator<Item = f64>) -> usize {
+        let index = self.len();
+        self.values.extend(row.into_iter().take(self.width));
+        self.values.resize((index + 1) * self.width, 0.0);
+        index
+    }
+
+    /// Removes the last row.
+    pub fn pop_row(&mut self) {
+        self.values.truncate(self.values.len().saturating_sub(self.width));
+    }
+
+    /// Returns a row.
+    ///
+    /// # Panics
+    /// Panics if the row does not exist.
+    pub fn row(&self, index: usize) -> &[f64] {
+        &self.values[index * self.width..(index + 1) * self.width]
+    }
+
+    /// Returns a row for modification.
+    ///
+    /// # Panics
+    /// Panics if the row does not exist.
+    pub fn row_mut(&mut self, index: usize) -> &mut [f64] {
+        &mut self.values[index * self.width..(index + 1) * self.width]
+    }
+}
+
+/// Read-only n-gram table and probability matrix in the mapped format of `ProfileData::to_mapped_bytes`.
+struct MappedTable {
+    /// The encoded bytes.
+    bytes: Box<dyn AsRef<[u8]> + Send + Sync>,
+    /// Number of languages.
+    langsize: usize,
+    /// Number of n-grams.
+    ngram_count: usize,
+    /// Position of the n-gram offsets.
+    offsets: usize,
+    /// Position of the string table.
+    strings: usize,
+    /// Position of the probability matrix.
+    matrix: usize,
+}
+
+impl MappedTable {
+    /// Checks the header and the n-gram table of the encoded bytes.
+    fn new(bytes: Box<dyn AsRef<[u8]> + Send + Sync>, limits: &ProfileLimits) -> Result<(Vec<Lang>, MappedTable), LangProfileJsonError> {
+        let data = (*bytes).as_ref();
+        limits.check_model_size(data.len())?;
+        let mut reader = ByteReader { bytes: data, pos: 0 };
+        if reader.take(4)? != ProfileData::MAPPED_MAGIC {
+            return Err(invalid("Not a langdetect mapped profile".to_string()));
+        }
+        let version = reader.read_u32()?;
+        if version != ProfileData::MAPPED_VERSION {
+            return Err(invalid(format!("Unsupported mapped profile version: {}", version)));
+        }
+        let langsize = reader.read_count(4)?;
+        let ngram_count = reader.read_count(4)?;
+        let mut langlist = Vec::with_capacity(langsize);
+        for _ in 0..langsize {
+            langlist.push(Lang::from(reader.read_str()?));
+        }
+        let offsets = reader.pos;
+        reader.take(4 * ngram_count + 4)?;
+        let strings = reader.pos;
+        let mut table = MappedTable { bytes: Box::new(Vec::new()), langsize, ngram_count, offsets, strings, matrix: 0 };
+        let strings_len = table.offset(data, ngram_count);
+        reader.take(strings_len)?;
+        table.matrix = reader.pos.next_multiple_of(8);
+        if table.matrix.checked_add(matrix_size(langsize, ngram_count)).is_none_or(|end| end > data.len()) {
+            return Err(invalid("Unexpected end of mapped profile".to_string()));
+        }
+        let mut previous: Option<&str> = None;
+        for id in 0..ngram_count {
+            let (start, end) = (table.offset(data, id), table.offset(data, id + 1));
+            if start > end || end > strings_len {
+                return Err(invalid(format!("Invalid n-gram offset: {}", start)));
+            }
+            let word = std::str::from_utf8(&data[strings + start..strings + end])
+                .map_err(|e| invalid(format!("Invalid UTF-8 in mapped profile: {}", e)))?;
+            limits.check_ngram_length(word)?;
+            if previous.is_some_and(|previous| previous >= word) {
+                return Err(invalid(format!("N-grams are not sorted: {:?}", word)));
+            }
+            previous = Some(word);
+        }
+        table.bytes = bytes;
+        Ok((langlist, table))
+    }
+
+    /// Reads the offset of an n-gram in the string table.
+    fn offset(&self, data: &[u8], id: usize) -> usize {
+        let pos = self.offsets + 4 * id;
+        u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap()) as usize
+    }
+
+    /// Returns the n-gram with the given (checked) id.
+    fn ngram(&self, id: usize) -> &str {
+        let data = (*self.bytes).as_ref();
+        let (start, end) = (self.offset(data, id), self.offset(data, id + 1));
+        std::str::from_utf8(&data[self.strings + start..self.strings + end]).unwrap_or_default()
+    }
+
+    /// Finds the id of an n-gram by binary search.
+    fn ngram_id(&self, word: &str) -> Option<usize> {
+        let (mut low, mut high) = (0, self.ngram_count);
+        while low < high {
+            let mid = (low + high) / 2;
+            match self.ngram(mid).cmp(word) {
+                std::cmp::Ordering::Less => low = mid + 1,
+                std::cmp::Ordering::Greater => high = mid,
+                std::cmp::Ordering::Equal => return Some(mid),
+            }
+        }
+        None
+    }
+
+    /// Reads the probability of an n-gram for a language, 0.0 for unknown ids or indices.
+    fn probability(&self, id: usize, index: usize) -> f64 {
+        if id >= self.ngram_count || index >= self.langsize {
+            return 0.0;
+        }
+        let pos = self.matrix + 8 * (id * self.langsize + index);
+        f64::from_le_bytes((*self.bytes).as_ref()[pos..pos + 8].try_into().unwrap())
+    }
+}
+
+impl std::fmt::Debug for MappedTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedTable")
+            .field("langsize", &self.langsize)
+            .field("ngram_count", &self.ngram_count)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Cursor over encoded bytes.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], LangProfileJsonError> {
+        let end = self.pos.checked_add(n).filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| invalid("Unexpected end of binary profile".to_string()))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, LangProfileJsonError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    /// Reads a count of items taking at least `item_size` bytes each, rejecting counts
+    /// the remaining bytes cannot hold.
+    fn read_count(&mut self, item_size: usize) -> Result<usize, LangProfileJsonError> {
+        let count = self.read_u32()? as usize;
+        if count > self.remaining() / item_size {
+            return Err(invalid(format!("Count {} exceeds the remaining {} bytes", count, self.remaining())));
+        }
+        Ok(count)
+    }
+
+    fn read_str(&mut self) -> Result<String, LangProfileJsonError> {
+        let len = self.read_u32()? as usize;
+        String::from_utf8(self.take(len)?.to_vec()).map_err(|e| invalid(format!("Invalid UTF-8 in binary profile: {}", e)))
+    }
+}
+
+/// Error for encoded bytes which are not in the expected format.
+fn invalid(message: String) -> LangProfileJsonError {
+    LangProfileJsonError::ParseError(message)
+}
+
+/// Returns the size of a probability matrix in bytes, saturating at `usize::MAX`.
+fn matrix_size(langsize: usize, ngram_count: usize) -> usize {
+    langsize.saturating_mul(ngram_count).saturating_mul(std::mem::size_of::<f64>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut map = HashMap::new();
+        map.insert("a".to_string(), vec![0.5, 0.0, 0.1]);
+        map.insert("bc".to_string(), vec![0.0, 0.3, 0.0]);
+        let data = ProfileData::from_word_lang_prob_map(&map);
+        assert_eq!(data.lang_count(), 3);
+        assert_eq!(data.ngram_count(), 2);
+        assert_eq!(data.column(0).unwrap(), vec![(data.ngram_id("a").unwrap(), 0.5)]);
+        assert_eq!(data.to_word_lang_prob_map(), map);
+        let id = data.ngram_id("bc").unwrap();
+        assert_eq!(data.ngram_id_packed(NGram::pack("bc").unwrap()), Some(id));
+        assert_eq!(data.ngram(id), Some("bc"));
+        assert_eq!(data.row_slice(id), Some(&[0.0, 0.3, 0.0][..]));
+        assert_eq!(data.row_slice(data.ngram_count()), None);
+
+        // N-grams too long to be packed are stored as well
+        let mut data = data;
+        data.set("abcd", 1, 0.5);
+        assert_eq!(data.probabilities("abcd"), Some(vec![0.0, 0.5, 0.0]));
+        assert_eq!(data.ngram(data.ngram_id("abcd").unwrap()), Some("abcd"));
+    }
+
+    #[test]
+    fn test_remove_language() {
+        let mut data = ProfileData::default();
+        data.resize_languages(3);
+        data.set("a", 0, 0.1);
+        data.set("a", 1, 0.2);
+        data.set("a", 2, 0.3);
+        assert_eq!(data.remove_language(1), Some(HashMap::from([("a".to_string(), 0.2)])));
+        assert_eq!(data.remove_language(2), None);
+        data.clear_language(1);
+        assert_eq!(data.probabilities("a"), Some(vec![0.1, 0.0]));
+        data.set("a", 1, 0.3);
+        assert_eq!(data.probabilities("a"), Some(vec![0.1, 0.3]));
+        assert!(data.language_memory(0) > 0);
+        assert_eq!(data.language_memory(5), 0);
+        // Out of range columns are ignored
+        data.set("b", 5, 1.0);
+        assert!(!data.contains("b"));
+    }
+
+    #[test]
+    fn test_shrink_to_budget() {
+        let mut data = ProfileData::default();
+        data.resize_languages(2);
+        for i in 0..200 {
+            data.set(&format!("a{}", i), 0, (i + 1) as f64);
+            data.set(&format!("b{}", i), 1, (i + 1) as f64 + 0.5);
+        }
+        data.set("a199", 1, 1000.0);
+        let full = data.memory_usage();
+        assert_eq!(data.shrink_to_budget(full), 0);
+        let removed = data.shrink_to_budget(full / 4);
+        assert!(removed > 0);
+        assert!(data.memory_usage() <= full / 4);
+        assert_eq!(data.ngram_count() + removed, 401 - 1);
+        // The most probable entries of both languages survive, with consistent ids
+        assert_eq!(data.probabilities("a199"), Some(vec![200.0, 1000.0]));
+        assert_eq!(data.probabilities("b199"), Some(vec![0.0, 200.5]));
+        assert!(!data.contains("a0"));
+        assert_eq!(data.shrink_to_budget(0), 200 * 2 + 1 - removed);
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn test_language_scripts() {
+        let mut data = ProfileData::default();
+        data.resize_languages(2);
+        data.set("a", 0, 0.95);
+        data.set("ж", 0, 0.05);
+        data.set("ab", 0, 0.5);
+        data.set("\u{3042}", 1, 0.5);
+        data.set("\u{30A2}", 1, 0.3);
+        data.set("\u{4E00}", 1, 0.2);
+        assert_eq!(data.language_scripts(0), &[Script::Latin]);
+        assert_eq!(data.language_scripts(1), &[Script::Hiragana, Script::Katakana, Script::Han]);
+        assert!(data.language_scripts(2).is_empty());
+        // Changes are picked up
+        data.set("ж", 0, 0.95);
+        assert_eq!(data.language_scripts(0), &[Script::Latin, Script::Cyrillic]);
+        data.set_language_scripts(0, Some(vec![Script::Cyrillic]));
+        assert_eq!(data.language_scripts(0), &[Script::Cyrillic]);
+        assert_eq!(data.declared_language_scripts(0), Some(&[Script::Cyrillic][..]));
+        data.remove_language(0);
+        assert_eq!(data.declared_language_scripts(0), None);
+        assert_eq!(data.language_scripts(0), &[Script::Hiragana, Script::Katakana, Script::Han]);
+        data.set_language_scripts(0, Some(vec![Script::Han]));
+        data.set_language_scripts(0, None);
+        assert_eq!(data.language_scripts(0), &[Script::Hiragana, Script::Katakana, Script::Han]);
+    }
+
+    #[test]
+    fn test_binary_round_trip() {
+        let mut data = ProfileData::default();
+        data.resize_languages(2);
+        data.set("a", 0, 0.5);
+        data.set("\u{3042}b", 1, 0.25);
+        let langlist = vec![Lang::En, Lang::Ja];
+        let bytes = data.to_bytes(&langlist);
+        let (decoded_langlist, decoded) = ProfileData::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded_langlist, langlist);
+        assert_eq!(decoded.to_word_lang_prob_map(), data.to_word_lang_prob_map());
+        assert!(ProfileData::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(ProfileData::from_bytes(b"JSON").is_err());
+        // Huge counts in a tiny file are rejected before anything is allocated
+        let mut bytes = ProfileData::BINARY_MAGIC.to_vec();
+        bytes.extend_from_slice(&ProfileData::BINARY_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(ProfileData::from_bytes(&bytes).is_err());
+        let bytes = ProfileData::default().to_bytes(&[]);
+        let mut huge = bytes[..bytes.len() - 4].to_vec();
+        huge.extend_from_slice(&u32::MAX.to_le_bytes());
+        assert!(ProfileData::from_bytes(&huge).is_err());
+        // The dense matrix, larger than the file, is checked before it is allocated
+        let mut wide = ProfileData::default();
+        wide.resize_languages(50);
+        for (index, word) in ["a", "b", "c", "d", "e", "f", "g", "h"].iter().enumerate() {
+            wide.set(word, index, 0.5);
+        }
+        let langlist: Vec<Lang> = (0..50).map(|index| Lang::from(format!("l{}", index))).collect();
+        let bytes = wide.to_bytes(&langlist);
+        let limits = ProfileLimits::default().with_max_model_size(bytes.len());
+        assert!(matches!(ProfileData::from_bytes_with_limits(&bytes, &limits), Err(LangProfileJsonError::LimitExceeded(_))));
+        let limits = ProfileLimits::default().with_max_model_size(50 * 8 * 8);
+        assert!(ProfileData::from_bytes_with_limits(&bytes, &limits).is_ok());
+        assert!(matches!(ProfileData::from_bytes_with_limits(b"JSON", &limits), Err(LangProfileJsonError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_binary_duplicated_ngram() {
+        // One language "en", the n-grams ["a", "a"] and an entry for the second one
+        let mut bytes = ProfileData::BINARY_MAGIC.to_vec();
+        bytes.extend_from_slice(&ProfileData::BINARY_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        write_str(&mut bytes, "en");
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        write_str(&mut bytes, "a");
+        write_str(&mut bytes, "a");
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&0.5f64.to_le_bytes());
+        assert!(ProfileData::from_bytes(&bytes).unwrap_err().contains("Duplicated n-gram"));
+        assert!(matches!(ProfileData::from_bytes_with_limits(&bytes, &ProfileLimits::default()), Err(LangProfileJsonError::ParseError(_))));
+        // Ids past the n-gram table are rejected as well
+        let mut data = ProfileData::default();
+        data.resize_languages(1);
+        data.set("a", 0, 0.5);
+        let mut bytes = data.to_bytes(&[Lang::En]);
+        let id = bytes.len() - 12;
+        bytes[id..id + 4].copy_from_slice(&1u32.to_le_bytes());
+        assert!(matches!(ProfileData::from_bytes_with_limits(&bytes, &ProfileLimits::default()), Err(LangProfileJsonError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_profile_matrix() {
+        let mut data = ProfileData::default();
+        data.resize_languages(3);
+        data.set("a", 0, 0.5);
+        data.set("a", 2, 0.25);
+        let id = data.ngram_id("a").unwrap();
+        let mut matrix = ProfileMatrix::new(2);
+        assert!(matrix.is_empty());
+        assert_eq!(data.push_row(id, Some(&[2, 1]), &mut matrix), 0);
+        assert_eq!(matrix.push_row([1.0]), 1);
+        assert_eq!(matrix.push_row([1.0, 2.0, 3.0]), 2);
+        assert_eq!(matrix.len(), 3);
+        assert_eq!(matrix.row(0), &[0.25, 0.0]);
+        assert_eq!(matrix.row(1), &[1.0, 0.0]);
+        assert_eq!(matrix.row(2), &[1.0, 2.0]);
+        matrix.pop_row();
+        assert_eq!(matrix.len(), 2);
+        assert_eq!(ProfileMatrix::new(0).push_row([1.0]), 0);
+    }
+
+    #[test]
+    fn test_mapped_round_trip() {
+        let mut data = ProfileData::default();
+        data.resize_languages(2);
+        data.set("b", 0, 0.5);
+        data.set("a", 0, 0.25);
+        data.set("\u{3042}", 1, 0.75);
+        let langlist = vec![Lang::En, Lang::Ja];
+        let bytes = data.to_mapped_bytes(&langlist);
+        let (decoded_langlist, mut mapped) = ProfileData::from_mapped_bytes(bytes.clone()).unwrap();
+        assert_eq!(decoded_langlist, langlist);
+        assert!(mapped.is_mapped());
+        assert_eq!(mapped.lang_count(), 2);
+        assert_eq!(mapped.to_word_lang_prob_map(), data.to_word_lang_prob_map());
+        assert_eq!(mapped.ngram(mapped.ngram_id("b").unwrap()), Some("b"));
+        assert!(!mapped.contains("c"));
+        assert_eq!(mapped.column(1).unwrap().len(), 1);
+        assert_eq!(mapped.language_scripts(1), &[Script::Hiragana]);
+        assert_eq!(mapped.memory_usage(), 0);
+        assert_eq!(mapped.row_slice(0), None);
+        assert_eq!(ProfileData::from_bytes(&mapped.to_bytes(&langlist)).unwrap().1.to_word_lang_prob_map(), data.to_word_lang_prob_map());
+
+        // Modifications copy the table into the matrix, keeping the n-gram ids
+        let id = mapped.ngram_id("b").unwrap();
+        mapped.set("c", 1, 0.125);
+        assert!(!mapped.is_mapped());
+        assert_eq!(mapped.ngram_id("b"), Some(id));
+        assert_eq!(mapped.row_slice(id), Some(&[0.5, 0.0][..]));
+        assert_eq!(mapped.probabilities("a"), Some(vec![0.25, 0.0]));
+        assert_eq!(mapped.probabilities("c"), Some(vec![0.0, 0.125]));
+
+        assert!(ProfileData::from_mapped_bytes(bytes[..bytes.len() - 1].to_vec()).is_err());
+        assert!(ProfileData::from_mapped_bytes(data.to_bytes(&langlist)).is_err());
+        let mut unsorted = bytes.clone();
+        let strings = unsorted.windows(2).position(|w| w == b"ab").unwrap();
+        unsorted.swap(strings, strings + 1);
+        assert!(ProfileData::from_mapped_bytes(unsorted).is_err());
+        // Huge counts in a tiny file are rejected before anything is allocated
+        let mut huge = bytes[..8].to_vec();
+        huge.extend_from_slice(&u32::MAX.to_le_bytes());
+        huge.extend_from_slice(&0u32.to_le_bytes());
+        assert!(ProfileData::from_mapped_bytes(huge).is_err());
+    }
+}
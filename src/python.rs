@@ -0,0 +1,215 @@
+//! Python extension module, enabled by the `python` feature.
+//!
+//! Mirrors the API of the Python `langdetect` package, so migrating mostly means changing
+//! the import. Build with `maturin build --release` (see `pyproject.toml`).
+//!
+//! ```python
+//! from langdetect_rs import detect, detect_langs, set_seed, DetectorFactory, LangDetectException
+//!
+//! set_seed(0)                        # instead of `DetectorFactory.seed = 0`
+//! detect("War doesn't show who's right, just who's left.")   # 'en'
+//! detect_langs("Otec matka syn.")    # [fi:0.57..., pl:0.42...]
+//!
+//! factory = DetectorFactory()
+//! factory.load_profile("/path/to/profiles")
+//! detector = factory.create()
+//! detector.append("Bonjour tout le monde")
+//! detector.detect()                  # 'fr'
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+
+use crate::detector::{Detector, DetectorError};
+use crate::detector_factory::DetectorFactory;
+use crate::language::Language;
+
+create_exception!(langdetect_rs, LangDetectException, PyException);
+
+/// Seed of the detectors created by the module-level functions.
+static SEED: Mutex<Option<u64>> = Mutex::new(None);
+
+impl From<DetectorError> for PyErr {
+    fn from(err: DetectorError) -> PyErr {
+        LangDetectException::new_err(err.to_string())
+    }
+}
+
+/// A detected language and its probability.
+#[pyclass(name = "Language", frozen, get_all)]
+struct PyLanguage {
+    lang: Option<String>,
+    prob: f64,
+}
+
+#[pymethods]
+impl PyLanguage {
+    fn __repr__(&self) -> String {
+        match &self.lang {
+            Some(lang) => format!("{}:{}", lang, self.prob),
+            None => String::new(),
+        }
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+impl From<Language> for PyLanguage {
+    fn from(language: Language) -> Self {
+        PyLanguage { lang: language.lang.map(String::from), prob: language.prob }
+    }
+}
+
+/// Language profiles creating `Detector` objects.
+#[pyclass(name = "DetectorFactory")]
+struct PyDetectorFactory {
+    factory: DetectorFactory,
+}
+
+#[pymethods]
+impl PyDetectorFactory {
+    /// Creates a factory without languages, see `load_profile` and `load_json_profile`.
+    #[new]
+    fn new() -> Self {
+        PyDetectorFactory { factory: DetectorFactory::new().build() }
+    }
+
+    /// Creates a factory with the built-in profiles.
+    #[staticmethod]
+    fn default() -> PyResult<Self> {
+        let builder = DetectorFactory::try_default().map_err(|err| LangDetectException::new_err(err.to_string()))?;
+        Ok(PyDetectorFactory { factory: builder.build() })
+    }
+
+    /// Loads every JSON profile of a directory.
+    fn load_profile(&mut self, profile_directory: &str) -> PyResult<()> {
+        self.factory.load_profile(profile_directory).map_err(LangDetectException::new_err)
+    }
+
+    /// Loads profiles from JSON strings.
+    fn load_json_profile(&mut self, json_profiles: Vec<String>) -> PyResult<()> {
+        let json_profiles: Vec<&str> = json_profiles.iter().map(String::as_str).collect();
+        self.factory.load_json_profile(&json_profiles).map_err(|err| LangDetectException::new_err(err.to_string()))
+    }
+
+    /// Removes all languages.
+    fn clear(&mut self) {
+        self.factory.clear();
+    }
+
+    /// Sets the seed of the created detectors.
+    fn set_seed(&mut self, seed: u64) {
+        self.factory.set_seed(seed);
+    }
+
+    /// Returns the codes of the loaded languages.
+    fn get_lang_list(&self) -> Vec<String> {
+        self.factory.get_lang_list().into_iter().map(String::from).collect()
+    }
+
+    /// Creates a detector, optionally with a custom alpha smoothing parameter.
+    #[pyo3(signature = (alpha=None))]
+    fn create(&self, alpha: Option<f64>) -> PyResult<PyDetector> {
+        if self.factory.langlist.is_empty() {
+            return Err(LangDetectException::new_err("Need to load profiles."));
+        }
+        Ok(PyDetector { detector: self.factory.create(alpha) })
+    }
+}
+
+/// Detects the language of the appended text.
+#[pyclass(name = "Detector")]
+struct PyDetector {
+    detector: Detector,
+}
+
+#[pymethods]
+impl PyDetector {
+    /// Appends text to analyze.
+    fn append(&mut self, text: &str) {
+        self.detector.append(text);
+    }
+
+    /// Returns the most probable language code, or "unknown".
+    fn detect(&mut self) -> PyResult<String> {
+        Ok(self.detector.detect()?)
+    }
+
+    /// Returns the languages above the probability threshold, most probable first.
+    fn get_probabilities(&mut self) -> PyResult<Vec<PyLanguage>> {
+        Ok(self.detector.get_probabilities()?.into_iter().map(PyLanguage::from).collect())
+    }
+
+    /// Enables verbose logging.
+    fn set_verbose(&mut self) {
+        self.detector.verbose = true;
+    }
+
+    /// Sets the alpha smoothing parameter.
+    fn set_alpha(&mut self, alpha: f64) {
+        self.detector.alpha = alpha;
+    }
+
+    /// Sets the maximum number of characters taken from every appended text.
+    fn set_max_text_length(&mut self, max_text_length: usize) {
+        self.detector.max_text_length = max_text_length;
+    }
+
+    /// Sets prior probabilities by language code.
+    fn set_prior_map(&mut self, prior_map: HashMap<String, f64>) -> PyResult<()> {
+        let priors = prior_map.iter().map(|(lang, prob)| (lang.as_str(), *prob)).collect();
+        Ok(self.detector.set_priors(priors)?)
+    }
+}
+
+/// Creates a detector with the built-in profiles and the module seed.
+fn create_detector(text: &str) -> Detector {
+    let mut detector = DetectorFactory::default_shared().create(None);
+    detector.seed = *SEED.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    detector.append(text);
+    detector
+}
+
+/// Sets the seed of the module-level functions (`DetectorFactory.seed` in `langdetect`).
+#[pyfunction]
+#[pyo3(signature = (seed=None))]
+fn set_seed(seed: Option<u64>) {
+    *SEED.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = seed;
+}
+
+/// Loads the built-in profiles now rather than on the first detection.
+#[pyfunction]
+fn init_factory() {
+    DetectorFactory::default_shared();
+}
+
+/// Detects the language of a text with the built-in profiles.
+#[pyfunction]
+fn detect(text: &str) -> PyResult<String> {
+    Ok(create_detector(text).detect()?)
+}
+
+/// Returns the probable languages of a text with the built-in profiles.
+#[pyfunction]
+fn detect_langs(text: &str) -> PyResult<Vec<PyLanguage>> {
+    Ok(create_detector(text).get_probabilities()?.into_iter().map(PyLanguage::from).collect())
+}
+
+#[pymodule]
+fn langdetect_rs(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyLanguage>()?;
+    m.add_class::<PyDetectorFactory>()?;
+    m.add_class::<PyDetector>()?;
+    m.add("LangDetectException", m.py().get_type::<LangDetectException>())?;
+    m.add_function(wrap_pyfunction!(set_seed, m)?)?;
+    m.add_function(wrap_pyfunction!(init_factory, m)?)?;
+    m.add_function(wrap_pyfunction!(detect, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_langs, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::ops::Range;
+
+use crate::detector_factory::split_sentences;
+
+/// Splits text into sentences and words for the per-segment detection methods.
+///
+/// `DetectorFactory::detect_sentences` and `DetectorFactory::detect_words` use the segmenter
+/// set with `DetectorFactoryBuilder::with_segmenter`, `DefaultSegmenter` by default. The default
+/// relies on spaces and punctuation, so plug in a dictionary or ICU based segmenter for
+/// scripts written without spaces (Thai, Japanese, Chinese, ...).
+///
+/// # Examples
+///
+/// ```rust
+/// use std::ops::Range;
+/// use langdetect_rs::detector_factory::DetectorFactory;
+/// use langdetect_rs::segmenter::Segmenter;
+///
+/// /// Treats every line as a sentence and every character as a word.
+/// struct CharSegmenter;
+///
+/// impl Segmenter for CharSegmenter {
+///     fn sentences(&self, text: &str) -> Vec<Range<usize>> {
+///         let mut offset = 0;
+///         text.split_inclusive('\n').map(|line| {
+///             let range = offset..offset + line.trim_end().len();
+///             offset += line.len();
+///             range
+///         }).filter(|range| !range.is_empty()).collect()
+///     }
+///
+///     fn words(&self, text: &str) -> Vec<Range<usize>> {
+///         text.char_indices().filter(|(_, ch)| ch.is_alphabetic()).map(|(i, ch)| i..i + ch.len_utf8()).collect()
+///     }
+/// }
+///
+/// let factory = DetectorFactory::default().with_segmenter(CharSegmenter).build();
+/// assert_eq!(factory.detect_words("日本語", None).len(), 3);
+/// ```
+pub trait Segmenter: Send + Sync {
+    /// Returns the byte ranges of the non-empty sentences of a text, in text order.
+    fn sentences(&self, text: &str) -> Vec<Range<usize>>;
+
+    /// Returns the byte ranges of the words of a text, in text order.
+    fn words(&self, text: &str) -> Vec<Range<usize>>;
+}
+
+/// Segmenter splitting on whitespace and punctuation.
+///
+/// Sentences are split with `split_sentences`, words with `split_words`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultSegmenter;
+
+impl Segmenter for DefaultSegmenter {
+    fn sentences(&self, text: &str) -> Vec<Range<usize>> {
+        split_sentences(text)
+    }
+
+    fn words(&self, text: &str) -> Vec<Range<usize>> {
+        split_words(text)
+    }
+}
+
+/// Splits text into byte ranges of words.
+///
+/// Words are separated by whitespace and punctuation. Apostrophes and hyphens between
+/// letters or digits stay inside the word ("don't", "e-mail"). Text without spaces, such
+/// as Thai or Japanese, is returned as a single word per run.
+///
+/// # Arguments
+/// * `text` - The text to split.
+///
+/// # Returns
+/// The byte ranges of the words, in text order.
+///
+/// # Example
+///
+/// ```rust
+/// use langdetect_rs::segmenter::split_words;
+///
+/// let text = "Don't panic, «e-mail» me!";
+/// let words: Vec<&str> = split_words(text).into_iter().map(|r| &text[r]).collect();
+/// assert_eq!(words, vec!["Don't", "panic", "e-mail", "me"]);
+/// ```
+pub fn split_words(text: &str) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, ch)) = chars.next() {
+        let joiner = matches!(ch, '\'' | '’' | '-' | '‐')
+            && start.is_some()
+            && chars.peek().is_some_and(|(_, next)| next.is_alphanumeric());
+        if is_separator(ch) && !joiner {
+            if let Some(s) = start.take() {
+                words.push(s..i);
+            }
+        } else {
+            start.get_or_insert(i);
+        }
+    }
+    if let Some(s) = start {
+        words.push(s..text.len());
+    }
+    words
+}
+
+/// Returns true for characters that separate words.
+fn is_separator(ch: char) -> bool {
+    ch.is_whitespace()
+        || ch.is_ascii_punctuation()
+        || matches!(
+            ch,
+            '«' | '»' | '“' | '”' | '„' | '‘' | '’' | '‐' | '–' | '—' | '…' | '¡' | '¿' | '·'
+                | '、' | '。' | '，' | '！' | '？' | '：' | '；' | '「' | '」' | '『' | '』' | '（' | '）'
+                | '،' | '؛' | '؟' | '।' | '॥' | '።'
+        )
+}
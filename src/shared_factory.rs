@@ -0,0 +1,234 @@
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::detector::{Detector, DetectorError};
+use crate::detector_factory::{DetectorFactory, DetectorFactoryError, ProfileColumn};
+use crate::language::Language;
+use crate::utils::lang_profile::LangProfile;
+
+/// Thread-safe factory whose language set can change while detections are running.
+///
+/// Detections run on a snapshot: an `Arc` of the factory taken at the start of the call.
+/// Updates copy the factory, modify the copy and swap it in, so a running detection is
+/// never affected by a concurrent `add_profile` or `delete_profile` and readers only wait
+/// for the swap itself, not for the copy or the change. Profile data is shared between snapshots and copied on write.
+///
+/// Intended for multi-tenant services adjusting the language set at runtime; for a fixed
+/// set of languages share a plain `Arc<DetectorFactory>` instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::Arc;
+/// use langdetect_rs::detector_factory::DetectorFactory;
+/// use langdetect_rs::language::Lang;
+/// use langdetect_rs::shared_factory::SharedFactory;
+///
+/// let shared = Arc::new(SharedFactory::new(DetectorFactory::default().build()));
+/// let worker = {
+///     let shared = Arc::clone(&shared);
+///     std::thread::spawn(move || shared.detect("Bonjour tout le monde !", None))
+/// };
+/// shared.delete_profile("af").unwrap();
+/// assert_eq!(worker.join().unwrap().unwrap(), "fr");
+/// assert!(!shared.snapshot().get_lang_list().contains(&Lang::Af));
+/// ```
+pub struct SharedFactory {
+    factory: RwLock<Arc<DetectorFactory>>,
+    /// Serializes updates, so none of them is based on a stale copy.
+    updates: Mutex<()>,
+}
+
+impl SharedFactory {
+    /// Wraps a factory.
+    ///
+    /// # Arguments
+    /// * `factory` - The initial factory.
+    pub fn new(factory: DetectorFactory) -> Self {
+        SharedFactory { factory: RwLock::new(Arc::new(factory)), updates: Mutex::new(()) }
+    }
+
+    /// Returns the current factory.
+    ///
+    /// The snapshot is not affected by later updates, so several detections can be run
+    /// on one consistent language set.
+    pub fn snapshot(&self) -> Arc<DetectorFactory> {
+        Arc::clone(&self.factory.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Creates a detector from the current factory, see `DetectorFactory::create`.
+    pub fn create(&self, alpha: Option<f64>) -> Detector {
+        self.snapshot().create(alpha)
+    }
+
+    /// Detects the language of a text with the current factory, see `DetectorFactory::detect`.
+    pub fn detect(&self, text: &str, alpha: Option<f64>) -> Result<String, DetectorError> {
+        self.snapshot().detect(text, alpha)
+    }
+
+    /// Computes language probabilities with the current factory, see `DetectorFactory::get_probabilities`.
+    pub fn get_probabilities(&self, text: &str, alpha: Option<f64>) -> Result<Vec<Language>, DetectorError> {
+        self.snapshot().get_probabilities(text, alpha)
+    }
+
+    /// Appends a language profile, see `DetectorFactory::push_profile`.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::DuplicatedLanguage` if the language already exists.
+    pub fn add_profile(&self, profile: LangProfile) -> Result<(), DetectorFactoryError> {
+        self.update(|factory| factory.push_profile(profile))
+    }
+
+    /// Removes a language, see `DetectorFactory::delete_profile`.
+    ///
+    /// # Returns
+    /// The removed probabilities, which `restore_profile` can add again later.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::LanguageNotFound` if the language is not loaded.
+    pub fn delete_profile(&self, lang: &str) -> Result<ProfileColumn, DetectorFactoryError> {
+        self.update(|factory| factory.delete_profile(lang))
+    }
+
+    /// Adds a language removed with `delete_profile` again, see `DetectorFactory::restore_profile`.
+    ///
+    /// # Errors
+    /// Returns `DetectorFactoryError::DuplicatedLanguage` if the language already exists.
+    pub fn restore_profile(&self, column: ProfileColumn) -> Result<(), DetectorFactoryError> {
+        self.update(|factory| factory.restore_profile(column))
+    }
+
+    /// Applies a change to a copy of the current factory and publishes it if the change succeeds.
+    ///
+    /// Updates are serialized; the copy is changed without holding the factory lock, so
+    /// detections (and snapshots taken by the change itself) run meanwhile.
+    /// A result cache of the factory is replaced by an empty one with the same settings,
+    /// since its entries belong to the old language set. Snapshots taken before keep the
+    /// old cache, so their results do not end up in the new one.
+    ///
+    /// # Arguments
+    /// * `change` - The modification; the factory is left unchanged if it returns an error.
+    ///
+    /// # Returns
+    /// The result of the change.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::shared_factory::SharedFactory;
+    ///
+    /// let shared = SharedFactory::new(DetectorFactory::default().build());
+    /// shared.update(|factory| {
+    ///     factory.delete_profile("af")?;
+    ///     factory.delete_profile("nl")
+    /// }).unwrap();
+    /// ```
+    pub fn update<T, E>(&self, change: impl FnOnce(&mut DetectorFactory) -> Result<T, E>) -> Result<T, E> {
+        let _update = self.updates.lock().unwrap_or_else(|e| e.into_inner());
+        let mut factory = DetectorFactory::clone(&self.snapshot());
+        let result = change(&mut factory)?;
+        factory.cache = factory.cache.as_ref().map(|cache| Arc::new(cache.empty_copy()));
+        *self.factory.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(factory);
+        Ok(result)
+    }
+}
+
+impl From<DetectorFactory> for SharedFactory {
+    fn from(factory: DetectorFactory) -> Self {
+        SharedFactory::new(factory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::DetectionCache;
+
+    fn profile(name: &str, words: &[&str]) -> LangProfile {
+        let mut profile = LangProfile::new().with_name(name).build();
+        for w in words {
+            profile.add(w);
+        }
+        profile
+    }
+
+    #[test]
+    fn test_snapshot_consistency() {
+        let mut factory = DetectorFactory::new().with_seed(Some(0)).build();
+        factory.push_profile(profile("en", &["a", "a", "b"])).unwrap();
+        factory.push_profile(profile("ja", &["\u{3042}", "\u{3044}"])).unwrap();
+        let shared = SharedFactory::new(factory);
+
+        let before = shared.snapshot();
+        let column = shared.delete_profile("ja").unwrap();
+        assert_eq!(before.detect("\u{3042}", None).unwrap(), "ja");
+        assert_ne!(shared.detect("\u{3042}", None).ok().as_deref(), Some("ja"));
+        assert!(matches!(shared.delete_profile("ja"), Err(DetectorFactoryError::LanguageNotFound(_))));
+        shared.restore_profile(column.clone()).unwrap();
+        assert_eq!(shared.detect("\u{3042}", None).unwrap(), "ja");
+        assert!(shared.restore_profile(column).is_err());
+        shared.delete_profile("ja").unwrap();
+
+        shared.add_profile(profile("xx", &["\u{3042}"])).unwrap();
+        assert!(shared.add_profile(profile("xx", &["x"])).is_err());
+        assert_eq!(shared.detect("\u{3042}", None).unwrap(), "xx");
+        assert_eq!(shared.snapshot().get_lang_list(), vec!["en", "xx"]);
+        assert_eq!(before.get_lang_list(), vec!["en", "ja"]);
+    }
+
+    #[test]
+    fn test_update_replaces_cache() {
+        let mut factory = DetectorFactory::new().with_seed(Some(0))
+            .with_cache(Some(Arc::new(DetectionCache::new().with_capacity(8).build())))
+            .build();
+        factory.push_profile(profile("en", &["a", "a", "b"])).unwrap();
+        factory.push_profile(profile("ja", &["\u{3042}", "\u{3044}"])).unwrap();
+        let shared = SharedFactory::new(factory);
+        let before = shared.snapshot();
+        assert_eq!(before.detect("\u{3042}", None).unwrap(), "ja");
+        shared.delete_profile("ja").unwrap();
+        // The old snapshot keeps filling its own cache
+        assert_eq!(before.detect("\u{3042}", None).unwrap(), "ja");
+        assert_eq!(before.cache.as_ref().unwrap().len(), 1);
+        let after = shared.snapshot();
+        assert!(after.cache.as_ref().unwrap().is_empty());
+        assert_ne!(after.detect("\u{3042}", None).ok().as_deref(), Some("ja"));
+    }
+
+    #[test]
+    fn test_concurrent_updates() {
+        let mut factory = DetectorFactory::new().with_seed(Some(0)).build();
+        factory.push_profile(profile("en", &["a", "a", "b"])).unwrap();
+        let shared = Arc::new(SharedFactory::new(factory));
+        let readers: Vec<_> = (0..4).map(|_| {
+            let shared = Arc::clone(&shared);
+            std::thread::spawn(move || {
+                for _ in 0..50 {
+                    assert_eq!(shared.detect("a b", None).unwrap(), "en");
+                }
+            })
+        }).collect();
+        for i in 0..10 {
+            shared.add_profile(profile(&format!("x{}", i), &["\u{3042}"])).unwrap();
+        }
+        for reader in readers {
+            reader.join().unwrap();
+        }
+        assert_eq!(shared.snapshot().get_lang_list().len(), 11);
+    }
+
+    #[test]
+    fn test_update_does_not_block_readers() {
+        let mut factory = DetectorFactory::new().with_seed(Some(0)).build();
+        factory.push_profile(profile("en", &["a", "a", "b"])).unwrap();
+        factory.push_profile(profile("ja", &["\u{3042}", "\u{3044}"])).unwrap();
+        let shared = SharedFactory::new(factory);
+        shared.update(|factory| {
+            factory.delete_profile("ja")?;
+            // Detections keep using the published factory while the change runs
+            assert_eq!(shared.detect("\u{3042}", None).unwrap(), "ja");
+            Ok::<_, DetectorFactoryError>(())
+        }).unwrap();
+        assert_eq!(shared.snapshot().get_lang_list(), vec!["en"]);
+    }
+}
@@ -0,0 +1,900 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::BufRead;
+use std::path::{Path, PathBuf};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::detector_factory::DetectorFactory;
+use crate::language::Lang;
+use crate::utils::lang_profile::LangProfile;
+use crate::utils::arabic::normalize_arabic;
+use crate::utils::ngram::{NGram, Normalization};
+
+/// Counters describing what the trainer did with the supplied corpus.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrainerStats {
+    /// Number of documents passed to the trainer.
+    pub documents: usize,
+    /// Number of sentences used for training.
+    pub sentences: usize,
+    /// Number of sentences skipped as near-duplicates of already seen ones.
+    pub duplicate_sentences: usize,
+    /// Number of documents cut short by the per-document character cap.
+    pub truncated_documents: usize,
+    /// Number of noisy sentence copies generated by augmentation.
+    pub augmented_sentences: usize,
+    /// Number of documents skipped because of a zero, negative or non-finite weight.
+    pub skipped_documents: usize,
+}
+
+/// Result of comparing a trained profile against the most confusable language of a factory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HardNegativeReport {
+    /// Language of the factory closest to the trained profile.
+    pub confusable: Lang,
+    /// Probability mass shared with the confusable language, between 0.0 and 1.0.
+    pub overlap: f64,
+    /// N-grams that best separate the trained profile from the confusable language,
+    /// with their probability margin, most discriminative first.
+    pub discriminative: Vec<(String, f64)>,
+}
+
+/// Noise injection settings used to augment the training corpus.
+///
+/// For every training sentence the trainer additionally feeds `copies` noisy
+/// variants into the profile, which makes the model more robust on user-generated
+/// content (missing diacritics, odd casing, typos). All rates are probabilities
+/// between 0.0 and 1.0.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Augmentation {
+    /// Number of noisy copies generated per sentence.
+    pub copies: usize,
+    /// Probability of stripping the accents of the whole copy (e.g. "é" -> "e").
+    pub accent_stripping: f64,
+    /// Probability of changing the casing of the whole copy (all lower or all upper case).
+    pub case_change: f64,
+    /// Per-character probability of a typo (dropped, doubled or swapped character).
+    pub typo_rate: f64,
+    /// Optional seed for reproducible augmentation.
+    pub seed: Option<u64>,
+}
+
+impl Default for Augmentation {
+    fn default() -> Self {
+        Augmentation {
+            copies: 1,
+            accent_stripping: 0.3,
+            case_change: 0.2,
+            typo_rate: 0.02,
+            seed: None,
+        }
+    }
+}
+
+impl Augmentation {
+    /// Produces a noisy copy of the sentence.
+    fn apply(&self, sentence: &str, rng: &mut StdRng) -> String {
+        let mut chars: Vec<char> = if rng.random_bool(self.accent_stripping.clamp(0.0, 1.0)) {
+            sentence.chars().map(strip_accent).collect()
+        } else {
+            sentence.chars().collect()
+        };
+        if rng.random_bool(self.case_change.clamp(0.0, 1.0)) {
+            let upper = rng.random_bool(0.5);
+            chars = chars.into_iter().flat_map(|c| {
+                if upper { c.to_uppercase().collect::<Vec<_>>() } else { c.to_lowercase().collect::<Vec<_>>() }
+            }).collect();
+        }
+        let typo_rate = self.typo_rate.clamp(0.0, 1.0);
+        let mut result = String::with_capacity(sentence.len());
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i].is_alphabetic() && rng.random_bool(typo_rate) {
+                match rng.random_range(0..3) {
+                    // Dropped character
+                    0 => {}
+                    // Doubled character
+                    1 => {
+                        result.push(chars[i]);
+                        result.push(chars[i]);
+                    }
+                    // Swapped with the next character
+                    _ => {
+                        if i + 1 < chars.len() {
+                            result.push(chars[i + 1]);
+                            result.push(chars[i]);
+                            i += 1;
+                        } else {
+                            result.push(chars[i]);
+                        }
+                    }
+                }
+            } else {
+                result.push(chars[i]);
+            }
+            i += 1;
+        }
+        result
+    }
+}
+
+/// Builds a `LangProfile` from a plain-text corpus.
+///
+/// Compared to calling `LangProfile::update()` directly, the trainer can drop
+/// near-identical sentences (navigation, licenses and other boilerplate that crawled
+/// corpora are full of) and cap how much a single document contributes, so a few
+/// huge documents do not skew the n-gram frequencies.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::trainer::LangProfileTrainer;
+///
+/// let mut trainer = LangProfileTrainer::new("en")
+///     .with_deduplication(true)
+///     .with_max_chars_per_document(Some(10_000))
+///     .build();
+/// trainer.add_document("Hello world. Hello world. This is a test.");
+/// assert_eq!(trainer.stats().duplicate_sentences, 1);
+/// let profile = trainer.finish();
+/// assert_eq!(profile.name.as_deref(), Some("en"));
+/// ```
+pub struct LangProfileTrainer {
+    profile: LangProfile,
+    deduplicate: bool,
+    max_chars_per_document: Option<usize>,
+    augmentation: Option<Augmentation>,
+    less_freq_ratio: usize,
+    minimum_freq: usize,
+    rng: StdRng,
+    seen: HashSet<u64>,
+    /// Fractional parts of weighted n-gram counts not yet added to the profile.
+    residues: HashMap<String, f64>,
+    /// Fractional parts of weighted per-length totals not yet added to the profile.
+    n_words_residues: [f64; NGram::N_GRAM],
+    stats: TrainerStats,
+}
+
+impl LangProfileTrainer {
+    /// Creates a new LangProfileTrainer builder for the given language.
+    ///
+    /// # Arguments
+    /// * `name` - The language identifier of the trained profile.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(name: &str) -> LangProfileTrainerBuilder {
+        LangProfileTrainerBuilder {
+            trainer: LangProfileTrainer {
+                profile: LangProfile::new().with_name(name).build(),
+                deduplicate: false,
+                max_chars_per_document: None,
+                augmentation: None,
+                less_freq_ratio: LangProfile::LESS_FREQ_RATIO,
+                minimum_freq: LangProfile::MINIMUM_FREQ,
+                rng: StdRng::from_rng(&mut rand::rng()),
+                seen: HashSet::new(),
+                residues: HashMap::new(),
+                n_words_residues: [0.0; NGram::N_GRAM],
+                stats: TrainerStats::default(),
+            },
+        }
+    }
+
+    /// Adds a single document of the corpus to the profile.
+    ///
+    /// The document is split into sentences. Near-duplicate sentences are skipped when
+    /// deduplication is enabled, and only the first `max_chars_per_document` characters
+    /// of kept sentences are used.
+    ///
+    /// # Arguments
+    /// * `text` - The document text.
+    pub fn add_document(&mut self, text: &str) {
+        self.add_weighted_document(text, 1.0);
+    }
+
+    /// Adds a document whose n-grams count `weight` times instead of once.
+    ///
+    /// Use weights below 1.0 for noisy documents (e.g. a low score of a quality
+    /// classifier) and above 1.0 for curated text. Fractional counts are accumulated
+    /// per n-gram and rounded when training finishes, so many low-weight documents
+    /// still add up. Documents with a zero, negative or non-finite weight are skipped.
+    ///
+    /// # Arguments
+    /// * `text` - The document text.
+    /// * `weight` - Contribution of the document relative to `add_document`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::trainer::LangProfileTrainer;
+    ///
+    /// let mut trainer = LangProfileTrainer::new("en").build();
+    /// trainer.add_document("curated text");
+    /// trainer.add_weighted_document("crawled text", 0.25);
+    /// let profile = trainer.finish();
+    /// ```
+    pub fn add_weighted_document(&mut self, text: &str, weight: f64) {
+        self.stats.documents += 1;
+        if !(weight.is_finite() && weight > 0.0) {
+            self.stats.skipped_documents += 1;
+            return;
+        }
+        let mut budget = self.max_chars_per_document.unwrap_or(usize::MAX);
+        for sentence in split_sentences(text) {
+            if budget == 0 {
+                self.stats.truncated_documents += 1;
+                break;
+            }
+            if self.deduplicate && !self.seen.insert(sentence_fingerprint(sentence)) {
+                self.stats.duplicate_sentences += 1;
+                continue;
+            }
+            let length = sentence.chars().count();
+            if length > budget {
+                let end = sentence.char_indices().nth(budget).map_or(sentence.len(), |(i, _)| i);
+                self.train_sentence(&sentence[..end], weight);
+                self.stats.truncated_documents += 1;
+                break;
+            }
+            budget -= length;
+            self.train_sentence(sentence, weight);
+        }
+    }
+
+    /// Adds every file of a directory as a separate document.
+    ///
+    /// Files are read in name order; subdirectories are skipped and invalid UTF-8
+    /// sequences are replaced, so a few broken bytes do not abort a large corpus.
+    ///
+    /// # Arguments
+    /// * `dir` - Directory of plain-text files.
+    ///
+    /// # Returns
+    /// The number of files added.
+    ///
+    /// # Errors
+    /// Returns the I/O error if the directory or one of its files cannot be read.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use langdetect_rs::trainer::LangProfileTrainer;
+    ///
+    /// let mut trainer = LangProfileTrainer::new("kk").with_deduplication(true).build();
+    /// let files = trainer.add_directory("corpus/kk").unwrap();
+    /// println!("{} files, {:?}", files, trainer.stats());
+    /// let profile = trainer.finish();
+    /// ```
+    pub fn add_directory<P: AsRef<Path>>(&mut self, dir: P) -> std::io::Result<usize> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<_>>()?;
+        paths.retain(|path| path.is_file());
+        paths.sort();
+        for path in paths.iter() {
+            let bytes = fs::read(path)?;
+            self.add_document(&String::from_utf8_lossy(&bytes));
+        }
+        Ok(paths.len())
+    }
+
+    /// Adds the abstracts of a Wikipedia abstract dump as separate documents.
+    ///
+    /// Port of `GenProfile` of the original Java library: the text of every `<abstract>`
+    /// element of a `{lang}wiki-{date}-abstract.xml` dump is used, everything else
+    /// (titles, URLs, links) is ignored. Decompress gzip-compressed dumps before passing
+    /// them in, e.g. with `flate2::read::GzDecoder` wrapped in a `BufReader`.
+    ///
+    /// # Arguments
+    /// * `reader` - The XML dump.
+    ///
+    /// # Returns
+    /// The number of non-empty abstracts added.
+    ///
+    /// # Errors
+    /// Returns the I/O error if reading fails or the dump is not valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::trainer::LangProfileTrainer;
+    ///
+    /// let dump = "<feed><doc><title>Wikipedia: Test</title>\n\
+    ///     <abstract>Test &amp; more text.</abstract><links/></doc></feed>";
+    /// let mut trainer = LangProfileTrainer::new("en").build();
+    /// assert_eq!(trainer.add_wiki_abstracts(dump.as_bytes()).unwrap(), 1);
+    /// ```
+    pub fn add_wiki_abstracts<R: BufRead>(&mut self, reader: R) -> std::io::Result<usize> {
+        let mut count = 0;
+        let mut current: Option<String> = None;
+        for line in reader.lines() {
+            let line = line?;
+            let mut rest = line.as_str();
+            loop {
+                match current.as_mut() {
+                    Some(text) => match rest.find(ABSTRACT_END) {
+                        Some(end) => {
+                            text.push_str(&rest[..end]);
+                            rest = &rest[end + ABSTRACT_END.len()..];
+                            let text = unescape_xml(text);
+                            if !text.trim().is_empty() {
+                                self.add_document(&text);
+                                count += 1;
+                            }
+                            current = None;
+                        }
+                        None => {
+                            text.push_str(rest);
+                            text.push('\n');
+                            break;
+                        }
+                    },
+                    None => match find_abstract_start(rest) {
+                        Some((end, empty)) => {
+                            rest = &rest[end..];
+                            if !empty {
+                                current = Some(String::new());
+                            }
+                        }
+                        None => break,
+                    },
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Feeds a sentence and its augmented copies (if enabled) into the profile.
+    fn train_sentence(&mut self, sentence: &str, weight: f64) {
+        self.update(sentence, weight);
+        self.stats.sentences += 1;
+        if let Some(augmentation) = self.augmentation.take() {
+            for _ in 0..augmentation.copies {
+                let noisy = augmentation.apply(sentence, &mut self.rng);
+                self.update(&noisy, weight);
+                self.stats.augmented_sentences += 1;
+            }
+            self.augmentation = Some(augmentation);
+        }
+    }
+
+    /// Counts the n-grams of a text, each occurrence weighted by `weight`.
+    fn update(&mut self, text: &str, weight: f64) {
+        if weight == 1.0 {
+            self.profile.update(text);
+            return;
+        }
+        let text = NGram::normalize_vi(text);
+        let text = NGram::normalize_sea(&text);
+        let text = normalize_arabic(&text);
+        let text = match self.profile.normalization {
+            Normalization::Strict => Cow::Borrowed(text.as_ref()),
+            Normalization::Lenient => NGram::normalize_lenient(&text),
+        };
+        let mut gram = NGram::with_normalization(self.profile.normalization);
+        for ch in text.chars() {
+            gram.add_char(ch);
+            for n in 1..=NGram::N_GRAM {
+                if let Some(g) = gram.get_str(n) {
+                    self.add_weighted(g, weight);
+                }
+            }
+        }
+    }
+
+    /// Adds the whole part of a weighted n-gram occurrence to the profile and keeps the rest.
+    fn add_weighted(&mut self, gram: &str, weight: f64) {
+        let length = gram.chars().count();
+        if gram.is_empty() || length > NGram::N_GRAM {
+            return;
+        }
+        let residue = match self.residues.get_mut(gram) {
+            Some(residue) => residue,
+            None => self.residues.entry(gram.to_string()).or_insert(0.0),
+        };
+        *residue += weight;
+        let whole = residue.floor();
+        *residue -= whole;
+        if whole > 0.0 {
+            *self.profile.freq.entry(gram.to_string()).or_insert(0) += whole as usize;
+        }
+        let total = &mut self.n_words_residues[length - 1];
+        *total += weight;
+        let whole = total.floor();
+        *total -= whole;
+        self.profile.n_words[length - 1] += whole as usize;
+    }
+
+    /// Returns the counters collected so far.
+    pub fn stats(&self) -> &TrainerStats {
+        &self.stats
+    }
+
+    /// Returns the profile trained so far, without omitting rare n-grams.
+    pub fn profile(&self) -> &LangProfile {
+        &self.profile
+    }
+
+    /// Finds the language of the factory most confusable with the profile trained so far.
+    ///
+    /// The overlap with every language is the probability mass both distributions share,
+    /// averaged over n-gram lengths. For the closest language the n-grams with the largest
+    /// probability margin in favour of the trained profile are reported.
+    ///
+    /// # Arguments
+    /// * `factory` - Factory holding the existing languages. A language with the same name
+    ///   as the trained profile is ignored.
+    /// * `top_n` - Maximum number of discriminative n-grams to report.
+    ///
+    /// # Returns
+    /// The report, or None if the factory holds no other language.
+    pub fn hard_negatives(&self, factory: &DetectorFactory, top_n: usize) -> Option<HardNegativeReport> {
+        let name = self.profile.name.as_deref();
+        let probability = |gram: &str, count: usize| {
+            let total = self.profile.n_words[gram.chars().count() - 1];
+            if total == 0 { 0.0 } else { count as f64 / total as f64 }
+        };
+        let mut overlaps = vec![0.0; factory.langlist.len()];
+        for (gram, &count) in self.profile.freq.iter() {
+            let p = probability(gram, count);
+            if let Some(probs) = factory.profile_data.probabilities(gram) {
+                for (overlap, &q) in overlaps.iter_mut().zip(probs.iter()) {
+                    *overlap += p.min(q);
+                }
+            }
+        }
+        let (index, overlap) = overlaps
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| Some(factory.langlist[*i].as_str()) != name)
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))?;
+        let mut discriminative: Vec<(String, f64)> = self.profile.freq
+            .iter()
+            .filter_map(|(gram, &count)| {
+                let other = factory.profile_data.ngram_id(gram).map_or(0.0, |id| factory.profile_data.probability(id, index));
+                let margin = probability(gram, count) - other;
+                (margin > 0.0).then(|| (gram.clone(), margin))
+            })
+            .collect();
+        discriminative.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+        discriminative.truncate(top_n);
+        Some(HardNegativeReport {
+            confusable: factory.langlist[index].clone(),
+            overlap: *overlap / NGram::N_GRAM as f64,
+            discriminative,
+        })
+    }
+
+    /// Boosts the discriminative n-grams of a hard-negative report.
+    ///
+    /// The counts of the reported n-grams are multiplied by `factor`, which increases their
+    /// weight in the final profile and improves separation from the confusable language.
+    ///
+    /// # Arguments
+    /// * `report` - Report produced by `hard_negatives`.
+    /// * `factor` - Count multiplier; values below 2 leave the profile unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::detector_factory::DetectorFactory;
+    /// use langdetect_rs::trainer::LangProfileTrainer;
+    ///
+    /// let factory = DetectorFactory::default().build();
+    /// let mut trainer = LangProfileTrainer::new("xx").build();
+    /// trainer.add_document("Dies ist ein kleiner Text in einer neuen Sprache.");
+    /// if let Some(report) = trainer.hard_negatives(&factory, 20) {
+    ///     println!("closest language: {} ({:.2})", report.confusable, report.overlap);
+    ///     trainer.boost_discriminative(&report, 2);
+    /// }
+    /// ```
+    pub fn boost_discriminative(&mut self, report: &HardNegativeReport, factor: usize) {
+        if factor < 2 {
+            return;
+        }
+        for (gram, _) in report.discriminative.iter() {
+            if let Some(count) = self.profile.freq.get_mut(gram) {
+                let extra = *count * (factor - 1);
+                *count += extra;
+                self.profile.n_words[gram.chars().count() - 1] += extra;
+            }
+        }
+    }
+
+    /// Finishes training: rounds weighted counts, omits less frequent n-grams and returns the profile.
+    ///
+    /// # Returns
+    /// The trained LangProfile.
+    pub fn finish(mut self) -> LangProfile {
+        for (gram, residue) in self.residues.drain() {
+            if residue >= 0.5 {
+                *self.profile.freq.entry(gram).or_insert(0) += 1;
+            }
+        }
+        for (total, residue) in self.profile.n_words.iter_mut().zip(self.n_words_residues.iter()) {
+            if *residue >= 0.5 {
+                *total += 1;
+            }
+        }
+        self.profile.omit_less_freq_with(self.less_freq_ratio, self.minimum_freq);
+        self.profile
+    }
+}
+
+/// Builder for `LangProfileTrainer` with fluent setters.
+pub struct LangProfileTrainerBuilder {
+    trainer: LangProfileTrainer,
+}
+
+impl LangProfileTrainerBuilder {
+    /// Enables or disables skipping of near-identical sentences.
+    ///
+    /// Sentences are compared after lowercasing and dropping everything but letters,
+    /// so differences in punctuation, digits or spacing do not make them distinct.
+    ///
+    /// # Arguments
+    /// * `deduplicate` - Whether to skip near-duplicate sentences.
+    pub fn with_deduplication(mut self, deduplicate: bool) -> Self {
+        self.trainer.deduplicate = deduplicate;
+        self
+    }
+
+    /// Sets the maximum number of characters a single document may contribute.
+    ///
+    /// # Arguments
+    /// * `max_chars` - The cap, or None for no limit.
+    pub fn with_max_chars_per_document(mut self, max_chars: Option<usize>) -> Self {
+        self.trainer.max_chars_per_document = max_chars;
+        self
+    }
+
+    /// Enables noise injection augmentation.
+    ///
+    /// # Arguments
+    /// * `augmentation` - Augmentation settings, or None to train on the corpus as-is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::trainer::{Augmentation, LangProfileTrainer};
+    ///
+    /// let trainer = LangProfileTrainer::new("fr")
+    ///     .with_augmentation(Some(Augmentation { seed: Some(42), ..Augmentation::default() }))
+    ///     .build();
+    /// ```
+    pub fn with_augmentation(mut self, augmentation: Option<Augmentation>) -> Self {
+        if let Some(seed) = augmentation.as_ref().and_then(|a| a.seed) {
+            self.trainer.rng = StdRng::seed_from_u64(seed);
+        }
+        self.trainer.augmentation = augmentation;
+        self
+    }
+
+    /// Sets the thresholds `finish()` uses to omit less frequent n-grams.
+    ///
+    /// See `LangProfile::omit_less_freq_with`. Small corpora usually need a lower
+    /// `minimum_freq` to keep enough n-grams.
+    ///
+    /// # Arguments
+    /// * `less_freq_ratio` - Divisor of the unigram total giving the relative threshold (default: 100000).
+    /// * `minimum_freq` - Absolute threshold (default: 2).
+    pub fn with_omit_thresholds(mut self, less_freq_ratio: usize, minimum_freq: usize) -> Self {
+        self.trainer.less_freq_ratio = less_freq_ratio;
+        self.trainer.minimum_freq = minimum_freq;
+        self
+    }
+
+    /// Sets the character normalization of n-gram extraction (default: `Normalization::Strict`).
+    ///
+    /// The normalization is recorded in the profile; detect with the same one.
+    ///
+    /// # Arguments
+    /// * `normalization` - The normalization rules, see `LangProfileBuilder::with_normalization`.
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.trainer.profile.normalization = normalization;
+        self
+    }
+
+    /// Builds the final LangProfileTrainer with the configured properties.
+    pub fn build(self) -> LangProfileTrainer {
+        self.trainer
+    }
+}
+
+/// Splits text into sentences on terminal punctuation and line breaks.
+fn split_sentences(text: &str) -> impl Iterator<Item = &str> {
+    text.split(['.', '!', '?', '\n', '\u{3002}', '\u{FF01}', '\u{FF1F}', '\u{0964}'])
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// Closing tag of an abstract in Wikipedia abstract dumps.
+const ABSTRACT_END: &str = "</abstract>";
+
+/// Finds the next `<abstract>` opening tag.
+///
+/// Returns the byte offset after the tag and whether it is self-closing (`<abstract />`).
+fn find_abstract_start(text: &str) -> Option<(usize, bool)> {
+    let mut offset = 0;
+    while let Some(start) = text[offset..].find("<abstract") {
+        let name_end = offset + start + "<abstract".len();
+        // Skip other elements with the same prefix, e.g. <abstracts>
+        if text[name_end..].starts_with(|c: char| c == '>' || c == '/' || c.is_whitespace()) {
+            let close = name_end + text[name_end..].find('>')?;
+            return Some((close + 1, text[..close].ends_with('/')));
+        }
+        offset = name_end;
+    }
+    None
+}
+
+/// Replaces the predefined XML entities and numeric character references.
+fn unescape_xml(text: &str) -> Cow<'_, str> {
+    if !text.contains('&') {
+        return Cow::Borrowed(text);
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest.find(';').map(|end| &rest[1..end]);
+        let decoded = entity.and_then(|entity| match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                .and_then(|code| code.ok())
+                .and_then(char::from_u32),
+        });
+        match (entity, decoded) {
+            (Some(entity), Some(ch)) => {
+                result.push(ch);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    Cow::Owned(result)
+}
+
+/// Maps a Latin letter with diacritics onto its base letter.
+fn strip_accent(ch: char) -> char {
+    match ch {
+        '\u{00C0}'..='\u{00C5}' | '\u{0100}' | '\u{0102}' | '\u{0104}' => 'A',
+        '\u{00E0}'..='\u{00E5}' | '\u{0101}' | '\u{0103}' | '\u{0105}' => 'a',
+        '\u{00C7}' | '\u{0106}' | '\u{0108}' | '\u{010A}' | '\u{010C}' => 'C',
+        '\u{00E7}' | '\u{0107}' | '\u{0109}' | '\u{010B}' | '\u{010D}' => 'c',
+        '\u{010E}' | '\u{0110}' => 'D',
+        '\u{010F}' | '\u{0111}' => 'd',
+        '\u{00C8}'..='\u{00CB}' | '\u{0112}' | '\u{0114}' | '\u{0116}' | '\u{0118}' | '\u{011A}' => 'E',
+        '\u{00E8}'..='\u{00EB}' | '\u{0113}' | '\u{0115}' | '\u{0117}' | '\u{0119}' | '\u{011B}' => 'e',
+        '\u{011C}' | '\u{011E}' | '\u{0120}' | '\u{0122}' => 'G',
+        '\u{011D}' | '\u{011F}' | '\u{0121}' | '\u{0123}' => 'g',
+        '\u{00CC}'..='\u{00CF}' | '\u{0128}' | '\u{012A}' | '\u{012C}' | '\u{012E}' | '\u{0130}' => 'I',
+        '\u{00EC}'..='\u{00EF}' | '\u{0129}' | '\u{012B}' | '\u{012D}' | '\u{012F}' | '\u{0131}' => 'i',
+        '\u{0139}' | '\u{013B}' | '\u{013D}' | '\u{0141}' => 'L',
+        '\u{013A}' | '\u{013C}' | '\u{013E}' | '\u{0142}' => 'l',
+        '\u{00D1}' | '\u{0143}' | '\u{0145}' | '\u{0147}' => 'N',
+        '\u{00F1}' | '\u{0144}' | '\u{0146}' | '\u{0148}' => 'n',
+        '\u{00D2}'..='\u{00D6}' | '\u{00D8}' | '\u{014C}' | '\u{014E}' | '\u{0150}' => 'O',
+        '\u{00F2}'..='\u{00F6}' | '\u{00F8}' | '\u{014D}' | '\u{014F}' | '\u{0151}' => 'o',
+        '\u{0154}' | '\u{0156}' | '\u{0158}' => 'R',
+        '\u{0155}' | '\u{0157}' | '\u{0159}' => 'r',
+        '\u{015A}' | '\u{015C}' | '\u{015E}' | '\u{0160}' | '\u{0218}' => 'S',
+        '\u{015B}' | '\u{015D}' | '\u{015F}' | '\u{0161}' | '\u{0219}' => 's',
+        '\u{0162}' | '\u{0164}' | '\u{021A}' => 'T',
+        '\u{0163}' | '\u{0165}' | '\u{021B}' => 't',
+        '\u{00D9}'..='\u{00DC}' | '\u{0168}' | '\u{016A}' | '\u{016C}' | '\u{016E}' | '\u{0170}' | '\u{0172}' => 'U',
+        '\u{00F9}'..='\u{00FC}' | '\u{0169}' | '\u{016B}' | '\u{016D}' | '\u{016F}' | '\u{0171}' | '\u{0173}' => 'u',
+        '\u{00DD}' | '\u{0176}' | '\u{0178}' => 'Y',
+        '\u{00FD}' | '\u{00FF}' | '\u{0177}' => 'y',
+        '\u{0179}' | '\u{017B}' | '\u{017D}' => 'Z',
+        '\u{017A}' | '\u{017C}' | '\u{017E}' => 'z',
+        _ => ch,
+    }
+}
+
+/// Hashes the letters of a sentence, ignoring case, digits, punctuation and spacing.
+fn sentence_fingerprint(sentence: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for ch in sentence.chars().filter(|c| c.is_alphabetic()) {
+        for lower in ch.to_lowercase() {
+            lower.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deduplication() {
+        let mut trainer = LangProfileTrainer::new("en").with_deduplication(true).build();
+        trainer.add_document("Cookie policy. Some text here.");
+        trainer.add_document("COOKIE  policy!! Other text here.");
+        let stats = trainer.stats();
+        assert_eq!(stats.documents, 2);
+        assert_eq!(stats.sentences, 3);
+        assert_eq!(stats.duplicate_sentences, 1);
+
+        let mut trainer = LangProfileTrainer::new("en").build();
+        trainer.add_document("Cookie policy. Some text here.");
+        trainer.add_document("COOKIE  policy!! Other text here.");
+        assert_eq!(trainer.stats().sentences, 4);
+    }
+
+    #[test]
+    fn test_max_chars_per_document() {
+        let mut trainer = LangProfileTrainer::new("en").with_max_chars_per_document(Some(5)).build();
+        trainer.add_document("abcdefgh. ijk.");
+        assert_eq!(trainer.stats().truncated_documents, 1);
+        // Only "abcde" is used
+        assert_eq!(trainer.profile().n_words[0], 5);
+        assert_eq!(trainer.profile().freq.get("f"), None);
+    }
+
+    #[test]
+    fn test_weighted_document() {
+        let mut trainer = LangProfileTrainer::new("en").build();
+        trainer.add_weighted_document("ab", 0.4);
+        // Fractional counts are held back until they add up
+        assert_eq!(trainer.profile().freq.get("a"), None);
+        trainer.add_weighted_document("ab", 0.4);
+        trainer.add_weighted_document("ab", 0.4);
+        assert_eq!(trainer.profile().freq.get("a"), Some(&1));
+        trainer.add_weighted_document("ab", 2.0);
+        trainer.add_weighted_document("ab", f64::NAN);
+        trainer.add_weighted_document("ab", 0.0);
+        assert_eq!(trainer.profile().freq.get("a"), Some(&3));
+        assert_eq!(trainer.stats().documents, 6);
+        assert_eq!(trainer.stats().skipped_documents, 2);
+
+        let mut trainer = LangProfileTrainer::new("en").build();
+        for _ in 0..4 {
+            trainer.add_weighted_document("aaa bbb", 0.5);
+        }
+        trainer.add_weighted_document("aaa", 0.7);
+        let profile = trainer.finish();
+        // 4 * 3 * 0.5 + 3 * 0.7 = 8.1
+        assert_eq!(profile.freq.get("a"), Some(&8));
+        assert_eq!(profile.freq.get("b"), Some(&6));
+
+        // Weighted documents are normalized like unweighted ones
+        let mut trainer = LangProfileTrainer::new("ar").build();
+        trainer.add_weighted_document("\u{0639}\u{0640}\u{0631}", 2.0);
+        assert_eq!(trainer.profile().freq.get("\u{0640}"), None);
+        assert_eq!(trainer.profile().freq.get("\u{0639}\u{0631}"), Some(&2));
+
+        // And with the normalization of the trainer
+        let mut trainer = LangProfileTrainer::new("fr").with_normalization(Normalization::Lenient).build();
+        trainer.add_weighted_document("d'h", 2.0);
+        trainer.add_document("d'h");
+        assert_eq!(trainer.profile().freq.get("d'h"), Some(&3));
+        assert_eq!(trainer.finish().normalization, Normalization::Lenient);
+    }
+
+    #[test]
+    fn test_finish() {
+        let mut trainer = LangProfileTrainer::new("en").build();
+        for _ in 0..5 {
+            trainer.add_document("aaa bbb");
+        }
+        let profile = trainer.finish();
+        assert_eq!(profile.name.as_deref(), Some("en"));
+        assert_eq!(profile.freq.get("a"), Some(&15));
+    }
+
+    #[test]
+    fn test_add_directory() {
+        let dir = std::env::temp_dir().join(format!("langdetect-trainer-{}", std::process::id()));
+        fs::create_dir_all(dir.join("nested")).unwrap();
+        fs::write(dir.join("a.txt"), "aaa bbb").unwrap();
+        fs::write(dir.join("b.txt"), b"aaa \xFF").unwrap();
+        fs::write(dir.join("nested").join("c.txt"), "ccc").unwrap();
+        let mut trainer = LangProfileTrainer::new("en").with_omit_thresholds(100_000, 0).build();
+        assert_eq!(trainer.add_directory(&dir).unwrap(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(trainer.stats().documents, 2);
+        let profile = trainer.finish();
+        assert_eq!(profile.freq.get("a"), Some(&6));
+        // Kept with a minimum frequency of 0
+        assert_eq!(profile.freq.get("b"), Some(&3));
+        assert_eq!(profile.freq.get("c"), None);
+        assert!(LangProfileTrainer::new("en").build().add_directory(dir).is_err());
+    }
+
+    #[test]
+    fn test_wiki_abstracts() {
+        let dump = "<feed>\n<doc>\n<title>Wikipedia: A</title>\n<abstract>aaa &amp; &#98;bb</abstract>\n\
+            <abstract />\n<abstracts>ccc</abstracts>\n<abstract xml:space=\"preserve\">ddd\neee</abstract><abstract></abstract>\n</doc>\n</feed>\n";
+        let mut trainer = LangProfileTrainer::new("en").build();
+        assert_eq!(trainer.add_wiki_abstracts(dump.as_bytes()).unwrap(), 2);
+        assert_eq!(trainer.stats().documents, 2);
+        let profile = trainer.profile();
+        assert_eq!(profile.freq.get("a"), Some(&3));
+        assert_eq!(profile.freq.get("b"), Some(&3));
+        assert_eq!(profile.freq.get("c"), None);
+        assert_eq!(profile.freq.get("e"), Some(&3));
+        assert_eq!(unescape_xml("&lt;x&gt; &unknown; & &#x41;"), "<x> &unknown; & A");
+    }
+
+    #[test]
+    fn test_strip_accent() {
+        let stripped: String = "Fran\u{00E7}ais \u{010D}e\u{0161}tina \u{00D8}re".chars().map(strip_accent).collect();
+        assert_eq!(stripped, "Francais cestina Ore");
+    }
+
+    #[test]
+    fn test_augmentation() {
+        let augmentation = Augmentation {
+            copies: 2,
+            accent_stripping: 1.0,
+            case_change: 0.0,
+            typo_rate: 0.0,
+            seed: Some(1),
+        };
+        let mut trainer = LangProfileTrainer::new("fr").with_augmentation(Some(augmentation)).build();
+        trainer.add_document("\u{00E9}t\u{00E9}");
+        assert_eq!(trainer.stats().sentences, 1);
+        assert_eq!(trainer.stats().augmented_sentences, 2);
+        assert_eq!(trainer.profile().freq.get("\u{00E9}"), Some(&2));
+        assert_eq!(trainer.profile().freq.get("e"), Some(&4));
+    }
+
+    #[test]
+    fn test_augmentation_is_reproducible() {
+        let make = || {
+            let augmentation = Augmentation { typo_rate: 0.5, seed: Some(7), ..Augmentation::default() };
+            let mut trainer = LangProfileTrainer::new("en").with_augmentation(Some(augmentation)).build();
+            trainer.add_document("The quick brown fox jumps over the lazy dog.");
+            trainer.profile().freq.clone()
+        };
+        assert_eq!(make(), make());
+    }
+
+    #[test]
+    fn test_hard_negatives() {
+        let mut factory = DetectorFactory::new().build();
+        let mut en = LangProfile::new().with_name("en").build();
+        en.update("abcabcabc");
+        let mut ja = LangProfile::new().with_name("ja").build();
+        ja.update("\u{3042}\u{3044}\u{3046}");
+        factory.add_profile(en, 0, 2).unwrap();
+        factory.add_profile(ja, 1, 2).unwrap();
+
+        let mut trainer = LangProfileTrainer::new("xx").build();
+        trainer.add_document("abcabd");
+        let report = trainer.hard_negatives(&factory, 3).unwrap();
+        assert_eq!(report.confusable, "en");
+        assert!(report.overlap > 0.0 && report.overlap <= 1.0);
+        assert_eq!(report.discriminative.len(), 3);
+        assert!(report.discriminative.iter().any(|(gram, _)| gram == "d"));
+
+        let before = trainer.profile().freq["d"];
+        trainer.boost_discriminative(&report, 3);
+        assert_eq!(trainer.profile().freq["d"], before * 3);
+    }
+
+    #[test]
+    fn test_hard_negatives_empty_factory() {
+        let factory = DetectorFactory::new().build();
+        let mut trainer = LangProfileTrainer::new("xx").build();
+        trainer.add_document("abc");
+        assert!(trainer.hard_negatives(&factory, 3).is_none());
+    }
+}
@@ -0,0 +1,85 @@
+use std::borrow::Cow;
+
+use crate::language::Lang;
+
+/// Languages the letter markers tell apart.
+pub const MARKER_LANGUAGES: [&str; 4] = ["ar", "fa", "ur", "ps"];
+
+/// Probability of a marker letter for the languages writing it.
+pub const MARKER_PROB: f64 = 0.05;
+
+/// Factor applied to `MARKER_PROB` for the languages of `MARKER_LANGUAGES` which do not write a marker.
+pub const MARKER_FOREIGN_RATIO: f64 = 0.01;
+
+/// Letters whose distinction is lost by n-gram normalization, with the languages writing them.
+///
+/// Arabic yeh and Farsi yeh share one n-gram character, and ZWNJ is a word boundary.
+const MARKERS: [(char, &[&str]); 3] = [
+    ('\u{064A}', &["ar", "ps"]),
+    ('\u{06CC}', &["fa", "ur", "ps"]),
+    ('\u{200C}', &["fa", "ps"]),
+];
+
+/// Returns the languages writing a marker letter, or None if the character is no marker.
+///
+/// # Arguments
+/// * `ch` - The character of the (non-normalized) text.
+pub fn marker_writers(ch: char) -> Option<&'static [&'static str]> {
+    MARKERS.iter().find(|(marker, _)| *marker == ch).map(|(_, writers)| *writers)
+}
+
+/// Builds the probability row of a marker letter, in `langlist` order.
+///
+/// Languages of `MARKER_LANGUAGES` which do not write the letter get a lower probability;
+/// other languages are not affected by markers.
+///
+/// # Arguments
+/// * `writers` - The languages writing the letter, see `marker_writers`.
+/// * `langlist` - The loaded language codes.
+pub fn marker_row(writers: &[&str], langlist: &[Lang]) -> Vec<f64> {
+    langlist.iter().map(|lang| {
+        if MARKER_LANGUAGES.contains(&lang.as_str()) && !writers.contains(&lang.as_str()) {
+            MARKER_PROB * MARKER_FOREIGN_RATIO
+        } else {
+            MARKER_PROB
+        }
+    }).collect()
+}
+
+/// Removes the characters of Arabic-script text which only affect rendering.
+///
+/// Tatweel (kashida) stretches words and ZWJ forces a joining form; neither is part of
+/// the spelling. ZWNJ is kept: it separates word parts in Persian and is treated as a word
+/// boundary by n-gram extraction, as during training.
+///
+/// # Arguments
+/// * `input` - The text to normalize.
+///
+/// # Returns
+/// Normalized text; the input itself if it contains no such characters.
+pub fn normalize_arabic(input: &str) -> Cow<'_, str> {
+    if !input.contains(['\u{0640}', '\u{200D}']) {
+        return Cow::Borrowed(input);
+    }
+    Cow::Owned(input.chars().filter(|c| !matches!(c, '\u{0640}' | '\u{200D}')).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markers() {
+        assert!(matches!(normalize_arabic("hello"), Cow::Borrowed(_)));
+        assert_eq!(normalize_arabic("\u{0639}\u{0640}\u{0640}\u{0631}\u{0628}\u{064A}"), "\u{0639}\u{0631}\u{0628}\u{064A}");
+        assert_eq!(normalize_arabic("\u{0645}\u{06CC}\u{200C}\u{0631}\u{200D}"), "\u{0645}\u{06CC}\u{200C}\u{0631}");
+
+        assert_eq!(marker_writers('\u{0628}'), None);
+        let langlist = vec![Lang::En, Lang::Ar, Lang::Fa, Lang::Ur];
+        let row = marker_row(marker_writers('\u{064A}').unwrap(), &langlist);
+        assert_eq!(row, vec![MARKER_PROB, MARKER_PROB, MARKER_PROB * MARKER_FOREIGN_RATIO, MARKER_PROB * MARKER_FOREIGN_RATIO]);
+        let row = marker_row(marker_writers('\u{06CC}').unwrap(), &langlist);
+        assert_eq!(row[1], MARKER_PROB * MARKER_FOREIGN_RATIO);
+        assert_eq!(row[2], MARKER_PROB);
+    }
+}
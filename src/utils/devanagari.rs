@@ -0,0 +1,73 @@
+/// Languages the tie-breaker chooses between, in the order of `function_word_scores`.
+pub const DEVANAGARI_LANGUAGES: [&str; 3] = ["hi", "mr", "ne"];
+
+/// Frequent words used by only one of the languages, in the order of `DEVANAGARI_LANGUAGES`.
+///
+/// Words shared by two of the languages (e.g. "को", "के", "तो") are left out.
+const FUNCTION_WORDS: [&[&str]; 3] = [
+    &[
+        "है", "हैं", "था", "थी", "थे", "में", "नहीं", "और", "यह", "वह", "मुझे", "मैं", "हूँ", "हूं", "से",
+        "क्या", "कि", "लिए", "बहुत", "रहा", "रही", "रहे", "गया", "गई", "चाहिए", "कैसे", "किया", "भी",
+    ],
+    &[
+        "आहे", "आहेत", "आहात", "आणि", "नाही", "नाहीत", "मी", "मला", "तुम्ही", "आम्ही", "होते", "होता",
+        "होती", "झाले", "काय", "खूप", "माझे", "माझा", "माझी", "आता", "पण", "येथे", "साठी", "मध्ये", "केले", "छान",
+    ],
+    &[
+        "छ", "छन्", "छु", "छौं", "छैन", "हो", "हुन्छ", "हुनुहुन्छ", "थियो", "थिए", "भयो", "गर्न", "गरेको", "भएको",
+        "पनि", "र", "म", "मलाई", "तपाईं", "हामी", "यो", "त्यो", "धेरै", "राम्रो", "मेरो", "उसको",
+    ],
+];
+
+/// Counts the function words of each language in a text.
+///
+/// The letter LLA (ळ), which Hindi and Nepali do not use, counts as a Marathi word.
+///
+/// # Arguments
+/// * `text` - The text to analyze.
+///
+/// # Returns
+/// The counts in the order of `DEVANAGARI_LANGUAGES`.
+pub fn function_word_scores(text: &str) -> [usize; 3] {
+    let mut scores = [0; 3];
+    for word in text.split(|c: char| c.is_whitespace() || c.is_ascii_punctuation() || matches!(c, '\u{0964}' | '\u{0965}')) {
+        for (score, words) in scores.iter_mut().zip(FUNCTION_WORDS.iter()) {
+            if words.contains(&word) {
+                *score += 1;
+            }
+        }
+    }
+    scores[1] += text.matches('\u{0933}').count();
+    scores
+}
+
+/// Returns the language with the most function words in a text.
+///
+/// # Arguments
+/// * `text` - The text to analyze.
+///
+/// # Returns
+/// The language code, or None if no language has more function words than the others.
+pub fn preferred_language(text: &str) -> Option<&'static str> {
+    let scores = function_word_scores(text);
+    let max = *scores.iter().max()?;
+    if max == 0 || scores.iter().filter(|s| **s == max).count() > 1 {
+        return None;
+    }
+    scores.iter().position(|s| *s == max).map(|index| DEVANAGARI_LANGUAGES[index])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_function_words() {
+        assert_eq!(function_word_scores("मुझे पानी चाहिए।"), [2, 0, 0]);
+        assert_eq!(function_word_scores("भारत हा एक मोठा देश आहे, खूप छान"), [0, 3, 0]);
+        assert_eq!(function_word_scores("ळ"), [0, 1, 0]);
+        assert_eq!(preferred_language("के भयो?"), Some("ne"));
+        assert_eq!(preferred_language("पानी"), None);
+        assert_eq!(preferred_language("है छ"), None);
+    }
+}
@@ -0,0 +1,103 @@
+/// Header names which mark the leading block of a message as a header block.
+const KNOWN_HEADERS: [&str; 10] = [
+    "from", "to", "cc", "subject", "date", "received", "message-id", "return-path", "mime-version", "reply-to",
+];
+
+/// Extracts the newly written content of a raw email message.
+///
+/// Removes the header block (if the message starts with one), quoted reply lines
+/// (starting with `>`) together with their attribution line (e.g. "On Mon, John wrote:"),
+/// and everything after a signature delimiter (`-- `) or a forwarded/original message marker.
+///
+/// # Arguments
+/// * `raw` - The raw message text.
+///
+/// # Returns
+/// The remaining body text.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::utils::email::strip_email;
+///
+/// let raw = "From: a@example.com\nSubject: Re: hello\n\nMerci beaucoup !\n\nOn Mon, Bob wrote:\n> Thank you!\n-- \nAlice";
+/// assert_eq!(strip_email(raw), "Merci beaucoup !");
+/// ```
+pub fn strip_email(raw: &str) -> String {
+    let lines: Vec<&str> = raw.lines().collect();
+    let body_start = header_block_end(&lines).unwrap_or(0);
+    let mut kept: Vec<&str> = Vec::new();
+    for (i, line) in lines.iter().enumerate().skip(body_start) {
+        if is_end_marker(line) {
+            break;
+        }
+        if is_quoted(line) {
+            continue;
+        }
+        // Attribution line introducing a quoted block
+        let next_quoted = lines[i + 1..].iter().find(|l| !l.trim().is_empty()).is_some_and(|l| is_quoted(l));
+        if next_quoted && line.trim_end().ends_with(':') {
+            continue;
+        }
+        kept.push(line);
+    }
+    kept.join("\n").trim().to_string()
+}
+
+/// Returns the index of the first body line if the message starts with a header block.
+fn header_block_end(lines: &[&str]) -> Option<usize> {
+    let mut known = false;
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim().is_empty() {
+            return if known && i > 0 { Some(i + 1) } else { None };
+        }
+        if line.starts_with([' ', '\t']) && i > 0 {
+            // Folded header continuation
+            continue;
+        }
+        let name = line.split_once(':').map(|(name, _)| name)?;
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return None;
+        }
+        known |= KNOWN_HEADERS.contains(&name.to_ascii_lowercase().as_str());
+    }
+    None
+}
+
+/// Checks whether a line is part of a quoted reply.
+fn is_quoted(line: &str) -> bool {
+    line.trim_start().starts_with('>')
+}
+
+/// Checks whether a line starts the signature or a forwarded/original message.
+fn is_end_marker(line: &str) -> bool {
+    let trimmed = line.trim();
+    line.trim_end_matches(['\r']) == "-- "
+        || trimmed == "--"
+        || (trimmed.starts_with("-----") && trimmed.to_ascii_lowercase().contains("message"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_headers() {
+        let raw = "Received: from mx\n\tby example.com\nFrom: a@example.com\nSubject: Test\n\nBody text";
+        assert_eq!(strip_email(raw), "Body text");
+        // Not a header block: no known header names
+        assert_eq!(strip_email("Note: keep this\n\nBody"), "Note: keep this\n\nBody");
+    }
+
+    #[test]
+    fn test_strip_quotes_and_signature() {
+        let raw = "Hola, nos vemos mañana.\n\nEl lunes, Ana escribió:\n> See you tomorrow\n>> Older\nPS: hasta luego\n-- \nJuan\n";
+        assert_eq!(strip_email(raw), "Hola, nos vemos mañana.\n\nPS: hasta luego");
+    }
+
+    #[test]
+    fn test_strip_original_message() {
+        let raw = "Danke!\n-----Original Message-----\nFrom: b@example.com\nThanks!";
+        assert_eq!(strip_email(raw), "Danke!");
+    }
+}
@@ -0,0 +1,216 @@
+use crate::language::Lang;
+
+/// Codes of the built-in languages: (profile name, ISO 639-3, BCP-47 tag).
+///
+/// Profile names are ISO 639-1 codes, except for the two Chinese profiles, which are
+/// tagged by script. Macrolanguages use their macrolanguage code ("zho", "nor", "fas").
+const CODES: [(&str, &str, &str); 55] = [
+    ("af", "afr", "af"),
+    ("ar", "ara", "ar"),
+    ("bg", "bul", "bg"),
+    ("bn", "ben", "bn"),
+    ("ca", "cat", "ca"),
+    ("cs", "ces", "cs"),
+    ("cy", "cym", "cy"),
+    ("da", "dan", "da"),
+    ("de", "deu", "de"),
+    ("el", "ell", "el"),
+    ("en", "eng", "en"),
+    ("es", "spa", "es"),
+    ("et", "est", "et"),
+    ("fa", "fas", "fa"),
+    ("fi", "fin", "fi"),
+    ("fr", "fra", "fr"),
+    ("gu", "guj", "gu"),
+    ("he", "heb", "he"),
+    ("hi", "hin", "hi"),
+    ("hr", "hrv", "hr"),
+    ("hu", "hun", "hu"),
+    ("id", "ind", "id"),
+    ("it", "ita", "it"),
+    ("ja", "jpn", "ja"),
+    ("kn", "kan", "kn"),
+    ("ko", "kor", "ko"),
+    ("lt", "lit", "lt"),
+    ("lv", "lav", "lv"),
+    ("mk", "mkd", "mk"),
+    ("ml", "mal", "ml"),
+    ("mr", "mar", "mr"),
+    ("ne", "nep", "ne"),
+    ("nl", "nld", "nl"),
+    ("no", "nor", "no"),
+    ("pa", "pan", "pa"),
+    ("pl", "pol", "pl"),
+    ("pt", "por", "pt"),
+    ("ro", "ron", "ro"),
+    ("ru", "rus", "ru"),
+    ("sk", "slk", "sk"),
+    ("sl", "slv", "sl"),
+    ("so", "som", "so"),
+    ("sq", "sqi", "sq"),
+    ("sv", "swe", "sv"),
+    ("sw", "swa", "sw"),
+    ("ta", "tam", "ta"),
+    ("te", "tel", "te"),
+    ("th", "tha", "th"),
+    ("tl", "tgl", "tl"),
+    ("tr", "tur", "tr"),
+    ("uk", "ukr", "uk"),
+    ("ur", "urd", "ur"),
+    ("vi", "vie", "vi"),
+    ("zh-cn", "zho", "zh-Hans"),
+    ("zh-tw", "zho", "zh-Hant"),
+];
+
+/// ISO 639-3 and legacy codes of built-in languages which are not in `CODES`.
+const ISO639_3_ALIASES: [(&str, &str); 9] = [
+    ("cmn", "zh-cn"),
+    ("chi", "zh-cn"),
+    ("nob", "no"),
+    ("nno", "no"),
+    ("pes", "fa"),
+    ("per", "fa"),
+    ("fil", "tl"),
+    ("swh", "sw"),
+    ("als", "sq"),
+];
+
+/// BCP-47 primary subtags of built-in languages which differ from the profile name.
+const BCP47_ALIASES: [(&str, &str); 6] = [
+    ("nb", "no"),
+    ("nn", "no"),
+    ("fil", "tl"),
+    ("iw", "he"),
+    ("in", "id"),
+    ("cmn", "zh-cn"),
+];
+
+/// Returns the ISO 639-1 code of a profile, e.g. "zh" for "zh-cn".
+///
+/// # Arguments
+/// * `code` - Profile name of a built-in language, e.g. a detection result.
+///
+/// # Returns
+/// The two-letter code, or None for languages without a built-in profile.
+pub fn to_iso639_1(code: &str) -> Option<&'static str> {
+    let (profile, _, _) = CODES.iter().find(|(profile, _, _)| *profile == code)?;
+    Some(profile.split('-').next().unwrap_or(profile))
+}
+
+/// Returns the ISO 639-3 code of a profile, e.g. "eng" for "en".
+///
+/// # Arguments
+/// * `code` - Profile name of a built-in language, e.g. a detection result.
+///
+/// # Returns
+/// The three-letter code, or None for languages without a built-in profile.
+///
+/// # Examples
+/// ```
+/// use langdetect_rs::utils::iso::to_iso639_3;
+/// assert_eq!(to_iso639_3("de"), Some("deu"));
+/// assert_eq!(to_iso639_3("zh-tw"), Some("zho"));
+/// ```
+pub fn to_iso639_3(code: &str) -> Option<&'static str> {
+    CODES.iter().find(|(profile, _, _)| *profile == code).map(|(_, iso639_3, _)| *iso639_3)
+}
+
+/// Returns the profile name of an ISO 639-3 code, e.g. "en" for "eng".
+///
+/// Also accepts individual language codes of the macrolanguages ("cmn", "nob", "pes", ...).
+/// Chinese ("zho") maps to the Simplified Chinese profile.
+///
+/// # Arguments
+/// * `code` - ISO 639-3 code, case-insensitive.
+///
+/// # Returns
+/// The profile name, or None if no built-in profile covers the language.
+pub fn from_iso639_3(code: &str) -> Option<&'static str> {
+    let code = code.to_ascii_lowercase();
+    CODES.iter().find(|(_, iso639_3, _)| *iso639_3 == code).map(|(profile, _, _)| *profile)
+        .or_else(|| ISO639_3_ALIASES.iter().find(|(alias, _)| *alias == code).map(|(_, profile)| *profile))
+}
+
+/// Returns the BCP-47 tag of a profile, e.g. "zh-Hant" for "zh-tw".
+///
+/// # Arguments
+/// * `code` - Profile name of a built-in language, e.g. a detection result.
+///
+/// # Returns
+/// The language tag, or None for languages without a built-in profile.
+pub fn to_bcp47(code: &str) -> Option<&'static str> {
+    CODES.iter().find(|(profile, _, _)| *profile == code).map(|(_, _, bcp47)| *bcp47)
+}
+
+/// Returns the profile name matching a BCP-47 tag, e.g. "pt" for "pt-BR".
+///
+/// The tag is matched by its primary language subtag, case-insensitively. Chinese tags
+/// are told apart by script or region: "zh-Hant", "zh-TW", "zh-HK" and "zh-MO" map to
+/// "zh-tw", any other Chinese tag to "zh-cn". Legacy and individual language subtags
+/// ("nb", "iw", "fil", ...) map to the covering profile.
+///
+/// # Arguments
+/// * `tag` - BCP-47 language tag, e.g. from an `Accept-Language` header.
+///
+/// # Returns
+/// The profile name, or None if no built-in profile covers the language.
+///
+/// # Examples
+/// ```
+/// use langdetect_rs::utils::iso::from_bcp47;
+/// assert_eq!(from_bcp47("en-US"), Some("en"));
+/// assert_eq!(from_bcp47("zh-Hant-HK"), Some("zh-tw"));
+/// assert_eq!(from_bcp47("nb-NO"), Some("no"));
+/// assert_eq!(from_bcp47("tlh"), None);
+/// ```
+pub fn from_bcp47(tag: &str) -> Option<&'static str> {
+    let tag = tag.to_ascii_lowercase().replace('_', "-");
+    let mut subtags = tag.split('-');
+    let primary = subtags.next()?;
+    if primary == "zh" || primary == "cmn" {
+        let traditional = subtags.any(|subtag| matches!(subtag, "hant" | "tw" | "hk" | "mo"));
+        return Some(if traditional { "zh-tw" } else { "zh-cn" });
+    }
+    CODES.iter().find(|(profile, _, _)| *profile == primary).map(|(profile, _, _)| *profile)
+        .or_else(|| BCP47_ALIASES.iter().find(|(alias, _)| *alias == primary).map(|(_, profile)| *profile))
+}
+
+/// Returns the English name of a profile, e.g. "German" for "de".
+///
+/// # Arguments
+/// * `code` - Profile name of a built-in language, e.g. a detection result.
+///
+/// # Returns
+/// The name, or None for languages without a built-in profile.
+pub fn language_name(code: &str) -> Option<&'static str> {
+    Lang::from(code).name()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iso_mapping() {
+        for lang in Lang::BUILT_IN {
+            let code = lang.as_str();
+            assert_eq!(to_bcp47(code).and_then(from_bcp47), Some(code));
+            if !code.starts_with("zh") {
+                assert_eq!(to_iso639_3(code).and_then(from_iso639_3), Some(code));
+                assert_eq!(to_iso639_1(code), Some(code));
+            }
+        }
+        assert_eq!(to_iso639_1("zh-tw"), Some("zh"));
+        assert_eq!(from_iso639_3("ZHO"), Some("zh-cn"));
+        assert_eq!(from_iso639_3("fil"), Some("tl"));
+        assert_eq!(from_iso639_3("xxx"), None);
+        assert_eq!(from_bcp47("zh_TW"), Some("zh-tw"));
+        assert_eq!(from_bcp47("zh-SG"), Some("zh-cn"));
+        assert_eq!(from_bcp47("iw"), Some("he"));
+        assert_eq!(from_bcp47(""), None);
+        assert_eq!(to_iso639_3("kk"), None);
+        assert_eq!(language_name("de"), Some("German"));
+        assert_eq!(language_name("zh-tw"), Some("Traditional Chinese"));
+        assert_eq!(language_name("kk"), None);
+    }
+}
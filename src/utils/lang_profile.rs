@@ -1,8 +1,15 @@
+use std::borrow::Cow;
 use std::fs;
+use std::io::Read;
 use std::collections::HashMap;
 use std::path::Path;
-use crate::utils::ngram::NGram;
-use serde::{Deserialize};
+use crate::utils::arabic::normalize_arabic;
+use crate::utils::ngram::{NGram, Normalization};
+use crate::utils::script::{scripts_by_share, Script};
+use crate::profile_data::ProfileData;
+use std::cell::Cell;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json;
 
 /// Errors that can occur when working with LangProfileJson.
@@ -12,10 +19,161 @@ pub enum LangProfileJsonError {
     IoError(String),
     /// JSON parsing error.
     ParseError(String),
+    /// The profile exceeds one of the `ProfileLimits`.
+    LimitExceeded(String),
+    /// The profile has no name, so it cannot be saved in the JSON format.
+    MissingName,
+}
+
+/// Size limits for profiles from untrusted sources, enforced while loading.
+///
+/// The defaults are far above the built-in profiles (at most about 25,000 n-grams and
+/// 300 KiB per language, a 40 MiB matrix for all of them) but keep a malicious or
+/// corrupted file from exhausting memory.
+///
+/// # Examples
+/// ```
+/// use langdetect_rs::utils::lang_profile::ProfileLimits;
+/// let limits = ProfileLimits::default().with_max_file_size(1 << 20).with_max_entries(50_000);
+/// assert_eq!(limits.max_ngram_length, ProfileLimits::DEFAULT_MAX_NGRAM_LENGTH);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProfileLimits {
+    /// Maximum size of a profile file or JSON string, in bytes.
+    pub max_file_size: usize,
+    /// Maximum number of entries in the `freq` map.
+    pub max_entries: usize,
+    /// Maximum length of an n-gram, in bytes.
+    pub max_ngram_length: usize,
+    /// Maximum size of a model with all languages, in bytes: of a binary, mapped or
+    /// snapshot file (decompressed) and of the probability matrix built from it or
+    /// from JSON profiles.
+    pub max_model_size: usize,
+}
+
+impl ProfileLimits {
+    /// Default maximum profile size: 16 MiB.
+    pub const DEFAULT_MAX_FILE_SIZE: usize = 16 << 20;
+    /// Default maximum number of n-grams per profile.
+    pub const DEFAULT_MAX_ENTRIES: usize = 1_000_000;
+    /// Default maximum n-gram length in bytes (three 4-byte characters and some slack).
+    pub const DEFAULT_MAX_NGRAM_LENGTH: usize = 64;
+    /// Default maximum model size: 1 GiB.
+    pub const DEFAULT_MAX_MODEL_SIZE: usize = 1 << 30;
+
+    /// Limits that accept any profile, for trusted sources.
+    pub fn unlimited() -> Self {
+        ProfileLimits { max_file_size: usize::MAX, max_entries: usize::MAX, max_ngram_length: usize::MAX, max_model_size: usize::MAX }
+    }
+
+    /// Sets the maximum size of a profile file or JSON string, in bytes.
+    pub fn with_max_file_size(mut self, max_file_size: usize) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Sets the maximum number of entries in the `freq` map.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Sets the maximum length of an n-gram, in bytes.
+    pub fn with_max_ngram_length(mut self, max_ngram_length: usize) -> Self {
+        self.max_ngram_length = max_ngram_length;
+        self
+    }
+
+    /// Sets the maximum size of a model with all languages, in bytes.
+    pub fn with_max_model_size(mut self, max_model_size: usize) -> Self {
+        self.max_model_size = max_model_size;
+        self
+    }
+
+    /// Checks the size of a profile's JSON text before parsing it.
+    ///
+    /// # Errors
+    /// Returns `LangProfileJsonError::LimitExceeded` if the text is larger than `max_file_size`.
+    pub fn check_size(&self, size: usize) -> Result<(), LangProfileJsonError> {
+        if size > self.max_file_size {
+            return Err(LangProfileJsonError::LimitExceeded(format!("Profile has more than {} bytes", self.max_file_size)));
+        }
+        Ok(())
+    }
+
+    /// Checks the size of a model with all languages, or of the matrix built from it.
+    ///
+    /// # Errors
+    /// Returns `LangProfileJsonError::LimitExceeded` if the size is larger than `max_model_size`.
+    pub fn check_model_size(&self, size: usize) -> Result<(), LangProfileJsonError> {
+        if size > self.max_model_size {
+            return Err(LangProfileJsonError::LimitExceeded(format!("Model has more than {} bytes", self.max_model_size)));
+        }
+        Ok(())
+    }
+
+    /// Checks the number of n-grams of a language while it is read.
+    pub(crate) fn check_entries(&self, count: usize) -> Result<(), LangProfileJsonError> {
+        if count > self.max_entries {
+            return Err(LangProfileJsonError::LimitExceeded(format!("Profile has more than {} n-grams", self.max_entries)));
+        }
+        Ok(())
+    }
+
+    /// Checks the length of an n-gram while it is read.
+    pub(crate) fn check_ngram_length(&self, gram: &str) -> Result<(), LangProfileJsonError> {
+        if gram.len() > self.max_ngram_length {
+            return Err(LangProfileJsonError::LimitExceeded(format!(
+                "Profile has an n-gram of {} bytes, the limit is {}", gram.len(), self.max_ngram_length
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks the n-grams of a parsed profile.
+    ///
+    /// # Errors
+    /// Returns `LangProfileJsonError::LimitExceeded` if the profile has more than `max_entries`
+    /// n-grams or an n-gram longer than `max_ngram_length`.
+    pub fn check_profile(&self, profile: &LangProfileJson) -> Result<(), LangProfileJsonError> {
+        if profile.freq.len() > self.max_entries {
+            return Err(LangProfileJsonError::LimitExceeded(format!(
+                "Profile '{}' has {} n-grams, the limit is {}", profile.name, profile.freq.len(), self.max_entries
+            )));
+        }
+        if let Some(gram) = profile.freq.keys().find(|gram| gram.len() > self.max_ngram_length) {
+            return Err(LangProfileJsonError::LimitExceeded(format!(
+                "Profile '{}' has an n-gram of {} bytes, the limit is {}", profile.name, gram.len(), self.max_ngram_length
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProfileLimits {
+    fn default() -> Self {
+        ProfileLimits {
+            max_file_size: Self::DEFAULT_MAX_FILE_SIZE,
+            max_entries: Self::DEFAULT_MAX_ENTRIES,
+            max_ngram_length: Self::DEFAULT_MAX_NGRAM_LENGTH,
+            max_model_size: Self::DEFAULT_MAX_MODEL_SIZE,
+        }
+    }
+}
+
+impl std::fmt::Display for LangProfileJsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LangProfileJsonError::IoError(e) | LangProfileJsonError::ParseError(e) | LangProfileJsonError::LimitExceeded(e) => {
+                write!(f, "{}", e)
+            }
+            LangProfileJsonError::MissingName => write!(f, "Profile has no name"),
+        }
+    }
 }
 
 /// JSON representation of a language profile loaded from disk.
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 pub struct LangProfileJson {
     /// Frequency map of n-grams to their counts.
     pub freq: HashMap<String, usize>,
@@ -23,10 +181,16 @@ pub struct LangProfileJson {
     pub n_words: Vec<usize>,
     /// Language identifier (ISO 639-1 code).
     pub name: String,
+    /// Scripts the language is written in (e.g. `["Latin"]`); derived from `freq` if absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scripts: Option<Vec<Script>>,
+    /// Normalization the profile was trained with; `Strict` if absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalization: Option<Normalization>,
 }
 
 impl LangProfileJson {
-    /// Loads a LangProfileJson from a file.
+    /// Loads a LangProfileJson from a file, within the default `ProfileLimits`.
     ///     
     /// # Arguments
     /// * `file_path` - Path to the JSON file containing the language profile.
@@ -35,7 +199,7 @@ impl LangProfileJson {
     /// A Result containing the LangProfileJson or a LangProfileJsonError.
     /// 
     /// # Errors
-    /// Returns `LangProfileJsonError` if reading or parsing fails.
+    /// Returns `LangProfileJsonError` if reading or parsing fails or the profile exceeds the limits.
     /// 
     /// # Examples
     /// ```
@@ -49,14 +213,241 @@ impl LangProfileJson {
     /// }
     /// ```
     pub fn new_from_file<P: AsRef<Path>>(file_path: P) -> Result<LangProfileJson, LangProfileJsonError> {
-        let content = fs::read_to_string(file_path)
+        Self::new_from_file_with_limits(file_path, &ProfileLimits::default())
+    }
+
+    /// Loads a LangProfileJson from a file, rejecting profiles which exceed the given limits.
+    ///
+    /// At most `max_file_size` bytes are read, whatever size the file system reports.
+    ///
+    /// # Arguments
+    /// * `file_path` - Path to the JSON file containing the language profile.
+    /// * `limits` - The size limits to enforce.
+    ///
+    /// # Errors
+    /// Returns `LangProfileJsonError` if reading or parsing fails or the profile exceeds the limits.
+    ///
+    /// # Examples
+    /// ```
+    /// use langdetect_rs::utils::lang_profile::{LangProfileJson, LangProfileJsonError, ProfileLimits};
+    /// let limits = ProfileLimits::default().with_max_entries(100);
+    /// let result = LangProfileJson::new_from_file_with_limits("./profiles/en", &limits);
+    /// assert!(matches!(result, Err(LangProfileJsonError::LimitExceeded(_))));
+    /// ```
+    pub fn new_from_file_with_limits<P: AsRef<Path>>(file_path: P, limits: &ProfileLimits) -> Result<LangProfileJson, LangProfileJsonError> {
+        let content = read_limited(file_path.as_ref(), limits.max_file_size)
             .map_err(|e| LangProfileJsonError::IoError(format!("Failed to read file: {}", e)))?;
-        let json_profile: LangProfileJson = serde_json::from_str(&content)
-            .map_err(|e| LangProfileJsonError::ParseError(format!("Failed to parse JSON: {}", e)))?;
-        Ok(json_profile)
+        Self::from_str_with_limits(&content, limits)
+    }
+
+    /// Parses a LangProfileJson from a JSON string, rejecting profiles which exceed the given limits.
+    ///
+    /// The number and length of the n-grams are checked while the `freq` map is read, so an
+    /// oversized map is rejected before it is built.
+    ///
+    /// # Arguments
+    /// * `json` - The JSON text of the profile.
+    /// * `limits` - The size limits to enforce.
+    ///
+    /// # Errors
+    /// Returns `LangProfileJsonError` if parsing fails or the profile exceeds the limits.
+    pub fn from_str_with_limits(json: &str, limits: &ProfileLimits) -> Result<LangProfileJson, LangProfileJsonError> {
+        limits.check_size(json.len())?;
+        let exceeded = Cell::new(None);
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        LimitedProfile { limits, exceeded: &exceeded }.deserialize(&mut deserializer)
+            .and_then(|json_profile| deserializer.end().map(|_| json_profile))
+            .map_err(|e| exceeded.take().unwrap_or_else(|| LangProfileJsonError::ParseError(format!("Failed to parse JSON: {}", e))))
+    }
+
+    /// Checks the profile for inconsistencies that would distort detection.
+    ///
+    /// # Returns
+    /// Human-readable warnings, empty if the profile looks sane.
+    ///
+    /// # Examples
+    /// ```
+    /// use langdetect_rs::utils::lang_profile::LangProfileJson;
+    /// let profile_json = LangProfileJson::new_from_file("./profiles/en").unwrap();
+    /// assert!(profile_json.validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.name.trim().is_empty() {
+            warnings.push("Profile name is empty".to_string());
+        }
+        if self.n_words.len() != NGram::N_GRAM {
+            warnings.push(format!("n_words has {} entries, expected {}", self.n_words.len(), NGram::N_GRAM));
+        }
+        if self.freq.is_empty() {
+            warnings.push("Profile contains no n-grams".to_string());
+        }
+        let mut sums = [0usize; NGram::N_GRAM];
+        let mut invalid_length = 0;
+        let mut zero_counts = 0;
+        for (gram, &count) in self.freq.iter() {
+            let length = gram.chars().count();
+            if !(1..=NGram::N_GRAM).contains(&length) {
+                invalid_length += 1;
+                continue;
+            }
+            if count == 0 {
+                zero_counts += 1;
+            }
+            sums[length - 1] += count;
+        }
+        if invalid_length > 0 {
+            warnings.push(format!("{} n-grams are not 1 to {} characters long and will be ignored", invalid_length, NGram::N_GRAM));
+        }
+        if zero_counts > 0 {
+            warnings.push(format!("{} n-grams have a zero count", zero_counts));
+        }
+        for (i, (&sum, &total)) in sums.iter().zip(self.n_words.iter()).enumerate() {
+            if sum > 0 && total == 0 {
+                warnings.push(format!("n_words[{}] is 0 although {}-grams are present", i, i + 1));
+            } else if sum > total {
+                warnings.push(format!("{}-gram counts sum to {}, more than n_words[{}] = {}", i + 1, sum, i, total));
+            }
+        }
+        warnings
+    }
+}
+
+/// Deserializes a `LangProfileJson` with the entries of `freq` counted against `ProfileLimits`.
+struct LimitedProfile<'a> {
+    limits: &'a ProfileLimits,
+    /// The exceeded limit, reported instead of the deserialization error it causes.
+    exceeded: &'a Cell<Option<LangProfileJsonError>>,
+}
+
+impl<'de> DeserializeSeed<'de> for LimitedProfile<'_> {
+    type Value = LangProfileJson;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<LangProfileJson, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for LimitedProfile<'_> {
+    type Value = LangProfileJson;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a language profile")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<LangProfileJson, A::Error> {
+        let (mut freq, mut n_words, mut name, mut scripts, mut normalization) = (None, None, None, None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "freq" if freq.is_none() => freq = Some(map.next_value_seed(LimitedFreq { limits: self.limits, exceeded: self.exceeded })?),
+                "n_words" if n_words.is_none() => n_words = Some(map.next_value()?),
+                "name" if name.is_none() => name = Some(map.next_value()?),
+                "scripts" if scripts.is_none() => scripts = Some(map.next_value()?),
+                "normalization" if normalization.is_none() => normalization = Some(map.next_value()?),
+                "freq" | "n_words" | "name" | "scripts" | "normalization" => return Err(de::Error::custom(format!("duplicate field `{}`", key))),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(LangProfileJson {
+            freq: freq.ok_or_else(|| de::Error::missing_field("freq"))?,
+            n_words: n_words.ok_or_else(|| de::Error::missing_field("n_words"))?,
+            name: name.ok_or_else(|| de::Error::missing_field("name"))?,
+            scripts: scripts.flatten(),
+            normalization: normalization.flatten(),
+        })
+    }
+}
+
+/// Deserializes the `freq` map of a profile, stopping at the first entry beyond the limits.
+struct LimitedFreq<'a> {
+    limits: &'a ProfileLimits,
+    exceeded: &'a Cell<Option<LangProfileJsonError>>,
+}
+
+impl LimitedFreq<'_> {
+    /// Records an exceeded limit and turns it into a deserialization error.
+    fn fail<E: de::Error>(&self, error: LangProfileJsonError) -> E {
+        let e = E::custom(error.to_string());
+        self.exceeded.set(Some(error));
+        e
     }
 }
 
+impl<'de> DeserializeSeed<'de> for LimitedFreq<'_> {
+    type Value = HashMap<String, usize>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<HashMap<String, usize>, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for LimitedFreq<'_> {
+    type Value = HashMap<String, usize>;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a map of n-grams to counts")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<HashMap<String, usize>, A::Error> {
+        let mut freq = HashMap::new();
+        while let Some(gram) = map.next_key::<String>()? {
+            self.limits.check_ngram_length(&gram).map_err(|e| self.fail(e))?;
+            self.limits.check_entries(freq.len() + 1).map_err(|e| self.fail(e))?;
+            let count: usize = map.next_value()?;
+            freq.insert(gram, count);
+        }
+        Ok(freq)
+    }
+}
+
+/// Reads a file to a string, failing with `InvalidData` if it is longer than `max_size` bytes.
+///
+/// Files ending in `.gz` or `.zst` are decompressed first (with the `gzip` or `zstd` feature);
+/// the limit applies to the decompressed content.
+pub(crate) fn read_limited(path: &Path, max_size: usize) -> std::io::Result<String> {
+    let mut content = String::new();
+    let limit = (max_size as u64).saturating_add(1);
+    open_decompressed(path)?.take(limit).read_to_string(&mut content)?;
+    if content.len() > max_size {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("file is larger than {} bytes", max_size)));
+    }
+    Ok(content)
+}
+
+/// Opens a file, decompressing it on the fly according to its extension.
+fn open_decompressed(path: &Path) -> std::io::Result<Box<dyn Read>> {
+    let file = fs::File::open(path)?;
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some("gz") => {
+            #[cfg(feature = "gzip")]
+            return Ok(Box::new(flate2::read::GzDecoder::new(std::io::BufReader::new(file))));
+            #[cfg(not(feature = "gzip"))]
+            return Err(missing_decompressor("gzip"));
+        }
+        Some("zst") => {
+            #[cfg(feature = "zstd")]
+            return Ok(Box::new(zstd::stream::read::Decoder::new(file)?));
+            #[cfg(not(feature = "zstd"))]
+            return Err(missing_decompressor("zstd"));
+        }
+        _ => Ok(Box::new(file)),
+    }
+}
+
+/// Error for a compressed file whose decompressor is not compiled in.
+#[cfg(not(all(feature = "gzip", feature = "zstd")))]
+fn missing_decompressor(feature: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Unsupported, format!("{} compressed file, enable the `{}` feature", feature, feature))
+}
+
+/// Returns the language part of a profile file name, e.g. "en" for "en", "en.json" or "en.json.gz".
+pub(crate) fn profile_file_stem(file_name: &str) -> &str {
+    let name = file_name.strip_suffix(".gz").or_else(|| file_name.strip_suffix(".zst")).unwrap_or(file_name);
+    name.strip_suffix(".json").unwrap_or(name)
+}
+
 /// Language profile which stores name, frequency map and counts of n-grams lengths.
 ///
 /// A language profile contains statistical information about n-gram frequencies
@@ -68,6 +459,10 @@ pub struct LangProfile {
     pub freq: HashMap<String, usize>,
     /// Total counts for each n-gram length: [1-gram, 2-gram, 3-gram].
     pub n_words: [usize; NGram::N_GRAM],
+    /// Declared scripts of the language; None to derive them from `freq`, see `scripts()`.
+    pub scripts: Option<Vec<Script>>,
+    /// Character normalization `update` extracts n-grams with; should match the detector's.
+    pub normalization: Normalization,
 }
 
 impl LangProfile {
@@ -89,12 +484,15 @@ impl LangProfile {
     ///     .with_name("en")
     ///     .build();
     /// ```
+    #[allow(clippy::new_ret_no_self)]
     pub fn new() -> LangProfileBuilder {
         LangProfileBuilder {
             profile: LangProfile {
                 name: None,
                 freq: HashMap::new(),
                 n_words: [0usize; NGram::N_GRAM],
+                scripts: None,
+                normalization: Normalization::Strict,
             },
         }
     }
@@ -118,9 +516,93 @@ impl LangProfile {
             name: Some(json.name),
             freq: json.freq,
             n_words: arr,
+            scripts: json.scripts,
+            normalization: json.normalization.unwrap_or_default(),
         })
     }
 
+    /// Serializes the profile in the JSON format `LangProfileJson` and the factory load.
+    ///
+    /// N-grams are ordered by their text, so equal profiles give identical output.
+    /// Declared scripts are kept; derived ones are left out. The normalization
+    /// is written only if it is not the default `Strict`.
+    ///
+    /// # Returns
+    /// The JSON text of the profile.
+    ///
+    /// # Errors
+    /// Returns `LangProfileJsonError::MissingName` if the profile has no name.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::utils::lang_profile::{LangProfile, LangProfileJson};
+    ///
+    /// let mut profile = LangProfile::new().with_name("xx").build();
+    /// profile.update("abc abc");
+    /// let json = profile.to_json().unwrap();
+    /// let loaded = LangProfile::from_json(serde_json::from_str::<LangProfileJson>(&json).unwrap()).unwrap();
+    /// assert_eq!(loaded.freq, profile.freq);
+    /// ```
+    pub fn to_json(&self) -> Result<String, LangProfileJsonError> {
+        let name = self.name.as_deref().ok_or(LangProfileJsonError::MissingName)?;
+        // Objects of serde_json values are sorted by key
+        let mut json = serde_json::json!({
+            "freq": self.freq,
+            "n_words": self.n_words,
+            "name": name,
+        });
+        if let Some(scripts) = &self.scripts {
+            json["scripts"] = serde_json::json!(scripts);
+        }
+        if self.normalization != Normalization::Strict {
+            json["normalization"] = serde_json::json!(self.normalization);
+        }
+        Ok(json.to_string())
+    }
+
+    /// Writes the profile to a file in the JSON format, see `to_json`.
+    ///
+    /// # Arguments
+    /// * `file_path` - Path of the written file, e.g. `profiles/xx` to extend the built-in set.
+    ///
+    /// # Errors
+    /// Returns `LangProfileJsonError::MissingName` if the profile has no name and
+    /// `LangProfileJsonError::IoError` if the file cannot be written.
+    pub fn to_json_file<P: AsRef<Path>>(&self, file_path: P) -> Result<(), LangProfileJsonError> {
+        let json = self.to_json()?;
+        fs::write(file_path, json).map_err(|e| LangProfileJsonError::IoError(format!("Failed to write file: {}", e)))
+    }
+
+    /// Returns the scripts the language is written in.
+    ///
+    /// The declared `scripts` if set, otherwise the scripts holding at least
+    /// `ProfileData::SCRIPT_MIN_SHARE` of the letter unigram counts.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::utils::lang_profile::LangProfile;
+    /// use langdetect_rs::utils::script::Script;
+    ///
+    /// let mut profile = LangProfile::new().with_name("ru").build();
+    /// profile.update("Привет, как дела?");
+    /// assert_eq!(profile.scripts(), vec![Script::Cyrillic]);
+    /// ```
+    pub fn scripts(&self) -> Vec<Script> {
+        if let Some(scripts) = &self.scripts {
+            return scripts.clone();
+        }
+        let unigrams = self.freq.iter().filter_map(|(gram, &count)| {
+            let mut chars = gram.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => Some((ch, count as f64)),
+                _ => None,
+            }
+        });
+        scripts_by_share(unigrams, ProfileData::SCRIPT_MIN_SHARE)
+    }
+
     /// Adds an n-gram to the profile's frequency counts.
     ///
     /// # Arguments
@@ -133,11 +615,17 @@ impl LangProfile {
             return;
         }
         let length = gram.chars().count();
-        if length < 1 || length > NGram::N_GRAM {
+        if !(1..=NGram::N_GRAM).contains(&length) {
             return;
         }
         self.n_words[length - 1] += 1;
-        *self.freq.entry(gram.to_string()).or_insert(0) += 1;
+        // Only allocate a key for n-grams seen for the first time
+        match self.freq.get_mut(gram) {
+            Some(count) => *count += 1,
+            None => {
+                self.freq.insert(gram.to_string(), 1);
+            }
+        }
     }
 
     /// Removes n-grams that appear less frequently than the threshold.
@@ -145,10 +633,21 @@ impl LangProfile {
     /// This optimization reduces profile size and improves detection speed.
     /// Also handles Roman character filtering for non-Latin languages.
     pub fn omit_less_freq(&mut self) {
+        self.omit_less_freq_with(Self::LESS_FREQ_RATIO, Self::MINIMUM_FREQ);
+    }
+
+    /// Removes less frequent n-grams with custom thresholds, see `omit_less_freq`.
+    ///
+    /// N-grams occurring at most `max(n_words[0] / less_freq_ratio, minimum_freq)` times are removed.
+    ///
+    /// # Arguments
+    /// * `less_freq_ratio` - Divisor of the unigram total giving the relative threshold (default: 100000).
+    /// * `minimum_freq` - Absolute threshold (default: 2).
+    pub fn omit_less_freq_with(&mut self, less_freq_ratio: usize, minimum_freq: usize) {
         if self.name.is_none() {
             return;
         }
-        let threshold = std::cmp::max(self.n_words[0] / Self::LESS_FREQ_RATIO, Self::MINIMUM_FREQ);
+        let threshold = std::cmp::max(self.n_words[0] / less_freq_ratio.max(1), minimum_freq);
         let mut roman = 0;
         let roman_char_re = regex::Regex::new(r"^[A-Za-z]$").unwrap();
         let roman_substr_re = regex::Regex::new(r".*[A-Za-z].*").unwrap();
@@ -181,6 +680,8 @@ impl LangProfile {
 
     /// Updates the profile by analyzing text and extracting n-grams.
     ///
+    /// The text is normalized as the detector does with the profile's `normalization`.
+    ///
     /// # Arguments
     /// * `text` - The text to analyze and add to the profile.
     pub fn update(&mut self, text: &str) {
@@ -188,12 +689,18 @@ impl LangProfile {
             return;
         }
         let text = NGram::normalize_vi(text);
-        let mut gram = NGram::new();
+        let text = NGram::normalize_sea(&text);
+        let text = normalize_arabic(&text);
+        let text = match self.normalization {
+            Normalization::Strict => Cow::Borrowed(text.as_ref()),
+            Normalization::Lenient => NGram::normalize_lenient(&text),
+        };
+        let mut gram = NGram::with_normalization(self.normalization);
         for ch in text.chars() {
             gram.add_char(ch);
             for n in 1..=NGram::N_GRAM {
-                if let Some(g) = gram.get(n) {
-                    self.add(&g);
+                if let Some(g) = gram.get_str(n) {
+                    self.add(g);
                 }
             }
         }
@@ -243,6 +750,26 @@ impl LangProfileBuilder {
         self
     }
 
+    /// Declares the scripts of the language instead of deriving them from the n-grams.
+    ///
+    /// # Arguments
+    /// * `scripts` - The scripts the language is written in.
+    pub fn with_scripts(mut self, scripts: Vec<Script>) -> Self {
+        self.profile.scripts = Some(scripts);
+        self
+    }
+
+    /// Sets the character normalization of n-gram extraction (default: `Normalization::Strict`).
+    ///
+    /// Profiles should be trained with the normalization they are detected with.
+    ///
+    /// # Arguments
+    /// * `normalization` - The normalization rules, see `DetectorFactoryBuilder::with_normalization`.
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.profile.normalization = normalization;
+        self
+    }
+
     /// Builds the final LangProfile with the configured properties.
     ///
     /// # Returns
@@ -278,6 +805,62 @@ mod tests {
         profile.omit_less_freq();
     }
 
+    #[test]
+    fn test_profile_limits() {
+        let json = r#"{"freq":{"a":3,"ab":1,"abc":1},"n_words":[3,1,1],"name":"xx"}"#;
+        assert!(LangProfileJson::from_str_with_limits(json, &ProfileLimits::default()).is_ok());
+        let too_big = ProfileLimits::default().with_max_file_size(json.len() - 1);
+        assert!(matches!(LangProfileJson::from_str_with_limits(json, &too_big), Err(LangProfileJsonError::LimitExceeded(_))));
+        let too_many = ProfileLimits::default().with_max_entries(2);
+        assert!(matches!(LangProfileJson::from_str_with_limits(json, &too_many), Err(LangProfileJsonError::LimitExceeded(_))));
+        let too_long = ProfileLimits::default().with_max_ngram_length(2);
+        assert!(matches!(LangProfileJson::from_str_with_limits(json, &too_long), Err(LangProfileJsonError::LimitExceeded(_))));
+        assert!(ProfileLimits::unlimited().with_max_ngram_length(3).check_profile(&serde_json::from_str(json).unwrap()).is_ok());
+        // The map is rejected at the first entry beyond the limit, even if the rest is malformed
+        let truncated = r#"{"name":"xx","freq":{"a":3,"ab":1,"abc":"#;
+        assert!(matches!(LangProfileJson::from_str_with_limits(truncated, &too_many), Err(LangProfileJsonError::LimitExceeded(_))));
+        assert!(matches!(LangProfileJson::from_str_with_limits(truncated, &ProfileLimits::default()), Err(LangProfileJsonError::ParseError(_))));
+        let extended = r#"{"freq":{"a":3},"n_words":[3,0,0],"name":"xx","scripts":null,"comment":[1]}"#;
+        assert_eq!(LangProfileJson::from_str_with_limits(extended, &ProfileLimits::default()).unwrap().scripts, None);
+        let duplicated = r#"{"freq":{"a":3},"freq":{"b":3},"n_words":[3,0,0],"name":"xx"}"#;
+        assert!(matches!(LangProfileJson::from_str_with_limits(duplicated, &ProfileLimits::default()), Err(LangProfileJsonError::ParseError(_))));
+
+        let tiny = ProfileLimits::default().with_max_file_size(16);
+        assert!(matches!(LangProfileJson::new_from_file_with_limits("./profiles/en", &tiny), Err(LangProfileJsonError::IoError(_))));
+        assert!(LangProfileJson::new_from_file("./profiles/en").is_ok());
+    }
+
+    #[test]
+    fn test_scripts() {
+        let mut profile = LangProfile::new().with_name("ja").build();
+        profile.update("これはテストです。");
+        assert_eq!(profile.scripts(), vec![Script::Hiragana, Script::Katakana]);
+        let profile = LangProfile::new().with_name("sr").with_scripts(vec![Script::Latin, Script::Cyrillic]).build();
+        assert_eq!(profile.scripts(), vec![Script::Latin, Script::Cyrillic]);
+        assert!(LangProfile::new().build().scripts().is_empty());
+    }
+
+    #[test]
+    fn test_update_normalizes_arabic() {
+        let mut stretched = LangProfile::new().with_name("ar").build();
+        stretched.update("\u{0639}\u{0640}\u{0640}\u{0631}\u{0628}\u{064A}");
+        let mut plain = LangProfile::new().with_name("ar").build();
+        plain.update("\u{0639}\u{0631}\u{0628}\u{064A}");
+        assert_eq!(stretched.freq, plain.freq);
+        assert_eq!(stretched.n_words, plain.n_words);
+    }
+
+    #[test]
+    fn test_update_normalization() {
+        let mut strict = LangProfile::new().with_name("fr").build();
+        strict.update("d'h");
+        assert_eq!(strict.freq.get("d'"), None);
+        let mut lenient = LangProfile::new().with_name("fr").with_normalization(Normalization::Lenient).build();
+        lenient.update("d'h");
+        assert_eq!(lenient.freq.get("d'"), Some(&1));
+        assert_eq!(lenient.freq.get("d'h"), Some(&1));
+    }
+
     #[test]
     fn test_add_illegally1() {
         let mut profile = LangProfile::new().build();
@@ -330,4 +913,101 @@ mod tests {
         // ignore
         profile.omit_less_freq();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_to_json() {
+        let mut profile = LangProfile::new().with_name("xx").build();
+        for _ in 0..3 {
+            profile.update("Zażółć gęślą jaźń");
+        }
+        profile.omit_less_freq();
+        assert!(!profile.freq.is_empty());
+        let json = profile.to_json().unwrap();
+        assert!(!json.contains("scripts"));
+        let loaded = LangProfile::from_json(LangProfileJson::from_str_with_limits(&json, &ProfileLimits::default()).unwrap()).unwrap();
+        assert_eq!(loaded.name.as_deref(), Some("xx"));
+        assert_eq!(loaded.freq, profile.freq);
+        assert_eq!(loaded.n_words, profile.n_words);
+        assert_eq!(profile.to_json().unwrap(), json);
+
+        let path = std::env::temp_dir().join(format!("langdetect-profile-{}.json", std::process::id()));
+        let declared = LangProfile::new().with_name("sr").with_freq(profile.freq.clone()).with_scripts(vec![Script::Latin, Script::Cyrillic]).build();
+        declared.to_json_file(&path).unwrap();
+        let loaded = LangProfileJson::new_from_file(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.scripts, Some(vec![Script::Latin, Script::Cyrillic]));
+
+        assert!(!json.contains("normalization"));
+        assert_eq!(loaded.normalization, None);
+        let lenient = LangProfile::new().with_name("xx").with_normalization(Normalization::Lenient).build();
+        let loaded = LangProfile::from_json(serde_json::from_str::<LangProfileJson>(&lenient.to_json().unwrap()).unwrap()).unwrap();
+        assert_eq!(loaded.normalization, Normalization::Lenient);
+        assert!(matches!(LangProfile::new().build().to_json(), Err(LangProfileJsonError::MissingName)));
+    }
+
+    #[test]
+    fn test_profile_file_stem() {
+        assert_eq!(profile_file_stem("en"), "en");
+        assert_eq!(profile_file_stem("zh-cn.json"), "zh-cn");
+        assert_eq!(profile_file_stem("en.json.gz"), "en");
+        assert_eq!(profile_file_stem("en.zst"), "en");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_profiles() {
+        use std::io::Write;
+        use crate::detector_factory::DetectorFactory;
+
+        let dir = std::env::temp_dir().join(format!("langdetect-gzip-profiles-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for lang in ["en", "fr"] {
+            let json = fs::read(format!("profiles/{}", lang)).unwrap();
+            let mut encoder = flate2::write::GzEncoder::new(fs::File::create(dir.join(format!("{}.json.gz", lang))).unwrap(), flate2::Compression::default());
+            encoder.write_all(&json).unwrap();
+            encoder.finish().unwrap();
+        }
+        let path = dir.join("en.json.gz");
+        let loaded = LangProfileJson::new_from_file(&path).unwrap();
+        assert_eq!(loaded.name, "en");
+        // The limit applies to the decompressed size
+        let limits = ProfileLimits::default().with_max_file_size(fs::metadata(&path).unwrap().len() as usize);
+        assert!(LangProfileJson::new_from_file_with_limits(&path, &limits).is_err());
+
+        let mut factory = DetectorFactory::new().build();
+        factory.load_profile(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(factory.get_lang_list().len(), 2);
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd_profiles() {
+        let path = std::env::temp_dir().join(format!("langdetect-profile-{}.json.zst", std::process::id()));
+        let json = fs::read("profiles/fr").unwrap();
+        fs::write(&path, zstd::encode_all(json.as_slice(), 0).unwrap()).unwrap();
+        let loaded = LangProfileJson::new_from_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap().name, "fr");
+    }
+
+    #[cfg(not(feature = "zstd"))]
+    #[test]
+    fn test_compressed_profile_without_feature() {
+        let path = std::env::temp_dir().join(format!("langdetect-profile-{}.json.zst", std::process::id()));
+        fs::write(&path, b"\x28\xb5\x2f\xfd").unwrap();
+        let result = LangProfileJson::new_from_file(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(LangProfileJsonError::IoError(e)) if e.contains("`zstd` feature")));
+    }
+
+    #[test]
+    fn test_validate_json_profile() {
+        let json: LangProfileJson = serde_json::from_str(
+            r#"{"freq":{"a":3,"ab":0,"abcd":1},"n_words":[2,1],"name":""}"#
+        ).unwrap();
+        let warnings = json.validate();
+        assert_eq!(warnings.len(), 5, "{:?}", warnings);
+        assert!(warnings.iter().any(|w| w.contains("1-gram counts sum to 3")));
+    }
+}
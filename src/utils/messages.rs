@@ -1,73 +1,46 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-use std::sync::Mutex;
+// Generated by build.rs from `messages.properties`: `static MESSAGES: &[(&str, &str)]`, sorted by key
+include!(concat!(env!("OUT_DIR"), "/messages.rs"));
 
-lazy_static::lazy_static! {
-	static ref MESSAGES: Mutex<Option<Messages>> = Mutex::new(None);
+/// Messages from `messages.properties`, compiled into the crate at build time.
+pub struct Messages {
+	messages: &'static [(&'static str, &'static str)],
 }
 
-pub struct Messages {
-	messages: HashMap<String, String>,
+impl Default for Messages {
+	fn default() -> Self {
+		Self::new()
+	}
 }
 
 impl Messages {
 	pub fn new() -> Self {
-		let mut messages = HashMap::new();
-		let filename = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/utils/messages.properties");
-		if let Ok(file) = File::open(&filename) {
-			let reader = BufReader::new(file);
-			for line in reader.lines().flatten() {
-				let line = line.trim();
-				if line.is_empty() || line.starts_with('#') {
-					continue;
-				}
-				let mut parts = line.splitn(2, '=');
-				if let (Some(key), Some(value)) = (parts.next(), parts.next()) {
-					messages.insert(key.to_string(), Self::parse_unicode_escapes(value));
-				}
-			}
-		}
-		Messages { messages }
-	}
-
-	/// Parse Unicode escape sequences (e.g., \u00A0) in property values
-	fn parse_unicode_escapes(input: &str) -> String {
-		let mut result = String::new();
-		let mut chars = input.chars().peekable();
-		while let Some(c) = chars.next() {
-			if c == '\\' {
-				if let Some('u') = chars.peek() {
-					chars.next(); // consume 'u'
-					let mut hex = String::new();
-					for _ in 0..4 {
-						if let Some(h) = chars.next() {
-							hex.push(h);
-						}
-					}
-					if let Ok(code) = u16::from_str_radix(&hex, 16) {
-						if let Some(ch) = std::char::from_u32(code as u32) {
-							result.push(ch);
-							continue;
-						}
-					}
-				}
-			}
-			result.push(c);
-		}
-		result
+		Messages { messages: MESSAGES }
 	}
 
 	pub fn get_string(&self, key: &str) -> String {
-		self.messages.get(key).cloned().unwrap_or_else(|| format!("!{}!", key))
+		match self.messages.binary_search_by(|(k, _)| (*k).cmp(key)) {
+			Ok(index) => self.messages[index].1.to_string(),
+			Err(_) => format!("!{}!", key),
+		}
 	}
 }
 
 pub fn get_string(key: &str) -> String {
-	let mut messages_guard = MESSAGES.lock().unwrap();
-	if messages_guard.is_none() {
-		*messages_guard = Some(Messages::new());
+	Messages::new().get_string(key)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_get_string() {
+		assert_eq!(get_string("NGram.LATIN1_EXCLUDE"), "\u{00A0}\u{00AB}\u{00B0}\u{00BB}");
+		assert_eq!(get_string("missing.key"), "!missing.key!");
+	}
+
+	#[test]
+	fn test_messages_sorted() {
+		assert!(MESSAGES.windows(2).all(|pair| pair[0].0 < pair[1].0));
 	}
-	messages_guard.as_ref().unwrap().get_string(key)
 }
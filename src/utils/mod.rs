@@ -11,3 +11,16 @@ pub mod ngram;
 pub mod lang_profile;
 /// Internationalization messages.
 pub mod messages;
+/// Java-style properties parser, also used by the build script.
+#[cfg(test)]
+mod properties;
+/// Email message cleanup (headers, quoted replies, signatures).
+pub mod email;
+/// Writing system classification of characters and texts.
+pub mod script;
+/// Normalization and letter markers of Arabic-script languages.
+pub mod arabic;
+/// Function words telling Devanagari languages apart.
+pub mod devanagari;
+/// Mapping of profile names to ISO 639 codes, BCP-47 tags and English names.
+pub mod iso;
@@ -1,8 +1,27 @@
 use crate::utils::unicode_block::*;
 use crate::utils::messages;
 
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 
+/// Character normalization rules of n-gram extraction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Normalization {
+    /// Rules of the Java langdetect: digits, apostrophes, hyphens and other ASCII symbols end a word.
+    ///
+    /// The built-in profiles were trained with these rules.
+    #[default]
+    Strict,
+    /// Keeps digits next to letters ("mp3", "4ever") and apostrophes and hyphens between
+    /// letters ("aujourd'hui", "e-mail") inside words.
+    ///
+    /// Splitting such words creates word boundaries which are not in the text, and the
+    /// n-grams around them ("d ", " h") are noise on modern web text. N-grams with the kept
+    /// characters are unknown to profiles trained with the strict rules and just skipped.
+    Lenient,
+}
+
 /// N-gram processor for extracting character sequences from text.
 ///
 /// The NGram struct maintains a sliding window of characters and provides
@@ -13,6 +32,11 @@ pub struct NGram {
     pub grams: String,
     /// Whether the current sequence contains capital words (used for filtering).
     pub capitalword: bool,
+    /// Character normalization rules.
+    ///
+    /// With `Normalization::Lenient` digits, apostrophes and hyphens are added as they are,
+    /// so the text should be prepared with `NGram::normalize_lenient`.
+    pub normalization: Normalization,
 }
 
 lazy_static::lazy_static! {
@@ -160,9 +184,62 @@ lazy_static::lazy_static! {
     };
 }
 
+impl Default for NGram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NGram {
     /// Maximum n-gram length supported.
     pub const N_GRAM: usize = 3;
+    /// Number of bits of a character in a packed n-gram, see `pack`.
+    const PACKED_CHAR_BITS: u32 = 21;
+
+    /// Packs an n-gram of up to `N_GRAM` characters into a single integer key.
+    ///
+    /// Every character takes 21 bits holding its code point plus one, so n-grams of
+    /// different lengths never collide and 3 characters fit into a `u64`. Used as the
+    /// n-gram key of `ProfileData` instead of a heap-allocated string.
+    ///
+    /// # Arguments
+    /// * `word` - The n-gram.
+    ///
+    /// # Returns
+    /// The key, or None if the n-gram is longer than `N_GRAM` characters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::utils::ngram::NGram;
+    ///
+    /// let key = NGram::pack(" ab").unwrap();
+    /// assert_ne!(NGram::pack("ab"), Some(key));
+    /// assert_eq!(NGram::unpack(key).collect::<String>(), " ab");
+    /// assert_eq!(NGram::pack("abcd"), None);
+    /// ```
+    pub fn pack(word: &str) -> Option<u64> {
+        let mut key = 0u64;
+        for (i, ch) in word.chars().enumerate() {
+            if i >= Self::N_GRAM {
+                return None;
+            }
+            key = (key << Self::PACKED_CHAR_BITS) | (ch as u64 + 1);
+        }
+        Some(key)
+    }
+
+    /// Returns the characters of a key built by `pack`, in order.
+    ///
+    /// # Arguments
+    /// * `key` - The packed n-gram.
+    pub fn unpack(key: u64) -> impl Iterator<Item = char> {
+        let mask = (1u64 << Self::PACKED_CHAR_BITS) - 1;
+        (0..Self::N_GRAM as u32).rev()
+            .map(move |i| ((key >> (i * Self::PACKED_CHAR_BITS)) & mask) as u32)
+            .filter(|&code| code != 0)
+            .filter_map(|code| char::from_u32(code - 1))
+    }
 
     /// Normalizes Vietnamese text by converting combining diacritics to precomposed characters.
     ///
@@ -172,8 +249,13 @@ impl NGram {
     /// * `input` - The Vietnamese text to normalize.
     ///
     /// # Returns
-    /// Normalized text with precomposed characters.
-    pub fn normalize_vi(input: &str) -> String {
+    /// Normalized text with precomposed characters; the input itself if it contains no
+    /// combining diacritics.
+    pub fn normalize_vi(input: &str) -> Cow<'_, str> {
+        // All marks of DMARK_CLASS lie in this range, so most texts need no copy
+        if !input.chars().any(|c| ('\u{0300}'..='\u{0323}').contains(&c)) {
+            return Cow::Borrowed(input);
+        }
         // Load normalization tables from messages.properties
         let bases = messages::get_string("TO_NORMALIZE_VI_CHARS");
         let dmarks = messages::get_string("DMARK_CLASS");
@@ -208,7 +290,102 @@ impl NGram {
             }
             result.push(c);
         }
-        result
+        Cow::Owned(result)
+    }
+
+    /// Normalizes Thai, Lao, Khmer and Myanmar text to the well-formed spelling of profiles.
+    ///
+    /// Fixes common typing variants of these scripts: a tone mark typed before the vowel
+    /// sign it sits on is moved after it, repeated vowel signs and tone marks are collapsed,
+    /// decomposed sara am (nikhahit + sara aa) and doubled sara e are composed, and the
+    /// invisible Khmer inherent vowels are removed.
+    ///
+    /// # Arguments
+    /// * `input` - The text to normalize.
+    ///
+    /// # Returns
+    /// Normalized text; the input itself if nothing changes.
+    pub fn normalize_sea(input: &str) -> Cow<'_, str> {
+        if !input.chars().any(|c| matches!(c, '\u{0E00}'..='\u{0EFF}' | '\u{1000}'..='\u{109F}' | '\u{1780}'..='\u{17FF}')) {
+            return Cow::Borrowed(input);
+        }
+        let mut result: Vec<char> = Vec::with_capacity(input.len());
+        for c in input.chars() {
+            if matches!(c, '\u{17B4}' | '\u{17B5}') {
+                continue;
+            }
+            if let Some(&prev) = result.last() {
+                if prev == c && is_sea_mark(c) {
+                    continue;
+                }
+                let composed = match (prev, c) {
+                    ('\u{0E40}', '\u{0E40}') => Some('\u{0E41}'),
+                    ('\u{0E4D}', '\u{0E32}') => Some('\u{0E33}'),
+                    ('\u{0EC0}', '\u{0EC0}') => Some('\u{0EC1}'),
+                    ('\u{0ECD}', '\u{0EB2}') => Some('\u{0EB3}'),
+                    _ => None,
+                };
+                if let Some(composed) = composed {
+                    *result.last_mut().unwrap() = composed;
+                    continue;
+                }
+                if is_misplaced_tone(prev, c) {
+                    // The vowel may already precede the tone mark
+                    if result.len() < 2 || result[result.len() - 2] != c {
+                        result.insert(result.len() - 1, c);
+                    }
+                    continue;
+                }
+            }
+            result.push(c);
+        }
+        let result: String = result.into_iter().collect();
+        if result == input {
+            Cow::Borrowed(input)
+        } else {
+            Cow::Owned(result)
+        }
+    }
+
+    /// Replaces digits, apostrophes and hyphens which are not inside a word with spaces.
+    ///
+    /// Digits are kept next to a letter ("mp3", "4ever"), apostrophes and hyphens between
+    /// a letter or kept digit and a letter ("aujourd'hui", "e-mail"). The kept characters are
+    /// then added as they are by an NGram with `Normalization::Lenient`.
+    ///
+    /// # Arguments
+    /// * `input` - The text to prepare.
+    ///
+    /// # Returns
+    /// The text with word boundaries marked; the input itself if nothing was replaced.
+    pub fn normalize_lenient(input: &str) -> Cow<'_, str> {
+        let chars: Vec<char> = input.chars().collect();
+        let is_letter = |ch: Option<&char>| ch.is_some_and(|&ch| Self::normalize(ch) != ' ');
+        let mut result = String::with_capacity(input.len());
+        let mut i = 0;
+        while i < chars.len() {
+            let ch = chars[i];
+            if ch.is_ascii_digit() {
+                let end = chars[i..].iter().position(|c| !c.is_ascii_digit()).map_or(chars.len(), |len| i + len);
+                let keep = (i > 0 && is_letter(chars.get(i - 1))) || is_letter(chars.get(end));
+                for digit in &chars[i..end] {
+                    result.push(if keep { *digit } else { ' ' });
+                }
+                i = end;
+                continue;
+            }
+            if is_word_joiner(ch) && (result.ends_with(' ') || result.is_empty() || !is_letter(chars.get(i + 1))) {
+                result.push(' ');
+            } else {
+                result.push(ch);
+            }
+            i += 1;
+        }
+        if result == input {
+            Cow::Borrowed(input)
+        } else {
+            Cow::Owned(result)
+        }
     }
 
     /// Creates a new NGram processor.
@@ -218,9 +395,18 @@ impl NGram {
         NGram {
             grams: " ".to_string(),
             capitalword: false,
+            normalization: Normalization::Strict,
         }
     }
 
+    /// Creates a new NGram processor with the given normalization rules.
+    ///
+    /// # Arguments
+    /// * `normalization` - The character normalization rules.
+    pub fn with_normalization(normalization: Normalization) -> Self {
+        NGram { normalization, ..Self::new() }
+    }
+
     /// Adds a character to the n-gram sliding window.
     ///
     /// The character is normalized and the window is maintained at maximum N_GRAM length.
@@ -229,16 +415,26 @@ impl NGram {
     /// # Arguments
     /// * `ch` - The character to add.
     pub fn add_char(&mut self, ch: char) {
-        let ch = Self::normalize(ch);
+        let ch = match self.normalization {
+            Normalization::Lenient if ch.is_ascii_digit() || is_word_joiner(ch) => ch,
+            _ => Self::normalize(ch),
+        };
+        self.push(ch);
+    }
+
+    /// Appends a normalized character to the sliding window.
+    fn push(&mut self, ch: char) {
         let last_char = self.grams.chars().last().unwrap_or(' ');
         if last_char == ' ' {
-            self.grams = " ".to_string();
+            // Reuse the existing buffer instead of allocating a new one
+            self.grams.clear();
+            self.grams.push(' ');
             self.capitalword = false;
             if ch == ' ' {
                 return;
             }
         } else if self.grams.chars().count() >= Self::N_GRAM {
-            self.grams = self.grams.chars().skip(1).collect();
+            self.grams.remove(0);
         }
         self.grams.push(ch);
 
@@ -251,6 +447,17 @@ impl NGram {
         }
     }
 
+    /// Packed version of [`NGram::get_str`], see [`NGram::pack`].
+    ///
+    /// # Arguments
+    /// * `n` - The length of n-gram to extract (1-3).
+    ///
+    /// # Returns
+    /// The packed n-gram or None if not available.
+    pub fn get_packed(&self, n: usize) -> Option<u64> {
+        self.get_str(n).and_then(Self::pack)
+    }
+
     /// Extracts an n-gram of the specified length from the current window.
     ///
     /// Returns None if the requested length is invalid or if the current
@@ -262,22 +469,30 @@ impl NGram {
     /// # Returns
     /// The n-gram string or None if not available.
     pub fn get(&self, n: usize) -> Option<String> {
-        if self.capitalword {
+        self.get_str(n).map(str::to_string)
+    }
+
+    /// Borrowing version of [`NGram::get`].
+    ///
+    /// Returns a slice of the internal window, so no allocation happens per n-gram.
+    /// This is what hot loops (n-gram extraction, profile training) should use.
+    ///
+    /// # Arguments
+    /// * `n` - The length of n-gram to extract (1-3).
+    ///
+    /// # Returns
+    /// The n-gram slice or None if not available.
+    pub fn get_str(&self, n: usize) -> Option<&str> {
+        if self.capitalword || !(1..=Self::N_GRAM).contains(&n) {
             return None;
         }
-        if n < 1 || n > Self::N_GRAM || self.grams.chars().count() < n {
+        // Byte offset of the n-th character counting from the end
+        let (start, _) = self.grams.char_indices().rev().nth(n - 1)?;
+        let gram = &self.grams[start..];
+        if n == 1 && gram == " " {
             return None;
         }
-        if n == 1 {
-            let ch = self.grams.chars().last()?;
-            if ch == ' ' {
-                return None;
-            }
-            return Some(ch.to_string());
-        } else {
-            let chars: Vec<char> = self.grams.chars().collect();
-            return Some(chars[chars.len()-n..].iter().collect());
-        }
+        Some(gram)
     }
 
     /// Normalizes a character for n-gram processing.
@@ -316,12 +531,44 @@ impl NGram {
             }
             UNICODE_GENERAL_PUNCTUATION => ' ',
             UNICODE_ARABIC => {
-                if ch == '\u{06CC}' {
-                    '\u{064A}'
+                match ch {
+                    // Yeh and kaf variants
+                    '\u{06CC}' | '\u{0620}' | '\u{063D}'..='\u{063F}' => '\u{064A}',
+                    '\u{06AA}' => '\u{0643}',
+                    '\u{063B}' | '\u{063C}' => '\u{06A9}',
+                    _ => ch,
+                }
+            }
+            // Digits and script punctuation separate words like their ASCII counterparts
+            UNICODE_THAI => {
+                if matches!(ch, '\u{0E3F}' | '\u{0E4F}' | '\u{0E50}'..='\u{0E5B}') {
+                    ' '
+                } else {
+                    ch
+                }
+            }
+            UNICODE_LAO => {
+                if ('\u{0ED0}'..='\u{0ED9}').contains(&ch) {
+                    ' '
+                } else {
+                    ch
+                }
+            }
+            UNICODE_MYANMAR => {
+                if ('\u{1040}'..='\u{104B}').contains(&ch) {
+                    ' '
                 } else {
                     ch
                 }
             }
+            UNICODE_KHMER => {
+                match ch {
+                    // Deprecated duplicate of QAQ
+                    '\u{17A3}' => '\u{17A2}',
+                    '\u{17D4}'..='\u{17D6}' | '\u{17D8}'..='\u{17DB}' | '\u{17E0}'..='\u{17E9}' => ' ',
+                    _ => ch,
+                }
+            }
             UNICODE_LATIN_EXTENDED_ADDITIONAL => {
                 if ch >= '\u{1EA0}' {
                     '\u{1EC3}'
@@ -341,6 +588,33 @@ impl NGram {
     }
 }
 
+/// Returns true for apostrophes and hyphens, which the lenient rules keep between letters.
+fn is_word_joiner(ch: char) -> bool {
+    matches!(ch, '\'' | '-' | '\u{2010}' | '\u{2011}' | '\u{2019}')
+}
+
+/// Returns true for vowel signs, tone marks and other combining signs of Thai, Lao, Khmer and Myanmar.
+fn is_sea_mark(ch: char) -> bool {
+    matches!(
+        ch,
+        '\u{0E31}' | '\u{0E34}'..='\u{0E3A}' | '\u{0E47}'..='\u{0E4E}'
+            | '\u{0EB1}' | '\u{0EB4}'..='\u{0EBC}' | '\u{0EC8}'..='\u{0ECD}'
+            | '\u{17B6}'..='\u{17D3}'
+            | '\u{102B}'..='\u{103E}'
+    )
+}
+
+/// Returns true if a vowel sign follows the tone mark it should precede.
+fn is_misplaced_tone(tone: char, vowel: char) -> bool {
+    match tone {
+        '\u{0E48}'..='\u{0E4B}' => matches!(vowel, '\u{0E31}' | '\u{0E34}'..='\u{0E39}'),
+        '\u{0EC8}'..='\u{0ECB}' => matches!(vowel, '\u{0EB1}' | '\u{0EB4}'..='\u{0EB9}' | '\u{0EBB}'),
+        // Myanmar dot below precedes asat
+        '\u{103A}' => vowel == '\u{1037}',
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -457,6 +731,32 @@ mod tests {
         assert_eq!(ngram.get(3), None);
     }
 
+    #[test]
+    fn test_get_str_multibyte() {
+        let mut ngram = NGram::new();
+        ngram.add_char('\u{044F}');
+        assert_eq!(ngram.get_str(1), Some("\u{044F}"));
+        assert_eq!(ngram.get_str(2), Some(" \u{044F}"));
+        // Only two characters in the window even though it is longer than 3 bytes
+        assert_eq!(ngram.get_str(3), None);
+        ngram.add_char('\u{0431}');
+        assert_eq!(ngram.get_str(3), Some(" \u{044F}\u{0431}"));
+        ngram.add_char('\u{0432}');
+        assert_eq!(ngram.get_str(3), Some("\u{044F}\u{0431}\u{0432}"));
+        assert_eq!(ngram.get_packed(3), NGram::pack("\u{044F}\u{0431}\u{0432}"));
+    }
+
+    #[test]
+    fn test_pack() {
+        for word in ["", "a", " a", "a ", "\u{10FFFF}\u{0}x", "\u{3042}\u{3044}"] {
+            let key = NGram::pack(word).unwrap();
+            assert_eq!(NGram::unpack(key).collect::<String>(), word);
+        }
+        assert_ne!(NGram::pack("\u{0}"), NGram::pack(""));
+        assert_ne!(NGram::pack("a"), NGram::pack("\u{0}a"));
+        assert_eq!(NGram::pack("abcd"), None);
+    }
+
     #[test]
     fn test_ngram3() {
         let mut ngram = NGram::new();
@@ -474,12 +774,73 @@ mod tests {
         assert_eq!(ngram.get(3), None);
     }
 
+    #[test]
+    fn test_lenient_normalization() {
+        let bigrams = |text: &str, normalization| {
+            let mut ngram = NGram::with_normalization(normalization);
+            let mut grams = Vec::new();
+            for ch in NGram::normalize_lenient(text).chars().chain([' ']) {
+                ngram.add_char(ch);
+                grams.extend(ngram.get(2));
+            }
+            grams
+        };
+        assert_eq!(bigrams("d'h mp3 4u", Normalization::Strict), [" d", "d ", " h", "h ", " m", "mp", "p ", " u", "u "]);
+        assert_eq!(bigrams("d'h mp3 4u", Normalization::Lenient), [" d", "d'", "'h", "h ", " m", "mp", "p3", "3 ", " 4", "4u", "u "]);
+        // Only between letters, and numbers stay word boundaries
+        assert_eq!(bigrams("a- 'b 2024", Normalization::Lenient), [" a", "a ", " b", "b "]);
+        assert_eq!(bigrams("a--b", Normalization::Lenient), [" a", "a ", " b", "b "]);
+        assert_eq!(bigrams("x\u{2019}y", Normalization::Lenient), [" x", "x\u{2019}", "\u{2019}y", "y "]);
+        assert!(matches!(NGram::normalize_lenient("e-mail"), Cow::Borrowed(_)));
+        assert_eq!(NGram::normalize_lenient("'90s -a 1-a"), " 90s  a   a");
+    }
+
+    #[test]
+    fn test_normalize_arabic_variants() {
+        for yeh in ['\u{064A}', '\u{06CC}', '\u{0620}', '\u{063D}', '\u{063F}'] {
+            assert_eq!(NGram::normalize(yeh), '\u{064A}');
+        }
+        assert_eq!(NGram::normalize('\u{06AA}'), '\u{0643}');
+        assert_eq!(NGram::normalize('\u{063B}'), '\u{06A9}');
+        assert_eq!(NGram::normalize('\u{06A9}'), '\u{06A9}');
+        assert_eq!(NGram::normalize('\u{0643}'), '\u{0643}');
+    }
+
+    #[test]
+    fn test_normalize_southeast_asian() {
+        assert!(matches!(NGram::normalize_sea("abc"), Cow::Borrowed(_)));
+        assert!(matches!(NGram::normalize_sea("\u{0E01}\u{0E34}\u{0E48}"), Cow::Borrowed(_)));
+        // Tone mark before the upper vowel, once or repeated
+        assert_eq!(NGram::normalize_sea("\u{0E01}\u{0E48}\u{0E34}"), "\u{0E01}\u{0E34}\u{0E48}");
+        assert_eq!(NGram::normalize_sea("\u{0E01}\u{0E34}\u{0E48}\u{0E34}"), "\u{0E01}\u{0E34}\u{0E48}");
+        assert_eq!(NGram::normalize_sea("\u{0E01}\u{0E49}\u{0E49}"), "\u{0E01}\u{0E49}");
+        // Decomposed sara am and doubled sara e
+        assert_eq!(NGram::normalize_sea("\u{0E19}\u{0E4D}\u{0E32}"), "\u{0E19}\u{0E33}");
+        assert_eq!(NGram::normalize_sea("\u{0E40}\u{0E40}\u{0E21}\u{0E27}"), "\u{0E41}\u{0E21}\u{0E27}");
+        // Lao
+        assert_eq!(NGram::normalize_sea("\u{0E81}\u{0EC8}\u{0EB4}"), "\u{0E81}\u{0EB4}\u{0EC8}");
+        assert_eq!(NGram::normalize_sea("\u{0E99}\u{0ECD}\u{0EB2}"), "\u{0E99}\u{0EB3}");
+        // Khmer inherent vowels, Myanmar asat and dot below
+        assert_eq!(NGram::normalize_sea("\u{1780}\u{17B4}\u{17B6}"), "\u{1780}\u{17B6}");
+        assert_eq!(NGram::normalize_sea("\u{1000}\u{103A}\u{1037}"), "\u{1000}\u{1037}\u{103A}");
+
+        assert_eq!(NGram::normalize('\u{0E51}'), ' ');
+        assert_eq!(NGram::normalize('\u{0E01}'), '\u{0E01}');
+        assert_eq!(NGram::normalize('\u{0ED5}'), ' ');
+        assert_eq!(NGram::normalize('\u{17E1}'), ' ');
+        assert_eq!(NGram::normalize('\u{17D4}'), ' ');
+        assert_eq!(NGram::normalize('\u{17A3}'), '\u{17A2}');
+        assert_eq!(NGram::normalize('\u{104A}'), ' ');
+        assert_eq!(NGram::normalize('\u{1000}'), '\u{1000}');
+    }
+
     #[test]
     fn test_normalize_vietnamese() {
         assert_eq!(NGram::normalize_vi(""), "");
         assert_eq!(NGram::normalize_vi("ABC"), "ABC");
         assert_eq!(NGram::normalize_vi("012"), "012");
         assert_eq!(NGram::normalize_vi("\u{00C0}"), "\u{00C0}");
+        assert!(matches!(NGram::normalize_vi("Vi\u{1EC7}t"), Cow::Borrowed(_)));
 
         // All combinations
         let bases = messages::get_string("TO_NORMALIZE_VI_CHARS");
@@ -0,0 +1,184 @@
+//! Parser for Java-style `.properties` files.
+//!
+//! Shared between the build script, which compiles `messages.properties` into the
+//! crate, and the unit tests.
+
+use std::collections::HashMap;
+
+/// Parse the content of a Java-style properties file.
+///
+/// Supports `#`/`!` comments, line continuations (trailing backslash),
+/// `=`, `:` or whitespace as key/value separators and escaped characters
+/// (including separators) in both keys and values.
+pub fn parse_properties(content: &str) -> HashMap<String, String> {
+	let mut messages = HashMap::new();
+	let mut lines = content.lines();
+	while let Some(line) = lines.next() {
+		let line = line.trim_start();
+		if line.is_empty() || line.starts_with('#') || line.starts_with('!') {
+			continue;
+		}
+		// Join continuation lines into a single logical line
+		let mut logical = line.to_string();
+		while has_continuation(&logical) {
+			logical.pop();
+			match lines.next() {
+				Some(next) => logical.push_str(next.trim_start()),
+				None => break,
+			}
+		}
+		let (key, value) = split_key_value(&logical);
+		messages.insert(parse_unicode_escapes(key), parse_unicode_escapes(value));
+	}
+	messages
+}
+
+/// Checks whether a line ends with an odd number of backslashes, i.e. continues on the next line.
+fn has_continuation(line: &str) -> bool {
+	line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+/// Splits a logical line into raw (still escaped) key and value parts.
+fn split_key_value(line: &str) -> (&str, &str) {
+	let mut escaped = false;
+	let mut key_end = line.len();
+	for (i, c) in line.char_indices() {
+		if escaped {
+			escaped = false;
+		} else if c == '\\' {
+			escaped = true;
+		} else if c == '=' || c == ':' || c.is_whitespace() {
+			key_end = i;
+			break;
+		}
+	}
+	let key = &line[..key_end];
+	let mut rest = line[key_end..].trim_start();
+	if rest.starts_with('=') || rest.starts_with(':') {
+		rest = rest[1..].trim_start();
+	}
+	(key, rest)
+}
+
+/// Parse escape sequences (e.g., \u00A0, \t, \=) in property keys and values.
+///
+/// Supplementary characters may be written either as a UTF-16 surrogate pair
+/// (e.g., \uD83D\uDE00) or as a single `\UXXXXXXXX` escape. Unpaired surrogates
+/// are replaced with U+FFFD, malformed Unicode escapes are kept as-is.
+/// Any other escaped character stands for itself.
+fn parse_unicode_escapes(input: &str) -> String {
+	let mut result = String::new();
+	let mut chars = input.chars().peekable();
+	while let Some(c) = chars.next() {
+		if c != '\\' {
+			result.push(c);
+			continue;
+		}
+		let marker = match chars.next() {
+			Some(marker) => marker,
+			None => break,
+		};
+		match marker {
+			't' => result.push('\t'),
+			'n' => result.push('\n'),
+			'r' => result.push('\r'),
+			'f' => result.push('\u{000C}'),
+			'u' | 'U' => {
+				let width = if marker == 'u' { 4 } else { 8 };
+				let hex: String = chars.by_ref().take(width).collect();
+				let code = match u32::from_str_radix(&hex, 16) {
+					Ok(code) if hex.len() == width => code,
+					_ => {
+						result.push(c);
+						result.push(marker);
+						result.push_str(&hex);
+						continue;
+					}
+				};
+				if (0xD800..=0xDBFF).contains(&code) {
+					// High surrogate: combine with a following \uXXXX low surrogate if present
+					let mut lookahead = chars.clone();
+					if lookahead.next() == Some('\\') && lookahead.next() == Some('u') {
+						let low_hex: String = lookahead.by_ref().take(4).collect();
+						if let Ok(low) = u32::from_str_radix(&low_hex, 16)
+							&& low_hex.len() == 4
+							&& (0xDC00..=0xDFFF).contains(&low)
+						{
+							let combined = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+							result.push(std::char::from_u32(combined).unwrap_or(char::REPLACEMENT_CHARACTER));
+							chars = lookahead;
+							continue;
+						}
+					}
+				}
+				result.push(std::char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+			}
+			other => result.push(other),
+		}
+	}
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_parse_unicode_escapes() {
+		assert_eq!(parse_unicode_escapes("\\u0041\\u00A0"), "A\u{00A0}");
+		assert_eq!(parse_unicode_escapes("plain"), "plain");
+	}
+
+	#[test]
+	fn test_parse_surrogate_pairs() {
+		assert_eq!(parse_unicode_escapes("\\uD83D\\uDE00"), "\u{1F600}");
+		assert_eq!(parse_unicode_escapes("\\U0001F600"), "\u{1F600}");
+		assert_eq!(parse_unicode_escapes("\\uD840\\uDC00x"), "\u{20000}x");
+		// Unpaired surrogates can not be represented
+		assert_eq!(parse_unicode_escapes("\\uD83Dx"), "\u{FFFD}x");
+		assert_eq!(parse_unicode_escapes("\\uDE00"), "\u{FFFD}");
+	}
+
+	#[test]
+	fn test_parse_malformed_escapes() {
+		assert_eq!(parse_unicode_escapes("\\u00"), "\\u00");
+		assert_eq!(parse_unicode_escapes("\\uZZZZ"), "\\uZZZZ");
+	}
+
+	#[test]
+	fn test_parse_escaped_characters() {
+		assert_eq!(parse_unicode_escapes("a\\tb\\nc"), "a\tb\nc");
+		assert_eq!(parse_unicode_escapes("a\\=b\\:c\\\\d"), "a=b:c\\d");
+	}
+
+	#[test]
+	fn test_parse_properties() {
+		let content = "# comment\n! another comment\n\nkey1=value1\nkey2 : value2\nkey3 value3\n  key4=\\u0041\n";
+		let messages = parse_properties(content);
+		assert_eq!(messages.len(), 4);
+		assert_eq!(messages["key1"], "value1");
+		assert_eq!(messages["key2"], "value2");
+		assert_eq!(messages["key3"], "value3");
+		assert_eq!(messages["key4"], "A");
+	}
+
+	#[test]
+	fn test_parse_properties_continuations() {
+		let content = "key=\\u0041\\\n    \\u0042\\\n\tC\neven=x\\\\\nnext=1\n";
+		let messages = parse_properties(content);
+		assert_eq!(messages["key"], "ABC");
+		// Even number of trailing backslashes is not a continuation
+		assert_eq!(messages["even"], "x\\");
+		assert_eq!(messages["next"], "1");
+	}
+
+	#[test]
+	fn test_parse_properties_escaped_separators() {
+		let content = "a\\=b=c\nx\\:y:z\nwith\\ space=1\nempty=\n";
+		let messages = parse_properties(content);
+		assert_eq!(messages["a=b"], "c");
+		assert_eq!(messages["x:y"], "z");
+		assert_eq!(messages["with space"], "1");
+		assert_eq!(messages["empty"], "");
+	}
+}
@@ -0,0 +1,353 @@
+use serde::{Deserialize, Serialize};
+
+use crate::utils::unicode_block::*;
+
+/// Writing system of a character, derived from its Unicode block.
+///
+/// Serialized by name (e.g. `"Cyrillic"`), as in the `scripts` field of JSON profiles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Armenian,
+    Georgian,
+    Hebrew,
+    Arabic,
+    Thaana,
+    Devanagari,
+    Bengali,
+    Gurmukhi,
+    Gujarati,
+    Oriya,
+    Tamil,
+    Telugu,
+    Kannada,
+    Malayalam,
+    Sinhala,
+    Thai,
+    Lao,
+    Tibetan,
+    Myanmar,
+    Khmer,
+    Ethiopic,
+    Hangul,
+    Hiragana,
+    Katakana,
+    Han,
+}
+
+impl Script {
+    /// Returns the script of a letter, or None for digits, punctuation, symbols and unknown blocks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::utils::script::Script;
+    ///
+    /// assert_eq!(Script::of('ж'), Some(Script::Cyrillic));
+    /// assert_eq!(Script::of('7'), None);
+    /// ```
+    pub fn of(ch: char) -> Option<Script> {
+        if !ch.is_alphabetic() {
+            return None;
+        }
+        let script = match unicode_block(ch)? {
+            UNICODE_BASIC_LATIN | UNICODE_LATIN_1_SUPPLEMENT | UNICODE_LATIN_EXTENDED_A | UNICODE_LATIN_EXTENDED_B
+            | UNICODE_IPA_EXTENSIONS | UNICODE_LATIN_EXTENDED_ADDITIONAL | UNICODE_LATIN_EXTENDED_C
+            | UNICODE_LATIN_EXTENDED_D => Script::Latin,
+            UNICODE_GREEK_AND_COPTIC | UNICODE_GREEK_EXTENDED => Script::Greek,
+            UNICODE_CYRILLIC | UNICODE_CYRILLIC_SUPPLEMENT | UNICODE_CYRILLIC_EXTENDED_A
+            | UNICODE_CYRILLIC_EXTENDED_B => Script::Cyrillic,
+            UNICODE_ARMENIAN => Script::Armenian,
+            UNICODE_GEORGIAN | UNICODE_GEORGIAN_SUPPLEMENT => Script::Georgian,
+            UNICODE_HEBREW => Script::Hebrew,
+            UNICODE_ARABIC | UNICODE_ARABIC_SUPPLEMENT | UNICODE_ARABIC_EXTENDED_A | UNICODE_ARABIC_PRESENTATION_FORMS_A
+            | UNICODE_ARABIC_PRESENTATION_FORMS_B => Script::Arabic,
+            UNICODE_THAANA => Script::Thaana,
+            UNICODE_DEVANAGARI | UNICODE_DEVANAGARI_EXTENDED => Script::Devanagari,
+            UNICODE_BENGALI => Script::Bengali,
+            UNICODE_GURMUKHI => Script::Gurmukhi,
+            UNICODE_GUJARATI => Script::Gujarati,
+            UNICODE_ORIYA => Script::Oriya,
+            UNICODE_TAMIL => Script::Tamil,
+            UNICODE_TELUGU => Script::Telugu,
+            UNICODE_KANNADA => Script::Kannada,
+            UNICODE_MALAYALAM => Script::Malayalam,
+            UNICODE_SINHALA => Script::Sinhala,
+            UNICODE_THAI => Script::Thai,
+            UNICODE_LAO => Script::Lao,
+            UNICODE_TIBETAN => Script::Tibetan,
+            UNICODE_MYANMAR | UNICODE_MYANMAR_EXTENDED_A => Script::Myanmar,
+            UNICODE_KHMER => Script::Khmer,
+            UNICODE_ETHIOPIC | UNICODE_ETHIOPIC_SUPPLEMENT | UNICODE_ETHIOPIC_EXTENDED
+            | UNICODE_ETHIOPIC_EXTENDED_A => Script::Ethiopic,
+            UNICODE_HANGUL_JAMO | UNICODE_HANGUL_COMPATIBILITY_JAMO | UNICODE_HANGUL_JAMO_EXTENDED_A
+            | UNICODE_HANGUL_JAMO_EXTENDED_B | UNICODE_HANGUL_SYLLABLES => Script::Hangul,
+            UNICODE_HIRAGANA => Script::Hiragana,
+            UNICODE_KATAKANA | UNICODE_KATAKANA_PHONETIC_EXTENSIONS => Script::Katakana,
+            UNICODE_CJK_UNIFIED_IDEOGRAPHS | UNICODE_CJK_UNIFIED_IDEOGRAPHS_EXTENSION_A
+            | UNICODE_CJK_UNIFIED_IDEOGRAPHS_EXTENSION_B | UNICODE_CJK_UNIFIED_IDEOGRAPHS_EXTENSION_C
+            | UNICODE_CJK_UNIFIED_IDEOGRAPHS_EXTENSION_D | UNICODE_CJK_COMPATIBILITY_IDEOGRAPHS
+            | UNICODE_CJK_COMPATIBILITY_IDEOGRAPHS_SUPPLEMENT => Script::Han,
+            _ => return None,
+        };
+        Some(script)
+    }
+
+    /// Human-readable name of the script.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Script::Latin => "Latin",
+            Script::Greek => "Greek",
+            Script::Cyrillic => "Cyrillic",
+            Script::Armenian => "Armenian",
+            Script::Georgian => "Georgian",
+            Script::Hebrew => "Hebrew",
+            Script::Arabic => "Arabic",
+            Script::Thaana => "Thaana",
+            Script::Devanagari => "Devanagari",
+            Script::Bengali => "Bengali",
+            Script::Gurmukhi => "Gurmukhi",
+            Script::Gujarati => "Gujarati",
+            Script::Oriya => "Oriya",
+            Script::Tamil => "Tamil",
+            Script::Telugu => "Telugu",
+            Script::Kannada => "Kannada",
+            Script::Malayalam => "Malayalam",
+            Script::Sinhala => "Sinhala",
+            Script::Thai => "Thai",
+            Script::Lao => "Lao",
+            Script::Tibetan => "Tibetan",
+            Script::Myanmar => "Myanmar",
+            Script::Khmer => "Khmer",
+            Script::Ethiopic => "Ethiopic",
+            Script::Hangul => "Hangul",
+            Script::Hiragana => "Hiragana",
+            Script::Katakana => "Katakana",
+            Script::Han => "Han",
+        }
+    }
+
+    /// Four-letter ISO 15924 code of the script, e.g. "Cyrl".
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use langdetect_rs::utils::script::Script;
+    ///
+    /// assert_eq!(Script::Devanagari.iso15924(), "Deva");
+    /// ```
+    pub fn iso15924(&self) -> &'static str {
+        match self {
+            Script::Latin => "Latn",
+            Script::Greek => "Grek",
+            Script::Cyrillic => "Cyrl",
+            Script::Armenian => "Armn",
+            Script::Georgian => "Geor",
+            Script::Hebrew => "Hebr",
+            Script::Arabic => "Arab",
+            Script::Thaana => "Thaa",
+            Script::Devanagari => "Deva",
+            Script::Bengali => "Beng",
+            Script::Gurmukhi => "Guru",
+            Script::Gujarati => "Gujr",
+            Script::Oriya => "Orya",
+            Script::Tamil => "Taml",
+            Script::Telugu => "Telu",
+            Script::Kannada => "Knda",
+            Script::Malayalam => "Mlym",
+            Script::Sinhala => "Sinh",
+            Script::Thai => "Thai",
+            Script::Lao => "Laoo",
+            Script::Tibetan => "Tibt",
+            Script::Myanmar => "Mymr",
+            Script::Khmer => "Khmr",
+            Script::Ethiopic => "Ethi",
+            Script::Hangul => "Hang",
+            Script::Hiragana => "Hira",
+            Script::Katakana => "Kana",
+            Script::Han => "Hani",
+        }
+    }
+
+    /// Returns the script of a four-letter ISO 15924 code, case-insensitive.
+    ///
+    /// # Arguments
+    /// * `code` - The code, e.g. "Latn".
+    ///
+    /// # Returns
+    /// The script, or None for codes of scripts not covered by `Script`.
+    pub fn from_iso15924(code: &str) -> Option<Script> {
+        let script = match code.to_ascii_lowercase().as_str() {
+            "latn" => Script::Latin,
+            "grek" => Script::Greek,
+            "cyrl" => Script::Cyrillic,
+            "armn" => Script::Armenian,
+            "geor" => Script::Georgian,
+            "hebr" => Script::Hebrew,
+            "arab" => Script::Arabic,
+            "thaa" => Script::Thaana,
+            "deva" => Script::Devanagari,
+            "beng" => Script::Bengali,
+            "guru" => Script::Gurmukhi,
+            "gujr" => Script::Gujarati,
+            "orya" => Script::Oriya,
+            "taml" => Script::Tamil,
+            "telu" => Script::Telugu,
+            "knda" => Script::Kannada,
+            "mlym" => Script::Malayalam,
+            "sinh" => Script::Sinhala,
+            "thai" => Script::Thai,
+            "laoo" => Script::Lao,
+            "tibt" => Script::Tibetan,
+            "mymr" => Script::Myanmar,
+            "khmr" => Script::Khmer,
+            "ethi" => Script::Ethiopic,
+            "hang" => Script::Hangul,
+            "hira" => Script::Hiragana,
+            "kana" => Script::Katakana,
+            "hani" => Script::Han,
+            _ => return None,
+        };
+        Some(script)
+    }
+}
+
+impl std::fmt::Display for Script {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Returns the script most letters of a text are written in.
+///
+/// # Arguments
+/// * `text` - The text to classify.
+///
+/// # Returns
+/// The most frequent script, or None if the text contains no letters of a known script.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::utils::script::{dominant_script, Script};
+///
+/// assert_eq!(dominant_script("Привет, world!"), Some(Script::Cyrillic));
+/// assert_eq!(dominant_script("123 !?"), None);
+/// ```
+pub fn dominant_script(text: &str) -> Option<Script> {
+    let mut counts: Vec<(Script, usize)> = Vec::new();
+    for script in text.chars().filter_map(Script::of) {
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+    // Ties are resolved in favour of the script seen first
+    counts.into_iter().rev().max_by_key(|(_, count)| *count).map(|(script, _)| script)
+}
+
+/// Returns the share of the letters of a text written in every script.
+///
+/// # Arguments
+/// * `text` - The text to classify.
+///
+/// # Returns
+/// The scripts with their share of the letters of a known script, largest share first
+/// (ties in order of appearance). Empty if the text contains no such letters.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::utils::script::{script_shares, Script};
+///
+/// assert_eq!(script_shares("abc жз"), vec![(Script::Latin, 0.6), (Script::Cyrillic, 0.4)]);
+/// ```
+pub fn script_shares(text: &str) -> Vec<(Script, f64)> {
+    let mut counts: Vec<(Script, usize)> = Vec::new();
+    for script in text.chars().filter_map(Script::of) {
+        match counts.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((script, 1)),
+        }
+    }
+    let total: usize = counts.iter().map(|(_, count)| count).sum();
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts.into_iter().map(|(script, count)| (script, count as f64 / total as f64)).collect()
+}
+
+/// Returns the scripts holding a minimum share of weighted letters.
+///
+/// Used to derive the scripts of a language from the probabilities (or counts) of its
+/// unigrams, so a few foreign letters do not count as a script of the language.
+///
+/// # Arguments
+/// * `weights` - Characters with their weight; characters without a known script are ignored.
+/// * `min_share` - Minimum share of the total weight of letters with a known script.
+///
+/// # Returns
+/// The scripts in declaration order of `Script`.
+///
+/// # Examples
+///
+/// ```rust
+/// use langdetect_rs::utils::script::{scripts_by_share, Script};
+///
+/// let weights = [('д', 60.0), ('а', 35.0), ('x', 5.0), ('7', 100.0)];
+/// assert_eq!(scripts_by_share(weights, 0.1), vec![Script::Cyrillic]);
+/// ```
+pub fn scripts_by_share(weights: impl IntoIterator<Item = (char, f64)>, min_share: f64) -> Vec<Script> {
+    let mut shares: Vec<(Script, f64)> = Vec::new();
+    for (script, weight) in weights.into_iter().filter_map(|(ch, weight)| Script::of(ch).map(|script| (script, weight))) {
+        match shares.iter_mut().find(|(s, _)| *s == script) {
+            Some((_, share)) => *share += weight,
+            None => shares.push((script, weight)),
+        }
+    }
+    let total: f64 = shares.iter().map(|(_, share)| share).sum();
+    let mut scripts: Vec<Script> = shares.into_iter()
+        .filter(|(_, share)| *share > 0.0 && *share >= total * min_share)
+        .map(|(script, _)| script)
+        .collect();
+    scripts.sort();
+    scripts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_of() {
+        assert_eq!(Script::of('a'), Some(Script::Latin));
+        assert_eq!(Script::of('ệ'), Some(Script::Latin));
+        assert_eq!(Script::of('α'), Some(Script::Greek));
+        assert_eq!(Script::of('ب'), Some(Script::Arabic));
+        assert_eq!(Script::of('क'), Some(Script::Devanagari));
+        assert_eq!(Script::of('한'), Some(Script::Hangul));
+        assert_eq!(Script::of('あ'), Some(Script::Hiragana));
+        assert_eq!(Script::of('中'), Some(Script::Han));
+        assert_eq!(Script::of(' '), None);
+        assert_eq!(Script::of('!'), None);
+    }
+
+    #[test]
+    fn test_dominant_script() {
+        assert_eq!(dominant_script("これは日本語です"), Some(Script::Hiragana));
+        // Ties go to the script seen first
+        assert_eq!(dominant_script("ab жз"), Some(Script::Latin));
+        assert_eq!(dominant_script(""), None);
+    }
+
+    #[test]
+    fn test_iso15924() {
+        for script in [Script::Latin, Script::Cyrillic, Script::Lao, Script::Han] {
+            assert_eq!(Script::from_iso15924(script.iso15924()), Some(script));
+        }
+        assert_eq!(Script::from_iso15924("HANG"), Some(Script::Hangul));
+        assert_eq!(Script::from_iso15924("Zyyy"), None);
+        assert_eq!(script_shares("中文 ひらがな"), vec![(Script::Hiragana, 4.0 / 6.0), (Script::Han, 2.0 / 6.0)]);
+        assert!(script_shares("123").is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+//! JavaScript API for browsers, enabled by the `wasm` feature.
+//!
+//! Build with `wasm-pack build --target web --features wasm`. The built-in profiles are
+//! compiled into the module, so no filesystem access is needed.
+//!
+//! ```js
+//! import init, { detect, getProbabilities } from "./pkg/langdetect_rs.js";
+//!
+//! await init();
+//! detect("Bonjour tout le monde !");           // { lang: "fr", prob: 0.99... }
+//! getProbabilities("Bonjour tout le monde !"); // [{ lang: "fr", prob: 0.99... }]
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+use crate::detector_factory::DetectorFactory;
+use crate::language::Language;
+
+/// Detects the language of a text with the built-in profiles.
+///
+/// # Arguments
+/// * `text` - The text to analyze.
+///
+/// # Returns
+/// An object `{ lang, prob }`; `lang` is `null` if no language could be detected.
+#[wasm_bindgen]
+pub fn detect(text: &str) -> JsValue {
+    let language = DetectorFactory::default_shared()
+        .detect_lang(text, None)
+        .unwrap_or_else(|_| Language::new(None, 0.0));
+    to_js(&language)
+}
+
+/// Computes the probabilities of the built-in languages for a text.
+///
+/// # Arguments
+/// * `text` - The text to analyze.
+///
+/// # Returns
+/// An array of `{ lang, prob }` objects sorted by probability descending, empty if the
+/// text has no features of any language.
+#[wasm_bindgen(js_name = getProbabilities)]
+pub fn get_probabilities(text: &str) -> JsValue {
+    to_js(&DetectorFactory::default_shared().get_probabilities(text, None).unwrap_or_default())
+}
+
+/// Converts a result to a plain JavaScript value.
+fn to_js<T: serde::Serialize>(value: &T) -> JsValue {
+    serde_wasm_bindgen::to_value(value).unwrap_or(JsValue::NULL)
+}